@@ -0,0 +1,111 @@
+//! Parses the definitions of monolingual and other additional dictionaries distributed as Yomichan dictionaries.
+
+use crate::yomichan::{Archive, Term};
+use serde_json::Value;
+use std::io::{Read, Seek};
+
+pub struct Dictionary {
+    pub name: String,
+    pub entries: Vec<DictionaryEntry>,
+}
+
+/// A term's definitions in the dictionary. A term can have several entries, such as one for each part of speech.
+pub struct DictionaryEntry {
+    pub word: String,
+    /// Not set for terms written in kana only.
+    pub reading: Option<String>,
+    /// The definitions as plain text, with line breaks where the dictionary has them.
+    pub definitions: Vec<String>,
+}
+
+impl Dictionary {
+    /// Reads the entries from the term banks of a Yomichan dictionary.
+    /// The dictionary is named after its title.
+    pub fn from_yomichan<R: Read + Seek>(archive: &mut Archive<R>) -> eyre::Result<Self> {
+        let entries = archive
+            .terms()?
+            .into_iter()
+            .filter_map(|Term(term, reading, _, _, _, glossary, _, _)| {
+                let definitions = glossary
+                    .iter()
+                    .filter_map(definition_text)
+                    .filter(|definition| !definition.is_empty())
+                    .collect::<Vec<_>>();
+                if definitions.is_empty() {
+                    return None;
+                }
+                Some(DictionaryEntry {
+                    word: term,
+                    reading: Some(reading).filter(|r| !r.is_empty()),
+                    definitions,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            name: archive.index.title.clone(),
+            entries,
+        })
+    }
+}
+
+// a definition is either a string, a text or structured content object, an image or a deinflection array,
+// the last two have no text to show
+fn definition_text(definition: &Value) -> Option<String> {
+    match definition {
+        Value::String(text) => Some(text.trim().to_string()),
+        Value::Object(object) => match object.get("type").and_then(Value::as_str) {
+            Some("text") => object
+                .get("text")
+                .and_then(Value::as_str)
+                .map(|text| text.trim().to_string()),
+            Some("structured-content") => {
+                let mut text = String::new();
+                structured_content_text(object.get("content")?, &mut text);
+                let lines = text
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>();
+                Some(lines.join("\n"))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// structured content is a tree of HTML-like elements, the text is collected with line breaks after block elements
+fn structured_content_text(content: &Value, text: &mut String) {
+    match content {
+        Value::String(string) => text.push_str(string),
+        Value::Array(array) => {
+            for content in array {
+                structured_content_text(content, text);
+            }
+        }
+        Value::Object(object) => {
+            let tag = object
+                .get("tag")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            match tag {
+                "br" => text.push('\n'),
+                // furigana would be mixed into the text
+                "rt" | "rp" | "img" => {}
+                _ => {
+                    if let Some(content) = object.get("content") {
+                        structured_content_text(content, text);
+                    }
+                    if matches!(
+                        tag,
+                        "div" | "li" | "ol" | "ul" | "p" | "tr" | "table" | "details"
+                    ) {
+                        text.push('\n');
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
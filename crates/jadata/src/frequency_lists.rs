@@ -0,0 +1,195 @@
+//! Parses word frequency lists distributed as Yomichan frequency dictionaries or TSV files.
+
+use crate::yomichan::{Archive, TermMeta};
+use eyre::Context;
+use serde_json::Value;
+use std::io::{Read, Seek};
+
+pub struct FrequencyList {
+    pub name: String,
+    pub entries: Vec<FrequencyEntry>,
+}
+
+/// The rank of a word in the list, the most common word has the lowest rank.
+pub struct FrequencyEntry {
+    pub word: String,
+    /// Some lists don't differentiate between readings, in which case the rank applies to all of them.
+    pub reading: Option<String>,
+    pub rank: u32,
+}
+
+impl FrequencyList {
+    /// Parses a TSV file formatted as {word}\t{reading}\t{rank} or {word}\t{rank}.
+    /// Lists that only contain the words in order are supported as well, in which case the rank is the line number.
+    pub fn from_tsv<R: Read>(name: String, mut r: R) -> eyre::Result<Self> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+
+        let mut entries = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.split('\t').map(str::trim).collect::<Vec<_>>();
+            let (word, reading, rank) = match fields.as_slice() {
+                [word] => (*word, None, None),
+                [word, rank] if rank.parse::<u32>().is_ok() => (*word, None, Some(*rank)),
+                [word, reading] => (*word, Some(*reading), None),
+                [word, reading, rank, ..] => (*word, Some(*reading), Some(*rank)),
+                [] => unreachable!("split always returns at least one field"),
+            };
+            let rank = match rank {
+                Some(rank) => rank
+                    .parse::<u32>()
+                    .wrap_err_with(|| format!("Invalid rank '{rank}' on line {}", idx + 1))?,
+                None => entries.len() as u32 + 1,
+            };
+            entries.push(FrequencyEntry {
+                word: word.to_string(),
+                reading: reading.filter(|r| !r.is_empty()).map(str::to_string),
+                rank,
+            });
+        }
+
+        Ok(Self { name, entries })
+    }
+
+    /// Reads the `freq` entries from the term meta banks of a Yomichan frequency dictionary.
+    /// The list is named after the dictionary's title.
+    pub fn from_yomichan<R: Read + Seek>(archive: &mut Archive<R>) -> eyre::Result<Self> {
+        let entries = archive
+            .term_meta()?
+            .into_iter()
+            .filter_map(|TermMeta(term, mode, data)| {
+                if mode != "freq" {
+                    return None;
+                }
+                let (reading, rank) = match &data {
+                    // {"reading": "...", "frequency": ...}
+                    Value::Object(object) if object.contains_key("reading") => (
+                        object
+                            .get("reading")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        object.get("frequency").and_then(yomichan_frequency),
+                    ),
+                    _ => (None, yomichan_frequency(&data)),
+                };
+                Some(FrequencyEntry {
+                    word: term,
+                    reading,
+                    rank: rank?,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            name: archive.index.title.clone(),
+            entries,
+        })
+    }
+}
+
+// the frequency can be a number, a string starting with a number or an object with the number as its value
+// some dictionaries store whole numbers as floats such as 1234.0, which are rounded
+fn yomichan_frequency(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(number) => number
+            .as_f64()
+            .filter(|n| *n >= 0.0)
+            .map(|n| n.round() as u32),
+        Value::String(string) => {
+            let digits = string
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>();
+            digits.parse().ok()
+        }
+        Value::Object(object) => object.get("value").and_then(yomichan_frequency),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::io::{Cursor, Write};
+
+    #[test]
+    fn parses_tsv() {
+        let tsv = "# comment\n\
+                   一\tいち\t5\n\
+                   二\t7\n\
+                   三\tさん\n\
+                   \n\
+                   四\n\
+                   五\t\t9\textra\n";
+        let list = FrequencyList::from_tsv("list".to_string(), tsv.as_bytes()).unwrap();
+        let entries = list
+            .entries
+            .iter()
+            .map(|e| (e.word.as_str(), e.reading.as_deref(), e.rank))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                ("一", Some("いち"), 5),
+                ("二", None, 7),
+                ("三", Some("さん"), 3),
+                ("四", None, 4),
+                ("五", None, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_tsv_rank() {
+        let tsv = "一\tいち\tfirst\n";
+        assert!(FrequencyList::from_tsv("list".to_string(), tsv.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn parses_yomichan_frequencies() {
+        assert_eq!(yomichan_frequency(&json!(12)), Some(12));
+        assert_eq!(yomichan_frequency(&json!(1234.0)), Some(1234));
+        assert_eq!(yomichan_frequency(&json!(2.6)), Some(3));
+        assert_eq!(yomichan_frequency(&json!(-1.0)), None);
+        assert_eq!(yomichan_frequency(&json!("56㋕")), Some(56));
+        assert_eq!(yomichan_frequency(&json!({"value": 78.0})), Some(78));
+        assert_eq!(yomichan_frequency(&json!("none")), None);
+        assert_eq!(yomichan_frequency(&json!(null)), None);
+    }
+
+    #[test]
+    fn parses_yomichan_archive() {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("index.json", options).unwrap();
+        writer
+            .write_all(br#"{"title": "Frequencies", "revision": "1"}"#)
+            .unwrap();
+        writer.start_file("term_meta_bank_1.json", options).unwrap();
+        let bank = json!([
+            ["一", "freq", 1],
+            ["二", "freq", {"reading": "に", "frequency": {"value": 2.0, "displayValue": "2"}}],
+            ["三", "freq", "3"],
+            ["四", "pitch", {"reading": "よん", "pitches": []}],
+        ]);
+        writer.write_all(bank.to_string().as_bytes()).unwrap();
+        let zip = writer.finish().unwrap();
+
+        let mut archive = Archive::open(Cursor::new(zip.into_inner())).unwrap();
+        let list = FrequencyList::from_yomichan(&mut archive).unwrap();
+        assert_eq!(list.name, "Frequencies");
+        let entries = list
+            .entries
+            .iter()
+            .map(|e| (e.word.as_str(), e.reading.as_deref(), e.rank))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [("一", None, 1), ("二", Some("に"), 2), ("三", None, 3)]
+        );
+    }
+}
@@ -1,7 +1,11 @@
+pub mod dictionaries;
+pub mod frequency_lists;
 pub mod jmdict;
 pub mod kanji_extra;
 pub mod kanji_names;
 pub mod kanji_similar;
 pub mod kanjidic;
 pub mod kradfile;
+pub mod pitch_accents;
 pub mod similar_kanji;
+pub mod yomichan;
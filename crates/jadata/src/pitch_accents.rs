@@ -0,0 +1,63 @@
+//! Models and parses Kanjium's pitch accent data.
+//! See <https://github.com/mifunetoshiro/kanjium>
+
+use eyre::Context;
+use std::io::Read;
+
+pub struct PitchAccents {
+    pub entries: Vec<PitchAccentEntry>,
+}
+
+/// The pitch accents of a word with the given reading.
+pub struct PitchAccentEntry {
+    pub word: String,
+    pub reading: String,
+    /// The mora after which the pitch drops, or 0 if it doesn't drop.
+    pub accents: Vec<u32>,
+}
+
+impl PitchAccents {
+    pub fn from<R: Read>(mut r: R) -> eyre::Result<Self> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+
+        // the file is formatted {word}\t{reading}\t{accent_1},{accent_2},...
+        // where the reading is empty for words written in kana,
+        // and the accents may be prefixed with parts of speech such as (名)0,(副)2
+        let mut entries = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut fields = line.split('\t');
+            let (Some(word), Some(reading), Some(accents)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                eyre::bail!("Invalid pitch accent line {}: '{line}'", idx + 1);
+            };
+            let reading = if reading.is_empty() { word } else { reading };
+            let mut parsed_accents = Vec::new();
+            for accent in accents.split(',') {
+                let accent = accent
+                    .rsplit(')')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .parse::<u32>()
+                    .wrap_err_with(|| {
+                        format!("Invalid pitch accent '{accent}' on line {}", idx + 1)
+                    })?;
+                if !parsed_accents.contains(&accent) {
+                    parsed_accents.push(accent);
+                }
+            }
+            entries.push(PitchAccentEntry {
+                word: word.to_string(),
+                reading: reading.to_string(),
+                accents: parsed_accents,
+            });
+        }
+
+        Ok(Self { entries })
+    }
+}
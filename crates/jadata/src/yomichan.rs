@@ -0,0 +1,90 @@
+//! Reads Yomichan dictionary archives.
+//! See <https://github.com/yomidevs/yomitan/blob/master/docs/making-yomitan-dictionaries.md>
+
+use eyre::Context;
+use serde::{Deserialize, de::DeserializeOwned};
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+/// A zip archive containing an index file and banks of entries split into numbered JSON files.
+pub struct Archive<R> {
+    pub index: Index,
+    zip: ZipArchive<R>,
+}
+
+/// Describes the dictionary in the archive.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Index {
+    pub title: String,
+    pub revision: String,
+}
+
+/// A term's entry in a `term_meta_bank`, formatted as [term, mode, data].
+#[derive(Debug, Clone, Deserialize)]
+pub struct TermMeta(pub String, pub String, pub serde_json::Value);
+
+/// A term's entry in a `term_bank` of a version 3 dictionary,
+/// formatted as [term, reading, definition tags, rules, score, definitions, sequence, term tags].
+#[derive(Debug, Clone, Deserialize)]
+pub struct Term(
+    pub String,
+    pub String,
+    pub Option<String>,
+    pub String,
+    pub f64,
+    pub Vec<serde_json::Value>,
+    pub i64,
+    pub String,
+);
+
+impl<R: Read + Seek> Archive<R> {
+    pub fn open(r: R) -> eyre::Result<Self> {
+        let mut zip = ZipArchive::new(r).context("Failed to open zip archive")?;
+        let index = {
+            let file = zip
+                .by_name("index.json")
+                .context("Failed to find index.json")?;
+            serde_json::from_reader(file).context("Failed to deserialize index.json")?
+        };
+        Ok(Self { index, zip })
+    }
+
+    /// Checks whether the archive contains any banks with the given name.
+    pub fn has_banks(&self, name: &str) -> bool {
+        let prefix = format!("{name}_");
+        self.zip
+            .file_names()
+            .any(|file_name| file_name.starts_with(&prefix) && file_name.ends_with(".json"))
+    }
+
+    /// Reads the entries of every bank with the given name, such as `term_bank` for `term_bank_1.json`, in order.
+    pub fn banks<T: DeserializeOwned>(&mut self, name: &str) -> eyre::Result<Vec<T>> {
+        let prefix = format!("{name}_");
+        let mut banks = self
+            .zip
+            .file_names()
+            .filter_map(|file_name| {
+                let number = file_name.strip_prefix(&prefix)?.strip_suffix(".json")?;
+                Some((number.parse::<u32>().ok()?, file_name.to_string()))
+            })
+            .collect::<Vec<_>>();
+        banks.sort();
+
+        let mut entries = Vec::new();
+        for (_, file_name) in banks {
+            let file = self.zip.by_name(&file_name)?;
+            let bank: Vec<T> = serde_json::from_reader(file)
+                .wrap_err_with(|| format!("Failed to deserialize {file_name}"))?;
+            entries.extend(bank);
+        }
+        Ok(entries)
+    }
+
+    pub fn term_meta(&mut self) -> eyre::Result<Vec<TermMeta>> {
+        self.banks("term_meta_bank")
+    }
+
+    pub fn terms(&mut self) -> eyre::Result<Vec<Term>> {
+        self.banks("term_bank")
+    }
+}
@@ -1,36 +1,71 @@
 //! Functionality for handling Anki cards and decks.
 
+pub mod history;
 mod kanji;
+mod note_type;
+mod render;
 mod word;
 
 pub use self::{
-    kanji::{Kanji, KanjiCard, KanjiWord},
-    word::{Furigana, Sentence, SentenceWord, WordCard, WordKanji},
+    kanji::{Kanji, KanjiCard, KanjiFields, KanjiWord},
+    note_type::{CardTemplate, CustomNoteType, NoteType, NoteTypeError},
+    render::RenderedCard,
+    word::{
+        ExtraSentence, Furigana, Sentence, SentenceWord, WordCard, WordCardTypes, WordFields,
+        WordKanji, WordModels,
+    },
 };
 pub use reanki::Deck;
 use std::sync::Arc;
 
+/// The note types of a deck, the default note types are used for the ones that are not set.
+#[derive(Debug, Clone, Default)]
+pub struct DeckNoteTypes {
+    pub word: Option<CustomNoteType>,
+    pub kanji: Option<CustomNoteType>,
+}
+
+impl DeckNoteTypes {
+    /// The note type used for word cards.
+    pub fn word(&self) -> NoteType {
+        self.word
+            .as_ref()
+            .map(|nt| nt.note_type.clone())
+            .unwrap_or_else(word::default_note_type)
+    }
+
+    /// The note type used for kanji cards.
+    pub fn kanji(&self) -> NoteType {
+        self.kanji
+            .as_ref()
+            .map(|nt| nt.note_type.clone())
+            .unwrap_or_else(kanji::default_note_type)
+    }
+}
+
+/// Creates a deck with the cards, which are introduced in the given order.
 pub fn create_deck(
     name: String,
     id: i64,
-    mut word_cards: Vec<WordCard>,
-    mut kanji_cards: Vec<KanjiCard>,
+    word_cards: Vec<WordCard>,
+    kanji_cards: Vec<KanjiCard>,
+    note_types: &DeckNoteTypes,
+    word_card_types: WordCardTypes,
 ) -> Deck {
+    let word_models = word::create_models(&name, note_types.word.as_ref(), word_card_types);
+    let (kanji_model, kanji_templates) = kanji::create_model(&name, note_types.kanji.as_ref());
+    let kanji_model = Arc::new(kanji_model);
     let mut deck = Deck::new(id, name, "Deck automatically generated by lbr".to_string());
-    let word_model = Arc::new(word::create_model());
-    let kanji_model = Arc::new(kanji::create_model());
-    let word_template = Arc::new(word::create_template());
-    let kanji_template = Arc::new(kanji::create_template());
-    word_cards.sort_unstable_by(|a, b| a.word_sentences.cmp(&b.word_sentences).reverse());
-    kanji_cards.sort_unstable_by(|a, b| a.kanji_words.cmp(&b.kanji_words).reverse());
     for (order, card) in word_cards.into_iter().enumerate() {
         tracing::debug!("creating word card {}", card.word);
-        deck.add_note(card.into_note(word_model.clone(), word_template.clone(), order as u16));
+        for note in card.into_notes(&word_models, order as u16) {
+            deck.add_note(note);
+        }
         tracing::debug!("created card");
     }
     for (order, card) in kanji_cards.into_iter().enumerate() {
         tracing::debug!("creating kanji card {}", card.kanji);
-        deck.add_note(card.into_note(kanji_model.clone(), kanji_template.clone(), order as u16));
+        deck.add_note(card.into_note(kanji_model.clone(), kanji_templates.clone(), order as u16));
         tracing::debug!("created card");
     }
     deck
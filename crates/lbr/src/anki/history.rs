@@ -0,0 +1,260 @@
+//! Review history from Anki collections for the cards generated by LBR.
+//! See <https://github.com/ankidroid/Anki-Android/wiki/Database-Structure> for the collection's structure.
+
+use crate::srs::Rating;
+use diesel::{prelude::*, sql_types::*, sqlite::SqliteConnection};
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read, Write},
+    time::{Duration, SystemTime},
+};
+use thiserror::Error;
+
+/// Anki considers cards with an interval of at least 21 days to be mature.
+pub const MATURE_INTERVAL: Duration = Duration::from_secs(21 * 24 * 60 * 60);
+/// The maximum size of a collection extracted from an Anki package.
+const MAX_COLLECTION_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Identifies an LBR card by its Anki note's GUID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LbrCard {
+    Word(i32),
+    Kanji(i32),
+}
+
+impl LbrCard {
+    /// Parses GUIDs such as `lbr-word-1` and `lbr-kanji-1`.
+    pub fn from_guid(guid: &str) -> Option<Self> {
+        let (kind, id) = guid.strip_prefix("lbr-")?.split_once('-')?;
+        let id = id.parse().ok()?;
+        match kind {
+            "word" => Some(Self::Word(id)),
+            "kanji" => Some(Self::Kanji(id)),
+            _ => None,
+        }
+    }
+}
+
+/// The state of a card in Anki and its past reviews.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardHistory {
+    pub card: LbrCard,
+    pub interval: Duration,
+    pub ease: f64,
+    /// Successful reviews in a row at the end of the history.
+    pub repetitions: u32,
+    pub lapses: u32,
+    /// The reviews in chronological order.
+    pub reviews: Vec<Review>,
+}
+
+impl CardHistory {
+    pub fn is_mature(&self) -> bool {
+        self.interval >= MATURE_INTERVAL
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Review {
+    pub reviewed_at: SystemTime,
+    pub rating: Rating,
+    /// The card's interval after the review.
+    pub interval: Duration,
+    pub ease: f64,
+}
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("Failed to read Anki package")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("Failed to read collection from Anki package")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to open Anki collection")]
+    Connection(#[from] diesel::ConnectionError),
+    #[error("Failed to query Anki collection")]
+    Query(#[from] diesel::result::Error),
+    #[error(
+        "The Anki package only contains a collection in the latest format, export it with support for older Anki versions"
+    )]
+    UnsupportedCollection,
+    #[error("The Anki collection is larger than the limit of {} MiB", MAX_COLLECTION_SIZE / 1024 / 1024)]
+    CollectionTooLarge,
+}
+
+/// Reads the review history of the LBR cards from an Anki package (.apkg) or collection file (.anki2 or .anki21).
+/// Cards that have never been reviewed and cards not generated by LBR are skipped.
+pub fn read_history(data: &[u8]) -> Result<Vec<CardHistory>, HistoryError> {
+    let collection = if data.starts_with(b"SQLite format 3\0") {
+        data.to_vec()
+    } else {
+        extract_collection(data)?
+    };
+    // SQLite can only open collections from files
+    let mut file = tempfile::Builder::new()
+        .prefix("lbr-anki-collection-")
+        .suffix(".sqlite")
+        .tempfile()?;
+    file.write_all(&collection)?;
+    file.flush()?;
+    let path = file.path().to_string_lossy();
+    let mut conn = SqliteConnection::establish(&path)?;
+    read_collection(&mut conn)
+}
+
+// reads the collection file from the package, preferring the newer format
+fn extract_collection(apkg: &[u8]) -> Result<Vec<u8>, HistoryError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(apkg))?;
+    for name in ["collection.anki21", "collection.anki2"] {
+        let Ok(file) = archive.by_name(name) else {
+            continue;
+        };
+        // the size in the archive can't be trusted, so the limit is enforced while reading
+        let mut collection = Vec::new();
+        file.take(MAX_COLLECTION_SIZE + 1)
+            .read_to_end(&mut collection)?;
+        if collection.len() as u64 > MAX_COLLECTION_SIZE {
+            return Err(HistoryError::CollectionTooLarge);
+        }
+        return Ok(collection);
+    }
+    Err(HistoryError::UnsupportedCollection)
+}
+
+fn read_collection(conn: &mut SqliteConnection) -> Result<Vec<CardHistory>, HistoryError> {
+    // notes with several card templates have a card for each,
+    // only the first one is used as it corresponds to the card reviewed in LBR
+    let cards = diesel::sql_query(
+        "SELECT cards.id, notes.guid, cards.ivl, cards.factor, cards.lapses
+        FROM cards JOIN notes ON notes.id = cards.nid
+        WHERE cards.type != 0 AND cards.ord = 0 AND notes.guid LIKE 'lbr-%'",
+    )
+    .load::<AnkiCard>(conn)?;
+    let mut reviews_by_card = diesel::sql_query(
+        "SELECT id, cid, ease, ivl, factor FROM revlog WHERE ease BETWEEN 1 AND 4 ORDER BY id",
+    )
+    .load::<AnkiReview>(conn)?
+    .into_iter()
+    .fold(HashMap::<i64, Vec<Review>>::new(), |mut acc, review| {
+        acc.entry(review.cid).or_default().push(Review {
+            reviewed_at: SystemTime::UNIX_EPOCH + Duration::from_millis(review.id.max(0) as u64),
+            rating: match review.ease {
+                1 => Rating::Again,
+                2 => Rating::Hard,
+                3 => Rating::Good,
+                _ => Rating::Easy,
+            },
+            interval: to_interval(review.ivl),
+            ease: to_ease(review.factor),
+        });
+        acc
+    });
+
+    let histories = cards
+        .into_iter()
+        .filter_map(|card| {
+            let lbr_card = LbrCard::from_guid(&card.guid)?;
+            let reviews = reviews_by_card.remove(&card.id).unwrap_or_default();
+            let repetitions = reviews
+                .iter()
+                .rev()
+                .take_while(|r| r.rating != Rating::Again)
+                .count();
+            Some(CardHistory {
+                card: lbr_card,
+                interval: to_interval(card.ivl),
+                ease: to_ease(card.factor),
+                repetitions: repetitions as u32,
+                lapses: card.lapses.max(0) as u32,
+                reviews,
+            })
+        })
+        .collect();
+    Ok(histories)
+}
+
+// positive intervals are in days and negative intervals in seconds
+fn to_interval(ivl: i64) -> Duration {
+    if ivl >= 0 {
+        Duration::from_secs(ivl as u64 * 24 * 60 * 60)
+    } else {
+        Duration::from_secs(ivl.unsigned_abs())
+    }
+}
+
+// the ease factor is stored in permille and is 0 for cards that are still being learned
+fn to_ease(factor: i64) -> f64 {
+    if factor > 0 {
+        factor as f64 / 1000.0
+    } else {
+        crate::srs::CardState::new().ease
+    }
+}
+
+#[derive(QueryableByName)]
+struct AnkiCard {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Text)]
+    guid: String,
+    #[diesel(sql_type = BigInt)]
+    ivl: i64,
+    #[diesel(sql_type = BigInt)]
+    factor: i64,
+    #[diesel(sql_type = BigInt)]
+    lapses: i64,
+}
+
+#[derive(QueryableByName)]
+struct AnkiReview {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = BigInt)]
+    cid: i64,
+    #[diesel(sql_type = BigInt)]
+    ease: i64,
+    #[diesel(sql_type = BigInt)]
+    ivl: i64,
+    #[diesel(sql_type = BigInt)]
+    factor: i64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_guids() {
+        assert_eq!(LbrCard::from_guid("lbr-word-12"), Some(LbrCard::Word(12)));
+        assert_eq!(LbrCard::from_guid("lbr-kanji-3"), Some(LbrCard::Kanji(3)));
+        assert_eq!(LbrCard::from_guid("lbr-word-x"), None);
+        assert_eq!(LbrCard::from_guid("lbr-word-cloze-12"), None);
+        assert_eq!(LbrCard::from_guid("f9Kd^s;1"), None);
+    }
+
+    #[test]
+    fn reads_collection() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        for statement in [
+            "CREATE TABLE notes (id INTEGER PRIMARY KEY, guid TEXT NOT NULL)",
+            "CREATE TABLE cards (id INTEGER PRIMARY KEY, nid INTEGER NOT NULL, ord INTEGER NOT NULL, type INTEGER NOT NULL, ivl INTEGER NOT NULL, factor INTEGER NOT NULL, lapses INTEGER NOT NULL)",
+            "CREATE TABLE revlog (id INTEGER PRIMARY KEY, cid INTEGER NOT NULL, ease INTEGER NOT NULL, ivl INTEGER NOT NULL, factor INTEGER NOT NULL)",
+            "INSERT INTO notes VALUES (1, 'lbr-word-5'), (2, 'lbr-kanji-6'), (3, 'other')",
+            "INSERT INTO cards VALUES (10, 1, 0, 2, 30, 2650, 1), (11, 2, 0, 0, 0, 0, 0), (12, 3, 0, 2, 5, 2500, 0), (13, 1, 1, 2, 5, 2500, 0)",
+            "INSERT INTO revlog VALUES (1000, 10, 3, 1, 2500), (2000, 10, 1, -600, 2300), (3000, 10, 3, 30, 2650), (4000, 10, 0, 30, 2650)",
+        ] {
+            diesel::sql_query(statement).execute(&mut conn).unwrap();
+        }
+
+        let histories = read_collection(&mut conn).unwrap();
+        assert_eq!(histories.len(), 1);
+        let history = &histories[0];
+        assert_eq!(history.card, LbrCard::Word(5));
+        assert!(history.is_mature());
+        assert_eq!(history.ease, 2.65);
+        assert_eq!(history.repetitions, 1);
+        assert_eq!(history.lapses, 1);
+        assert_eq!(history.reviews.len(), 3);
+        assert_eq!(history.reviews[1].rating, Rating::Again);
+        assert_eq!(history.reviews[1].interval, Duration::from_secs(600));
+    }
+}
@@ -1,7 +1,14 @@
 //! Kanji Anki cards.
 
-use reanki::{Field, Model, Note, Template};
-use std::sync::Arc;
+use super::{
+    note_type::{CardTemplate, CustomNoteType, NoteType},
+    render::RenderedCard,
+};
+use reanki::{Model, ModelType, Note, Template};
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct KanjiCard {
@@ -11,6 +18,17 @@ pub struct KanjiCard {
     pub example_source_word: KanjiWord,
     pub similar_kanji: Vec<Kanji>,
     pub kanji_words: usize,
+    /// The school grade the kanji is taught in, 8 for jouyou kanji taught in secondary school.
+    pub grade: Option<u32>,
+    /// The level in the old four level JLPT, from 4 to 1.
+    pub jlpt: Option<u32>,
+    pub stroke_count: Option<u32>,
+    /// The kanji's rank among the 2500 most used kanji in newspapers.
+    pub frequency: Option<u32>,
+    /// The number of the kanji's classical (Kangxi) radical, from 1 to 214.
+    pub radical: Option<u32>,
+    /// When the card's example was chosen.
+    pub generated_at: SystemTime,
 }
 
 impl KanjiCard {
@@ -42,6 +60,22 @@ impl KanjiCard {
             .join("");
         let similar_kanji = format!("<ul>{similar_kanji}</ul>");
 
+        // stroke_count, grade, jlpt, frequency
+        let optional_number = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or_default();
+        let stroke_count = optional_number(self.stroke_count);
+        let grade = optional_number(self.grade);
+        let jlpt = optional_number(self.jlpt);
+        let frequency = optional_number(self.frequency);
+
+        // radical
+        let radical = self
+            .radical
+            .map(|n| match kangxi_radical(n) {
+                Some(radical) => format!("{radical} ({n})"),
+                None => n.to_string(),
+            })
+            .unwrap_or_default();
+
         KanjiFields {
             id: self.id.to_string(),
             count: self.kanji_words.to_string(),
@@ -50,21 +84,35 @@ impl KanjiCard {
             example_source_word,
             example_source_word_translation,
             similar_kanji,
-            generated_at: std::time::UNIX_EPOCH
-                .elapsed()
-                .unwrap()
+            stroke_count,
+            grade,
+            jlpt,
+            frequency,
+            radical,
+            generated_at: self
+                .generated_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
                 .as_secs()
                 .to_string(),
         }
     }
 
-    pub fn into_note(self, model: Arc<Model>, template: Arc<Template>, order: u16) -> Note {
+    pub fn into_note(self, model: Arc<Model>, templates: Vec<Arc<Template>>, order: u16) -> Note {
         // negate id to avoid conflicts with word ids
         let kanji_id = self.id;
         let guid: String = format!("lbr-kanji-{kanji_id}");
         let fields = self.into_fields();
-        Note::new(guid, model, vec![template], fields.into_fields()).order(order)
+        Note::new(guid, model, templates, fields.into_fields()).order(order)
+    }
+}
+
+// the Kangxi radicals are encoded in order starting from U+2F00
+fn kangxi_radical(number: u32) -> Option<char> {
+    if !(1..=214).contains(&number) {
+        return None;
     }
+    char::from_u32(0x2F00 + number - 1)
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -89,27 +137,45 @@ pub struct KanjiFields {
     example_source_word: String,
     example_source_word_translation: String,
     similar_kanji: String,
+    stroke_count: String,
+    grade: String,
+    jlpt: String,
+    frequency: String,
+    radical: String,
     generated_at: String,
 }
 
 impl KanjiFields {
-    // keep in sync with `to_fields`
-    fn fields() -> Vec<Field> {
-        vec![
-            Field::new("id".to_string()),
-            // the count should be the 1th field
-            // as this is used by the model as the sort field
-            Field::new("count".to_string()),
-            Field::new("kanji".to_string()),
-            Field::new("name".to_string()),
-            Field::new("example_source_word".to_string()),
-            Field::new("example_source_word_translation".to_string()),
-            Field::new("similar_kanji".to_string()),
-            Field::new("generated_at".to_string()),
-        ]
+    // keep in sync with `into_fields`
+    pub const NAMES: [&str; 13] = [
+        "id",
+        // the count should be the 1th field
+        // as this is used by the model as the sort field
+        "count",
+        "kanji",
+        "name",
+        "example_source_word",
+        "example_source_word_translation",
+        "similar_kanji",
+        "stroke_count",
+        "grade",
+        "jlpt",
+        "frequency",
+        "radical",
+        "generated_at",
+    ];
+
+    /// Renders the card's question and answer the same way Anki does.
+    pub fn render(self, note_type: &NoteType) -> RenderedCard {
+        let values = self.into_fields();
+        let fields = Self::NAMES
+            .into_iter()
+            .zip(values.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+        note_type.render(&fields)
     }
 
-    // keep in sync with `fields`
+    // keep in sync with `NAMES`
     fn into_fields(self) -> Vec<String> {
         vec![
             self.id,
@@ -119,21 +185,17 @@ impl KanjiFields {
             self.example_source_word,
             self.example_source_word_translation,
             self.similar_kanji,
+            self.stroke_count,
+            self.grade,
+            self.jlpt,
+            self.frequency,
+            self.radical,
             self.generated_at,
         ]
     }
 }
 
-/// Globally unique anki model ID. Randomly chosen.
-const LBR_KANJI_ANKI_MODEL_ID: i64 = -1289186172;
-pub fn create_model() -> Model {
-    let fields = KanjiFields::fields();
-    Model::new(
-        LBR_KANJI_ANKI_MODEL_ID,
-        "lbr-kanji".to_string(),
-        fields,
-        1,
-        r#"
+const CSS: &str = r#"
 .card {
     text-align: center;
     background-color: Linen;
@@ -154,24 +216,19 @@ pub fn create_model() -> Model {
     display: inline-block;
     text-align: left;
 }
-"#
-        .to_string(),
-        reanki::ModelType::Standard,
-    )
+#info {
+    font-size: 1rem;
+    color: gray;
 }
+"#;
 
-const LBR_KANJI_ANKI_TEMPLATE_ID: i64 = -155074387;
-pub fn create_template() -> Template {
-    Template::new(
-        LBR_KANJI_ANKI_TEMPLATE_ID,
-        "lbr-kanji".to_string(),
-        r#"
+const QUESTION_TEMPLATE: &str = r#"
 <div id=kanji>
     {{kanji}}
 </div>
-"#
-        .to_string(),
-        r#"
+"#;
+
+const ANSWER_TEMPLATE: &str = r#"
 <div id=answer>
     <div id=kanji>
         {{kanji}}
@@ -179,6 +236,13 @@ pub fn create_template() -> Template {
     <div id=name>
         {{name}}
     </div>
+    <div id=info>
+        {{#stroke_count}}<span>{{stroke_count}} strokes</span>{{/stroke_count}}
+        {{#radical}}<span>radical {{radical}}</span>{{/radical}}
+        {{#grade}}<span>grade {{grade}}</span>{{/grade}}
+        {{#jlpt}}<span>JLPT level {{jlpt}}</span>{{/jlpt}}
+        {{#frequency}}<span>#{{frequency}} in newspapers</span>{{/frequency}}
+    </div>
 
     <hr>
 
@@ -193,7 +257,86 @@ pub fn create_template() -> Template {
         {{similar_kanji}}
     </div>
 </div>
-"#
-        .to_string(),
-    )
+"#;
+
+/// The note type used for kanji cards in decks that don't have their own.
+pub fn default_note_type() -> NoteType {
+    NoteType {
+        css: CSS.to_string(),
+        templates: vec![CardTemplate {
+            name: "lbr-kanji".to_string(),
+            question: QUESTION_TEMPLATE.to_string(),
+            answer: ANSWER_TEMPLATE.to_string(),
+        }],
+    }
+}
+
+/// Globally unique anki model ID. Randomly chosen.
+const LBR_KANJI_ANKI_MODEL_ID: i64 = -1289186172;
+const LBR_KANJI_ANKI_TEMPLATE_ID: i64 = -155074387;
+
+/// Creates the model and templates for the deck's own note type, or the default one.
+pub fn create_model(
+    deck_name: &str,
+    note_type: Option<&CustomNoteType>,
+) -> (Model, Vec<Arc<Template>>) {
+    match note_type {
+        Some(CustomNoteType {
+            model_id,
+            note_type,
+        }) => (
+            note_type.create_model(
+                *model_id,
+                &format!("lbr-kanji ({deck_name})"),
+                &KanjiFields::NAMES,
+                ModelType::Standard,
+            ),
+            note_type.create_templates(model_id.wrapping_add(1)),
+        ),
+        None => {
+            let note_type = default_note_type();
+            (
+                note_type.create_model(
+                    LBR_KANJI_ANKI_MODEL_ID,
+                    "lbr-kanji",
+                    &KanjiFields::NAMES,
+                    ModelType::Standard,
+                ),
+                note_type.create_templates(LBR_KANJI_ANKI_TEMPLATE_ID),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn creates_fields_from_card() {
+        let card = KanjiCard {
+            id: 1,
+            kanji: "猫".to_string(),
+            name: "cat".to_string(),
+            example_source_word: KanjiWord {
+                word: "猫".to_string(),
+                translations: vec!["cat".to_string()],
+            },
+            similar_kanji: vec![],
+            kanji_words: 2,
+            grade: Some(8),
+            jlpt: None,
+            stroke_count: Some(11),
+            frequency: Some(1702),
+            radical: Some(94),
+            generated_at: UNIX_EPOCH,
+        };
+        let fields = card.into_fields();
+        assert_eq!(fields.stroke_count, "11");
+        assert_eq!(fields.grade, "8");
+        assert_eq!(fields.jlpt, "");
+        assert_eq!(fields.frequency, "1702");
+        assert_eq!(fields.radical, "⽝ (94)");
+        assert_eq!(fields.into_fields().len(), KanjiFields::NAMES.len());
+    }
 }
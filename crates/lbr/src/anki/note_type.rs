@@ -0,0 +1,235 @@
+//! Customisable Anki note types.
+
+use super::render::{self, RenderedCard};
+use reanki::{Field, Model, ModelType, Template};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// The styling and card templates of the notes for either words or kanji.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteType {
+    pub css: String,
+    /// A card is created from each template for every note, so a second template can be used for reverse cards.
+    pub templates: Vec<CardTemplate>,
+}
+
+/// A note type defined for a deck, with its own model id in Anki.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomNoteType {
+    pub model_id: i64,
+    pub note_type: NoteType,
+}
+
+/// The front and back of a card, written with Anki's template syntax.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardTemplate {
+    pub name: String,
+    pub question: String,
+    pub answer: String,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum NoteTypeError {
+    #[error("The note type has no card templates")]
+    NoTemplates,
+    #[error("A card template has no name")]
+    UnnamedTemplate,
+    #[error("There are several card templates named {0}")]
+    DuplicateTemplate(String),
+    #[error("The front of the card template {template} doesn't contain any fields")]
+    EmptyQuestion { template: String },
+    #[error("The front of the card template {template} can't contain FrontSide")]
+    FrontSideInQuestion { template: String },
+    #[error("The card template {template} refers to an unknown field {field}")]
+    UnknownField { template: String, field: String },
+    #[error("The section {field} in the card template {template} is not closed")]
+    UnclosedSection { template: String, field: String },
+    #[error("The section {field} in the card template {template} is closed without being opened")]
+    UnopenedSection { template: String, field: String },
+}
+
+/// Fields that Anki provides for every card.
+const SPECIAL_FIELDS: [&str; 6] = ["Tags", "Type", "Deck", "Subdeck", "Card", "CardFlag"];
+
+impl NoteType {
+    /// Checks that the templates only refer to the given fields and that their sections are closed.
+    pub fn validate(&self, fields: &[&str]) -> Result<(), NoteTypeError> {
+        if self.templates.is_empty() {
+            return Err(NoteTypeError::NoTemplates);
+        }
+        for (idx, template) in self.templates.iter().enumerate() {
+            let name = template.name.trim();
+            if name.is_empty() {
+                return Err(NoteTypeError::UnnamedTemplate);
+            }
+            if self.templates[..idx].iter().any(|t| t.name.trim() == name) {
+                return Err(NoteTypeError::DuplicateTemplate(name.to_string()));
+            }
+
+            let question_fields = validate_template(name, &template.question, fields)?;
+            if question_fields.contains(&"FrontSide") {
+                return Err(NoteTypeError::FrontSideInQuestion {
+                    template: name.to_string(),
+                });
+            }
+            if !question_fields.iter().any(|f| fields.contains(f)) {
+                return Err(NoteTypeError::EmptyQuestion {
+                    template: name.to_string(),
+                });
+            }
+            validate_template(name, &template.answer, fields)?;
+        }
+        Ok(())
+    }
+
+    /// Renders the card created from the note type's first template.
+    pub(super) fn render(&self, fields: &[(&str, &str)]) -> RenderedCard {
+        let template = &self.templates[0];
+        let question = render::render(&template.question, fields);
+        let mut answer_fields = fields.to_vec();
+        answer_fields.push(("FrontSide", &question));
+        let answer = render::render(&template.answer, &answer_fields);
+        RenderedCard {
+            question,
+            answer,
+            css: self.css.clone(),
+        }
+    }
+
+    pub(super) fn create_model(
+        &self,
+        id: i64,
+        name: &str,
+        fields: &[&str],
+        model_type: ModelType,
+    ) -> Model {
+        let fields = fields
+            .iter()
+            .map(|name| Field::new(name.to_string()))
+            .collect();
+        Model::new(
+            id,
+            name.to_string(),
+            fields,
+            1,
+            self.css.clone(),
+            model_type,
+        )
+    }
+
+    // the templates are numbered from the first id so that they stay the same between generations
+    pub(super) fn create_templates(&self, first_template_id: i64) -> Vec<Arc<Template>> {
+        self.templates
+            .iter()
+            .enumerate()
+            .map(|(idx, template)| {
+                Arc::new(Template::new(
+                    first_template_id.wrapping_add(idx as i64),
+                    template.name.clone(),
+                    template.question.clone(),
+                    template.answer.clone(),
+                ))
+            })
+            .collect()
+    }
+}
+
+// returns the fields referred to in the template
+fn validate_template<'a>(
+    name: &str,
+    template: &'a str,
+    fields: &[&str],
+) -> Result<Vec<&'a str>, NoteTypeError> {
+    let mut referenced = Vec::new();
+    let mut open_sections = Vec::new();
+    for tag in render::tags(template) {
+        let field = if let Some(field) = tag.strip_prefix(['#', '^']) {
+            open_sections.push(field.trim());
+            field.trim()
+        } else if let Some(field) = tag.strip_prefix('/') {
+            let field = field.trim();
+            if open_sections.pop() != Some(field) {
+                return Err(NoteTypeError::UnopenedSection {
+                    template: name.to_string(),
+                    field: field.to_string(),
+                });
+            }
+            field
+        } else {
+            // filters such as furigana: are before the field name
+            tag.rsplit(':').next().unwrap_or_default().trim()
+        };
+        if field != "FrontSide" && !SPECIAL_FIELDS.contains(&field) && !fields.contains(&field) {
+            return Err(NoteTypeError::UnknownField {
+                template: name.to_string(),
+                field: field.to_string(),
+            });
+        }
+        referenced.push(field);
+    }
+    if let Some(field) = open_sections.pop() {
+        return Err(NoteTypeError::UnclosedSection {
+            template: name.to_string(),
+            field: field.to_string(),
+        });
+    }
+    Ok(referenced)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn note_type(question: &str, answer: &str) -> NoteType {
+        NoteType {
+            css: String::new(),
+            templates: vec![CardTemplate {
+                name: "card".to_string(),
+                question: question.to_string(),
+                answer: answer.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn validates_templates() {
+        let fields = ["word", "translation"];
+        assert_eq!(
+            note_type(
+                "{{furigana:word}}",
+                "{{FrontSide}}{{#translation}}{{translation}}{{/translation}}{{tts ja_JP:word}}"
+            )
+            .validate(&fields),
+            Ok(())
+        );
+        assert_eq!(
+            note_type("{{wrod}}", "").validate(&fields),
+            Err(NoteTypeError::UnknownField {
+                template: "card".to_string(),
+                field: "wrod".to_string()
+            })
+        );
+        assert_eq!(
+            note_type("{{#word}}{{word}}", "").validate(&fields),
+            Err(NoteTypeError::UnclosedSection {
+                template: "card".to_string(),
+                field: "word".to_string()
+            })
+        );
+        assert_eq!(
+            note_type("{{Tags}}", "").validate(&fields),
+            Err(NoteTypeError::EmptyQuestion {
+                template: "card".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn renders_front_side_on_answer() {
+        let rendered = note_type("<b>{{word}}</b>", "{{FrontSide}}<hr>{{translation}}")
+            .render(&[("word", "猫"), ("translation", "cat")]);
+        assert_eq!(rendered.question, "<b>猫</b>");
+        assert_eq!(rendered.answer, "<b>猫</b><hr>cat");
+    }
+}
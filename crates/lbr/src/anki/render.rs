@@ -0,0 +1,176 @@
+//! Renders cards to HTML the same way Anki does, for reviewing cards without Anki.
+//! Only supports the template features used by LBR's templates.
+//! See <https://docs.ankiweb.net/templates/fields.html> for the syntax.
+
+/// The HTML for both sides of a card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedCard {
+    pub question: String,
+    pub answer: String,
+    /// Styling for the card's elements, the card is expected to be within an element with the class `card`.
+    pub css: String,
+}
+
+/// Returns the contents of the tags in the template, such as `#field` or `furigana:field`.
+pub fn tags(template: &str) -> impl Iterator<Item = &str> {
+    let mut rest = template;
+    std::iter::from_fn(move || {
+        let start = rest.find("{{")?;
+        let end = rest[start..].find("}}")?;
+        let tag = rest[start + 2..start + end].trim();
+        rest = &rest[start + end + 2..];
+        Some(tag)
+    })
+}
+
+/// Replaces field references such as `{{field}}` and `{{furigana:field}}` with the field values
+/// and removes conditional sections such as `{{#field}}...{{/field}}` when the field is empty
+/// and `{{^field}}...{{/field}}` when it isn't.
+pub fn render(template: &str, fields: &[(&str, &str)]) -> String {
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| *v)
+            .unwrap_or_default()
+    };
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 2..start + end].trim();
+        rest = &rest[start + end + 2..];
+
+        if let Some(name) = tag.strip_prefix(['#', '^']) {
+            // the section is kept only if the field has a value, or doesn't for inverted sections
+            let inverted = tag.starts_with('^');
+            let closing = format!("{{{{/{name}}}}}");
+            let section_end = rest.find(&closing).unwrap_or(rest.len());
+            if field(name).trim().is_empty() == inverted {
+                rendered.push_str(&render(&rest[..section_end], fields));
+            }
+            rest = &rest[(section_end + closing.len()).min(rest.len())..];
+        } else if let Some((filters, name)) = tag.rsplit_once(':') {
+            // filters are applied from right to left
+            let value = filters
+                .rsplit(':')
+                .fold(field(name.trim()).to_string(), |value, filter| {
+                    apply_filter(filter.trim(), &value)
+                });
+            rendered.push_str(&value);
+        } else {
+            rendered.push_str(field(tag));
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn apply_filter(filter: &str, value: &str) -> String {
+    match filter {
+        "furigana" => furigana(value),
+        "kana" => map_furigana(value, |base, reading| {
+            // empty readings are used to separate words
+            if reading.trim().is_empty() {
+                base.to_string()
+            } else {
+                reading.to_string()
+            }
+        }),
+        "kanji" => map_furigana(value, |base, _reading| base.to_string()),
+        "text" => strip_tags(value),
+        // text to speech is only available in Anki
+        tts if tts.starts_with("tts") => String::new(),
+        _ => value.to_string(),
+    }
+}
+
+/// Converts text such as `漢字[かんじ]` to ruby.
+/// The base text starts after the previous space or tag, a single space before it is removed.
+fn furigana(text: &str) -> String {
+    map_furigana(text, |base, reading| {
+        format!("<ruby><rb>{base}</rb><rt>{reading}</rt></ruby>")
+    })
+}
+
+// replaces text such as `漢字[かんじ]` with the result of the function called with the base text and reading
+fn map_furigana(text: &str, f: impl Fn(&str, &str) -> String) -> String {
+    let mut rendered = String::with_capacity(text.len() * 2);
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find(']').map(|close| open + close) else {
+            break;
+        };
+        let before = &rest[..open];
+        let base_start = before.rfind([' ', '>']).map(|idx| idx + 1).unwrap_or(0);
+        let base = &before[base_start..];
+        let reading = &rest[open + 1..close];
+        if base.is_empty() || reading.is_empty() {
+            rendered.push_str(&rest[..=close]);
+        } else {
+            let preceding = &before[..base_start];
+            rendered.push_str(preceding.strip_suffix(' ').unwrap_or(preceding));
+            rendered.push_str(&f(base, reading));
+        }
+        rest = &rest[close + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn strip_tags(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn renders_fields_and_sections() {
+        let template =
+            "<div>{{word}}</div>{{#kanji}}<div>{{kanji}}</div>{{/kanji}}{{#empty}}x{{/empty}}";
+        let rendered = render(
+            template,
+            &[("word", "猫"), ("kanji", "猫 (cat)"), ("empty", "")],
+        );
+        assert_eq!(rendered, "<div>猫</div><div>猫 (cat)</div>");
+    }
+
+    #[test]
+    fn renders_inverted_sections_and_filters() {
+        let template = "{{^empty}}{{kana:word}}{{/empty}}{{kanji:word}}{{text:html}}{{tts ja_JP:word}}";
+        let rendered = render(
+            template,
+            &[("word", "猫[ねこ]だ[ ]"), ("html", "<b>bold</b>"), ("empty", "")],
+        );
+        assert_eq!(rendered, "ねこだ猫だbold");
+    }
+
+    #[test]
+    fn renders_furigana() {
+        assert_eq!(
+            furigana("<span>吾輩[わがはい]</span>は[ ]猫[ねこ]"),
+            "<span><ruby><rb>吾輩</rb><rt>わがはい</rt></ruby></span><ruby><rb>は</rb><rt> </rt></ruby><ruby><rb>猫</rb><rt>ねこ</rt></ruby>"
+        );
+        assert_eq!(
+            furigana("a 日本[にほん]"),
+            "a<ruby><rb>日本</rb><rt>にほん</rt></ruby>"
+        );
+    }
+}
@@ -1,8 +1,18 @@
 //! Sentence word Anki cards.
 
-use reanki::{Field, Model, ModelType, Note, Template};
+use super::{
+    note_type::{CardTemplate, CustomNoteType, NoteType},
+    render::RenderedCard,
+};
+use lbr_core::{word_definitions::WordDefinitions, word_sense::WordSense};
+use reanki::{Model, ModelType, Note, Template};
 use serde::Deserialize;
-use std::{fmt::Write, ops::Range, sync::Arc};
+use std::{
+    fmt::Write,
+    ops::Range,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct WordCard {
@@ -11,10 +21,22 @@ pub struct WordCard {
     pub word: String,
     pub word_range: Range<usize>,
     pub word_furigana: Vec<Furigana>,
+    /// The word's reading in kana.
+    pub reading: String,
+    /// The mora after which the pitch drops for each of the word's pitch accents, 0 if it doesn't drop.
+    pub pitch_accents: Vec<u32>,
     pub word_sentences: usize,
     pub sentence: Sentence,
     pub translations: Vec<String>,
+    /// The word's dictionary senses, shown instead of the translations when available.
+    pub senses: Vec<WordSense>,
+    /// The word's definitions in the dictionaries the user has chosen to show.
+    pub definitions: Vec<WordDefinitions>,
     pub kanji: Vec<WordKanji>,
+    /// Additional examples of the word shown on the back of the card.
+    pub extra_sentences: Vec<ExtraSentence>,
+    /// When the card's example was chosen.
+    pub generated_at: SystemTime,
 }
 
 impl WordCard {
@@ -22,78 +44,39 @@ impl WordCard {
         // count
         let count = self.word_sentences.to_string();
 
+        // timestamps
+        let timestamp = self
+            .sentence
+            .timestamps
+            .as_ref()
+            .map(|t| format!("{}–{}", format_timestamp(t.start), format_timestamp(t.end)))
+            .unwrap_or_default();
+
         // sentence
         // here, we insert furigana ruby and highlight the card's word in the sentence
-        let mut sentence_idx = 0;
-        let sentence_text = &self.sentence.sentence;
-        // there's a lot of fluff added so we just estimate the capacity to x4...
-        let mut sentence = String::with_capacity(sentence_text.len() * 4);
-        // process sentence words in order of appearance
-        let mut sentence_words = self.sentence.words;
-        sentence_words.sort_unstable_by_key(|sw| sw.idx_start);
-        for sw in sentence_words {
-            // insert stuff that's before this sentence word
-            // sentence words don't cover punctuation etc., so this step is needed
-            // to ensure it's accounted for
-            let sentence_word_start = sw.idx_start as usize;
-            if sentence_idx < sentence_word_start {
-                let push = &sentence_text[sentence_idx..sentence_word_start];
-                sentence.push_str(push);
-                if !push.ends_with(char::is_whitespace) {
-                    // empty ruby are used to prevent furigana from running over past where it's supposed to
-                    sentence.push_str("[ ]");
-                }
-            }
-            sentence_idx = sentence_word_start;
-
-            // check if this sentence word is the word on the card
-            let in_study_word = self.word_range.start == sentence_idx;
-            if in_study_word {
-                sentence.push_str("<span id=highlighted>");
-            } else {
-                // all elements are wrapped in spans which are styled so that
-                // word breaks do not happen within words unless absolutely necessary
-                sentence.push_str("<span>");
-            }
-
-            // process furigana in order of appearance
-            let mut furigana = sw.furigana;
-            furigana.sort_unstable_by_key(|f| f.range.start);
-            for furigana in furigana {
-                let furigana_start = sentence_word_start + furigana.range.start;
-                let furigana_end = sentence_word_start + furigana.range.end;
-
-                // push stuff before this furigana that wasn't processed yet
-                if sentence_idx < furigana_start {
-                    sentence.push_str(&sentence_text[sentence_idx..furigana_start]);
-                    sentence.push_str("[ ]");
-                }
-
-                // push stuff covered by this furigana
-                sentence.push_str(&sentence_text[furigana_start..furigana_end]);
-                write!(sentence, "[{}]", furigana.furigana).unwrap();
-                sentence_idx = furigana_end;
-            }
+        let sentence_id = self.sentence.id.to_string();
+        let (sentence, highlighted) = format_sentence(self.sentence, &self.word_range);
 
-            // insert stuff left over after the furigana
-            let sw_idx_end = sw.idx_end as usize;
-            if sentence_idx < sw_idx_end {
-                let push = &sentence_text[sentence_idx..sw_idx_end];
-                sentence.push_str(push);
-                if !push.ends_with(char::is_whitespace) {
-                    sentence.push_str("[ ]");
-                }
-                sentence_idx = sw_idx_end;
-            }
-            // close out the word with a "word break opportunity" so that when line breaks are needed
-            // they are placed after words instead of in the middle
-            sentence.push_str("</span><wbr/>");
+        // extra sentences, formatted the same way as the main one
+        let mut extra_sentences = String::new();
+        for extra in self.extra_sentences {
+            let (formatted, _) = format_sentence(extra.sentence, &extra.word_range);
+            write!(extra_sentences, "<div>{formatted}</div>").unwrap();
         }
 
-        // push stuff left over after processing all sentence words
-        if sentence_idx < self.sentence.sentence.len() {
-            sentence.push_str(&sentence_text[sentence_idx..]);
-        }
+        // the sentence with the card's word left out for production and cloze cards,
+        // these are left empty if the word couldn't be found in the sentence
+        let (sentence_blank, sentence_cloze) = match highlighted {
+            Some(highlighted) => {
+                let before = &sentence[..highlighted.start];
+                let after = &sentence[highlighted.end..];
+                (
+                    format!("{before}＿＿＿{after}"),
+                    format!("{before}{{{{c1::{}}}}}{after}", &sentence[highlighted]),
+                )
+            }
+            None => (String::new(), String::new()),
+        };
 
         // word in dictionary form with furigana
         let mut word = String::with_capacity(self.word.len() * 4);
@@ -115,14 +98,50 @@ impl WordCard {
             word.push_str(&self.word[word_idx..]);
         }
 
+        // pitch accents, with classes on each mora for styling
+        let mut pitch_accent = String::new();
+        for accent in &self.pitch_accents {
+            pitch_accent.push_str("<div class=pitch-accent>");
+            for mora in lbr_core::pitch_accent::pitch_accent(&self.reading, *accent) {
+                let class = match (mora.high, mora.downstep) {
+                    (true, true) => "high downstep",
+                    (true, false) => "high",
+                    (false, _) => "low",
+                };
+                write!(pitch_accent, "<span class=\"{class}\">{}</span>", mora.text).unwrap();
+            }
+            pitch_accent.push_str("</div>");
+        }
+
         // translation
         let mut translation = String::with_capacity(self.translations.len() * 64);
         translation.push_str("<ul>");
-        for t in self.translations {
-            write!(&mut translation, "<li>{t}</li>").unwrap();
+        if self.senses.is_empty() {
+            for t in self.translations {
+                write!(&mut translation, "<li>{t}</li>").unwrap();
+            }
+        } else {
+            for sense in self.senses {
+                write!(&mut translation, "<li>{}</li>", sense.summary()).unwrap();
+            }
         }
         translation.push_str("</ul>");
 
+        // definitions, grouped by dictionary
+        let mut definitions = String::new();
+        for dictionary in self.definitions {
+            write!(
+                definitions,
+                "<div class=dictionary>{}</div><ul>",
+                dictionary.dictionary
+            )
+            .unwrap();
+            for definition in dictionary.definitions {
+                write!(definitions, "<li>{}</li>", definition.replace('\n', "<br>")).unwrap();
+            }
+            definitions.push_str("</ul>");
+        }
+
         // kanji
         let mut kanji = String::with_capacity('字'.len_utf8() * self.kanji.len() + 32);
         for k in self.kanji {
@@ -137,24 +156,150 @@ impl WordCard {
             id: self.id.to_string(),
             count,
             word_id: self.word_id.to_string(),
-            sentence_id: self.sentence.id.to_string(),
+            sentence_id,
             sentence,
+            extra_sentences,
+            sentence_blank,
+            sentence_cloze,
             word,
+            pitch_accent,
             translation,
+            definitions,
             kanji,
-            generated_at: std::time::UNIX_EPOCH
-                .elapsed()
-                .unwrap()
+            timestamp,
+            generated_at: self
+                .generated_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
                 .as_secs()
                 .to_string(),
         }
     }
 
-    pub fn into_note(self, model: Arc<Model>, template: Arc<Template>, order: u16) -> Note {
+    /// Creates the card's main note and a note for each of the deck's extra card types.
+    pub fn into_notes(self, models: &WordModels, order: u16) -> Vec<Note> {
         let word_id = self.id;
-        let guid = format!("lbr-word-{word_id}");
         let fields = self.into_fields();
-        Note::new(guid, model, vec![template], fields.into_fields()).order(order)
+        let has_blank = !fields.sentence_blank.is_empty();
+        let fields = fields.into_fields();
+
+        let (model, templates) = &models.main;
+        let mut notes = vec![
+            Note::new(
+                format!("lbr-word-{word_id}"),
+                model.clone(),
+                templates.clone(),
+                fields.clone(),
+            )
+            .order(order),
+        ];
+        for (card_type, model, templates) in &models.extra {
+            if !has_blank && matches!(card_type, ExtraCard::Production | ExtraCard::Cloze) {
+                continue;
+            }
+            let guid = format!("lbr-word-{}-{word_id}", card_type.name());
+            notes.push(
+                Note::new(guid, model.clone(), templates.clone(), fields.clone()).order(order),
+            );
+        }
+        notes
+    }
+}
+
+// inserts furigana ruby into the sentence and highlights the word in the given range,
+// returns the formatted sentence and where the highlighted word is within it
+fn format_sentence(
+    sentence: Sentence,
+    word_range: &Range<usize>,
+) -> (String, Option<Range<usize>>) {
+    let mut sentence_idx = 0;
+    let sentence_text = &sentence.sentence;
+    // there's a lot of fluff added so we just estimate the capacity to x4...
+    let mut formatted = String::with_capacity(sentence_text.len() * 4);
+    // process sentence words in order of appearance
+    let mut sentence_words = sentence.words;
+    sentence_words.sort_unstable_by_key(|sw| sw.idx_start);
+    // where the card's word is within the processed sentence
+    let mut highlighted = None;
+    for sw in sentence_words {
+        // insert stuff that's before this sentence word
+        // sentence words don't cover punctuation etc., so this step is needed
+        // to ensure it's accounted for
+        let sentence_word_start = sw.idx_start as usize;
+        if sentence_idx < sentence_word_start {
+            let push = &sentence_text[sentence_idx..sentence_word_start];
+            formatted.push_str(push);
+            if !push.ends_with(char::is_whitespace) {
+                // empty ruby are used to prevent furigana from running over past where it's supposed to
+                formatted.push_str("[ ]");
+            }
+        }
+        sentence_idx = sentence_word_start;
+
+        // check if this sentence word is the word on the card
+        let in_study_word = word_range.start == sentence_idx;
+        if in_study_word {
+            formatted.push_str("<span id=highlighted>");
+        } else {
+            // all elements are wrapped in spans which are styled so that
+            // word breaks do not happen within words unless absolutely necessary
+            formatted.push_str("<span>");
+        }
+        let word_start = formatted.len();
+
+        // process furigana in order of appearance
+        let mut furigana = sw.furigana;
+        furigana.sort_unstable_by_key(|f| f.range.start);
+        for furigana in furigana {
+            let furigana_start = sentence_word_start + furigana.range.start;
+            let furigana_end = sentence_word_start + furigana.range.end;
+
+            // push stuff before this furigana that wasn't processed yet
+            if sentence_idx < furigana_start {
+                formatted.push_str(&sentence_text[sentence_idx..furigana_start]);
+                formatted.push_str("[ ]");
+            }
+
+            // push stuff covered by this furigana
+            formatted.push_str(&sentence_text[furigana_start..furigana_end]);
+            write!(formatted, "[{}]", furigana.furigana).unwrap();
+            sentence_idx = furigana_end;
+        }
+
+        // insert stuff left over after the furigana
+        let sw_idx_end = sw.idx_end as usize;
+        if sentence_idx < sw_idx_end {
+            let push = &sentence_text[sentence_idx..sw_idx_end];
+            formatted.push_str(push);
+            if !push.ends_with(char::is_whitespace) {
+                formatted.push_str("[ ]");
+            }
+            sentence_idx = sw_idx_end;
+        }
+        if in_study_word {
+            highlighted = Some(word_start..formatted.len());
+        }
+        // close out the word with a "word break opportunity" so that when line breaks are needed
+        // they are placed after words instead of in the middle
+        formatted.push_str("</span><wbr/>");
+    }
+
+    // push stuff left over after processing all sentence words
+    if sentence_idx < sentence_text.len() {
+        formatted.push_str(&sentence_text[sentence_idx..]);
+    }
+
+    (formatted, highlighted)
+}
+
+// formats the timestamp as e.g. 1:02:03 or 02:03
+fn format_timestamp(timestamp: Duration) -> String {
+    let seconds = timestamp.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
     }
 }
 
@@ -164,6 +309,16 @@ pub struct Sentence {
     pub id: i32,
     pub sentence: String,
     pub words: Vec<SentenceWord>,
+    /// When the sentence is spoken, for sentences from subtitles.
+    pub timestamps: Option<Range<Duration>>,
+}
+
+/// An additional example sentence of a card's word.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExtraSentence {
+    /// Where the card's word is in the sentence.
+    pub word_range: Range<usize>,
+    pub sentence: Sentence,
 }
 
 /// Delineates a word within a sentence with furigana, if any.
@@ -196,31 +351,51 @@ pub struct WordFields {
     word_id: String,
     sentence_id: String,
     sentence: String,
+    extra_sentences: String,
+    sentence_blank: String,
+    sentence_cloze: String,
     word: String,
+    pitch_accent: String,
     translation: String,
+    definitions: String,
     kanji: String,
+    timestamp: String,
     generated_at: String,
 }
 
 impl WordFields {
-    // keep in sync with `to_fields`
-    fn fields() -> Vec<Field> {
-        vec![
-            Field::new("id".to_string()),
-            // count should be the 1th field
-            // as the model sets this as the sort field
-            Field::new("count".to_string()),
-            Field::new("word_id".to_string()),
-            Field::new("sentence_id".to_string()),
-            Field::new("sentence".to_string()),
-            Field::new("word".to_string()),
-            Field::new("translation".to_string()),
-            Field::new("kanji".to_string()),
-            Field::new("generated_at".to_string()),
-        ]
+    // keep in sync with `into_fields`
+    pub const NAMES: [&str; 15] = [
+        "id",
+        // count should be the 1th field
+        // as the model sets this as the sort field
+        "count",
+        "word_id",
+        "sentence_id",
+        "sentence",
+        "word",
+        "translation",
+        "kanji",
+        "generated_at",
+        "timestamp",
+        "sentence_blank",
+        "sentence_cloze",
+        "extra_sentences",
+        "pitch_accent",
+        "definitions",
+    ];
+
+    /// Renders the card's question and answer the same way Anki does.
+    pub fn render(self, note_type: &NoteType) -> RenderedCard {
+        let values = self.into_fields();
+        let fields = Self::NAMES
+            .into_iter()
+            .zip(values.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+        note_type.render(&fields)
     }
 
-    // keep in sync with `fields`
+    // keep in sync with `NAMES`
     fn into_fields(self) -> Vec<String> {
         vec![
             self.id,
@@ -232,20 +407,17 @@ impl WordFields {
             self.translation,
             self.kanji,
             self.generated_at,
+            self.timestamp,
+            self.sentence_blank,
+            self.sentence_cloze,
+            self.extra_sentences,
+            self.pitch_accent,
+            self.definitions,
         ]
     }
 }
 
-/// Globally unique anki model ID. Randomly chosen.
-const LBR_WORD_ANKI_MODEL_ID: i64 = -2108777964;
-pub fn create_model() -> Model {
-    let fields = WordFields::fields();
-    Model::new(
-        LBR_WORD_ANKI_MODEL_ID,
-        "lbr-word".to_string(),
-        fields,
-        1,
-        r#"
+const CSS: &str = r#"
 .card {
     text-align: center;
     background-color: Linen;
@@ -274,6 +446,32 @@ span {
     display: inline-block;
     text-align: left;
 }
+#timestamp {
+    font-size: 1rem;
+    color: gray;
+}
+#definitions {
+    font-size: 1.25rem;
+    display: inline-block;
+    text-align: left;
+}
+#definitions .dictionary {
+    font-size: 1rem;
+    color: gray;
+}
+#extra_sentences {
+    font-size: 1.5rem;
+}
+.pitch-accent .high {
+    border-top: 2px solid currentColor;
+}
+.pitch-accent .downstep {
+    border-right: 2px solid currentColor;
+}
+.cloze {
+    color: blue;
+    font-weight: bold;
+}
 
 ruby rt {
     display: none;
@@ -281,24 +479,15 @@ ruby rt {
 #answer ruby rt {
     display: revert;
 }
-"#
-        .to_string(),
-        ModelType::Standard,
-    )
-}
+"#;
 
-const LBR_WORD_ANKI_TEMPLATE_ID: i64 = -911618187;
-pub fn create_template() -> Template {
-    Template::new(
-        LBR_WORD_ANKI_TEMPLATE_ID,
-        "lbr-word".to_string(),
-        r#"
+const QUESTION_TEMPLATE: &str = r#"
 <div id=sentence>
     {{furigana:sentence}}
 </div>
-"#
-        .to_string(),
-        r#"
+"#;
+
+const ANSWER_TEMPLATE: &str = r#"
 <div id=answer>
     <div id=sentence>
         {{furigana:sentence}}
@@ -309,6 +498,11 @@ pub fn create_template() -> Template {
     <div id=word>
         {{furigana:word}}
     </div>
+{{#pitch_accent}}
+    <div id=pitch_accent>
+        {{pitch_accent}}
+    </div>
+{{/pitch_accent}}
 {{#kanji}}
     <div>
         <div id=kanji>
@@ -316,15 +510,266 @@ pub fn create_template() -> Template {
         </div>
     </div>
 {{/kanji}}
+    <div>
+        <div id=translation>
+            {{translation}}
+        </div>
+    </div>
+{{#definitions}}
+    <div>
+        <div id=definitions>
+            {{definitions}}
+        </div>
+    </div>
+{{/definitions}}
+{{#timestamp}}
+    <div id=timestamp>
+        {{timestamp}}
+    </div>
+{{/timestamp}}
+{{#extra_sentences}}
+    <hr>
+    <div id=extra_sentences>
+        {{furigana:extra_sentences}}
+    </div>
+{{/extra_sentences}}
+</div>
+"#;
+
+const READING_QUESTION_TEMPLATE: &str = r#"
+<div id=word>
+    {{kanji:word}}
+</div>
+"#;
+
+const READING_ANSWER_TEMPLATE: &str = r#"
+<div id=answer>
+    <div id=word>
+        {{furigana:word}}
+    </div>
+{{#pitch_accent}}
+    <div id=pitch_accent>
+        {{pitch_accent}}
+    </div>
+{{/pitch_accent}}
+
+    <hr>
+
     <div>
         <div id=translation>
             {{translation}}
         </div>
     </div>
 </div>
-"#
-        .to_string(),
-    )
+"#;
+
+const PRODUCTION_QUESTION_TEMPLATE: &str = r#"
+<div>
+    <div id=translation>
+        {{translation}}
+    </div>
+</div>
+<div id=sentence>
+    {{furigana:sentence_blank}}
+</div>
+"#;
+
+const PRODUCTION_ANSWER_TEMPLATE: &str = r#"
+<div id=answer>
+    <div>
+        <div id=translation>
+            {{translation}}
+        </div>
+    </div>
+    <div id=sentence>
+        {{furigana:sentence}}
+    </div>
+
+    <hr>
+
+    <div id=word>
+        {{furigana:word}}
+    </div>
+</div>
+"#;
+
+const CLOZE_QUESTION_TEMPLATE: &str = r#"
+<div id=sentence>
+    {{furigana:cloze:sentence_cloze}}
+</div>
+"#;
+
+const CLOZE_ANSWER_TEMPLATE: &str = r#"
+<div id=answer>
+    <div id=sentence>
+        {{furigana:cloze:sentence_cloze}}
+    </div>
+
+    <hr>
+
+    <div id=word>
+        {{furigana:word}}
+    </div>
+    <div>
+        <div id=translation>
+            {{translation}}
+        </div>
+    </div>
+</div>
+"#;
+
+/// The note type used for word cards in decks that don't have their own.
+pub fn default_note_type() -> NoteType {
+    NoteType {
+        css: CSS.to_string(),
+        templates: vec![CardTemplate {
+            name: "lbr-word".to_string(),
+            question: QUESTION_TEMPLATE.to_string(),
+            answer: ANSWER_TEMPLATE.to_string(),
+        }],
+    }
+}
+
+/// Globally unique anki model ID. Randomly chosen.
+const LBR_WORD_ANKI_MODEL_ID: i64 = -2108777964;
+const LBR_WORD_ANKI_TEMPLATE_ID: i64 = -911618187;
+const LBR_WORD_READING_ANKI_MODEL_ID: i64 = 1418342177;
+const LBR_WORD_READING_ANKI_TEMPLATE_ID: i64 = -1692480906;
+const LBR_WORD_PRODUCTION_ANKI_MODEL_ID: i64 = 2074156389;
+const LBR_WORD_PRODUCTION_ANKI_TEMPLATE_ID: i64 = 597402583;
+const LBR_WORD_CLOZE_ANKI_MODEL_ID: i64 = -1377913062;
+const LBR_WORD_CLOZE_ANKI_TEMPLATE_ID: i64 = 830574741;
+
+/// Which kinds of word cards are created in addition to the main sentence card.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WordCardTypes {
+    /// Cards that show the word and ask for its reading.
+    pub reading: bool,
+    /// Cards that show the translation and the sentence without the word and ask for the word.
+    pub production: bool,
+    /// Cloze deletion cards that hide the word in the sentence.
+    pub cloze: bool,
+}
+
+// each extra card type has its own note so that its reviews are kept separate from the main card
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExtraCard {
+    Reading,
+    Production,
+    Cloze,
+}
+
+impl ExtraCard {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Reading => "reading",
+            Self::Production => "production",
+            Self::Cloze => "cloze",
+        }
+    }
+
+    fn create_model(self) -> (Model, Vec<Arc<Template>>) {
+        let (model_id, template_id, question, answer, model_type) = match self {
+            Self::Reading => (
+                LBR_WORD_READING_ANKI_MODEL_ID,
+                LBR_WORD_READING_ANKI_TEMPLATE_ID,
+                READING_QUESTION_TEMPLATE,
+                READING_ANSWER_TEMPLATE,
+                ModelType::Standard,
+            ),
+            Self::Production => (
+                LBR_WORD_PRODUCTION_ANKI_MODEL_ID,
+                LBR_WORD_PRODUCTION_ANKI_TEMPLATE_ID,
+                PRODUCTION_QUESTION_TEMPLATE,
+                PRODUCTION_ANSWER_TEMPLATE,
+                ModelType::Standard,
+            ),
+            Self::Cloze => (
+                LBR_WORD_CLOZE_ANKI_MODEL_ID,
+                LBR_WORD_CLOZE_ANKI_TEMPLATE_ID,
+                CLOZE_QUESTION_TEMPLATE,
+                CLOZE_ANSWER_TEMPLATE,
+                ModelType::Cloze,
+            ),
+        };
+        let name = format!("lbr-word-{}", self.name());
+        let note_type = NoteType {
+            css: CSS.to_string(),
+            templates: vec![CardTemplate {
+                name: name.clone(),
+                question: question.to_string(),
+                answer: answer.to_string(),
+            }],
+        };
+        (
+            note_type.create_model(model_id, &name, &WordFields::NAMES, model_type),
+            note_type.create_templates(template_id),
+        )
+    }
+}
+
+/// The models and templates of a deck's word notes.
+pub struct WordModels {
+    main: (Arc<Model>, Vec<Arc<Template>>),
+    extra: Vec<(ExtraCard, Arc<Model>, Vec<Arc<Template>>)>,
+}
+
+/// Creates the models for the deck's main word notes and the enabled extra card types.
+pub fn create_models(
+    deck_name: &str,
+    note_type: Option<&CustomNoteType>,
+    card_types: WordCardTypes,
+) -> WordModels {
+    let (model, templates) = create_model(deck_name, note_type);
+    let extra = [
+        (card_types.reading, ExtraCard::Reading),
+        (card_types.production, ExtraCard::Production),
+        (card_types.cloze, ExtraCard::Cloze),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, card_type)| {
+        let (model, templates) = card_type.create_model();
+        (card_type, Arc::new(model), templates)
+    })
+    .collect();
+    WordModels {
+        main: (Arc::new(model), templates),
+        extra,
+    }
+}
+
+// creates the model and templates for the deck's own note type, or the default one
+fn create_model(
+    deck_name: &str,
+    note_type: Option<&CustomNoteType>,
+) -> (Model, Vec<Arc<Template>>) {
+    match note_type {
+        Some(CustomNoteType {
+            model_id,
+            note_type,
+        }) => (
+            note_type.create_model(
+                *model_id,
+                &format!("lbr-word ({deck_name})"),
+                &WordFields::NAMES,
+                ModelType::Standard,
+            ),
+            note_type.create_templates(model_id.wrapping_add(1)),
+        ),
+        None => {
+            let note_type = default_note_type();
+            (
+                note_type.create_model(
+                    LBR_WORD_ANKI_MODEL_ID,
+                    "lbr-word",
+                    &WordFields::NAMES,
+                    ModelType::Standard,
+                ),
+                note_type.create_templates(LBR_WORD_ANKI_TEMPLATE_ID),
+            )
+        }
+    }
 }
 
 #[cfg(test)]
@@ -342,6 +787,8 @@ mod test {
                 range: 0..3,
                 furigana: "ねこ".to_string(),
             }],
+            reading: "ねこ".to_string(),
+            pitch_accents: vec![1],
             word_sentences: 1,
             sentence: Sentence {
                 id: 1,
@@ -385,12 +832,25 @@ mod test {
                         furigana: vec![],
                     },
                 ],
+                timestamps: None,
             },
             translations: vec!["Cat".to_string()],
+            senses: vec![WordSense {
+                glosses: vec!["cat".to_string()],
+                parts_of_speech: vec!["n".to_string()],
+                misc: vec!["word usually written using kana alone".to_string()],
+                ..Default::default()
+            }],
+            definitions: vec![WordDefinitions {
+                dictionary: "大辞林".to_string(),
+                definitions: vec!["ネコ科の哺乳類。\n愛玩用に飼われる。".to_string()],
+            }],
             kanji: vec![WordKanji {
                 chara: "猫".to_string(),
                 name: Some("Cat".to_string()),
             }],
+            extra_sentences: vec![],
+            generated_at: UNIX_EPOCH,
         };
 
         let fields = card.into_fields();
@@ -398,5 +858,34 @@ mod test {
             fields.sentence,
             "吾[わが]輩[はい]は[ ]<span id=highlighted>猫[ねこ]</span>で[ ]ある[ ]"
         );
+        assert!(
+            fields
+                .sentence_blank
+                .contains("<span id=highlighted>＿＿＿</span>")
+        );
+        assert!(!fields.sentence_blank.contains("猫"));
+        assert!(
+            fields
+                .sentence_cloze
+                .contains("<span id=highlighted>{{c1::猫[ねこ]}}</span>")
+        );
+        assert_eq!(
+            fields.pitch_accent,
+            r#"<div class=pitch-accent><span class="high downstep">ね</span><span class="low">こ</span></div>"#
+        );
+        assert_eq!(
+            fields.translation,
+            "<ul><li>(n) cat; (word usually written using kana alone)</li></ul>"
+        );
+        assert_eq!(
+            fields.definitions,
+            "<div class=dictionary>大辞林</div><ul><li>ネコ科の哺乳類。<br>愛玩用に飼われる。</li></ul>"
+        );
+    }
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(format_timestamp(Duration::from_millis(123_456)), "02:03");
+        assert_eq!(format_timestamp(Duration::from_secs(3723)), "1:02:03");
     }
 }
@@ -1,6 +1,6 @@
 //! Contains functionality related to lbr_core.
 
-use crate::{StandardisedReading, is_kanji, standardise_reading};
+use crate::{StandardisedReading, deinflector, is_kanji, standardise_reading};
 use ichiran::{Alternative, WordInfo};
 use lbr_core::ichiran_types::{self as it, Segment};
 use std::{
@@ -8,6 +8,9 @@ use std::{
     ops::{Not, Range},
 };
 
+/// Counters that ichiran includes in the dictionary forms of counted words, such as 人目 and 年間.
+const COUNTERS: &[&str] = &["目", "間"];
+
 /// Converts ichiran segments to lbr's format.
 ///
 /// # Panics
@@ -121,50 +124,40 @@ fn process_word_info(
     current_idx: usize,
 ) -> Option<Range<usize>> {
     let mut word = wi.text;
-    let mut reading = wi.reading;
+    let reading = wi.reading;
     let mut reading_hiragana = replace_invisible_characters(&wi.kana);
-    let jp_equivalent = lbr_core::find_jp_equivalent(remaining_text, &word)
-        .or_else(|| {
-            // in some cases such as 言ってる ichiran will segment it as 言って いる
-            // including the い which is not present in the original text
-            // searching for "る" in the entire rest of the sentence is a little risky so we'll
-            // only accept it if it's right at the start
-            if word == "いる" {
-                let jp_equivalent = lbr_core::find_jp_equivalent(remaining_text, "る")?;
-                if jp_equivalent.0 == 0 {
-                    word = "る".to_string();
-                    reading = "る".to_string();
-                    reading_hiragana = "る".to_string();
-                    return Some(jp_equivalent);
+    let mut contraction = None;
+    let jp_equivalent = lbr_core::find_jp_equivalent(remaining_text, &word).or_else(|| {
+        // ichiran segments contractions such as 言ってる as 言って いる and 責められちゃう as 責められて ちゃう,
+        // including kana that are not present in the original text,
+        // so we'll try the contracted forms of the word instead
+        // searching for a short form in the entire rest of the sentence is a little risky so we'll
+        // only accept it if it's right at the start
+        deinflector::contracted_forms(&word, remaining_text)
+            .into_iter()
+            .find_map(|(contracted, inflection)| {
+                let jp_equivalent = lbr_core::find_jp_equivalent(remaining_text, &contracted)?;
+                if jp_equivalent.0 != 0 {
+                    return None;
                 }
-            }
-            None
-        })
-        .or_else(|| {
-            // in some cases such as 責められちゃう ichiran will segment it as 責められて　ちゃう,
-            // including the て which is not present in the original text
-            // so we'll try without it
-            if word.ends_with('て') {
-                return None;
-            }
-            let word_chars = word.chars().count();
-            let word_without_last = word.chars().take(word_chars - 1).collect::<String>();
-            let jp_equivalent = lbr_core::find_jp_equivalent(remaining_text, &word_without_last)?;
-            word = word_without_last;
-
-            let reading_chars = reading.chars().count();
-            let reading_without_last = reading.chars().take(reading_chars - 1).collect::<String>();
-            reading = reading_without_last;
-
-            let kana_chars = reading_hiragana.chars().count();
-            let kana_without_last = reading_hiragana
-                .chars()
-                .take(kana_chars - 1)
-                .collect::<String>();
-            reading_hiragana = kana_without_last;
-
-            Some(jp_equivalent)
-        });
+                reading_hiragana = if let Some(removed) = word.strip_suffix(&contracted) {
+                    reading_hiragana
+                        .strip_prefix(removed)
+                        .unwrap_or(&reading_hiragana)
+                        .to_string()
+                } else if let Some(removed) = word.strip_prefix(&contracted) {
+                    reading_hiragana
+                        .strip_suffix(removed)
+                        .unwrap_or(&reading_hiragana)
+                        .to_string()
+                } else {
+                    reading_hiragana.clone()
+                };
+                word = contracted;
+                contraction = Some(inflection);
+                Some(jp_equivalent)
+            })
+    });
     let Some((start_idx, len)) = jp_equivalent else {
         tracing::warn!("Failed to find {word} in text {remaining_text}");
         return None;
@@ -216,10 +209,27 @@ fn process_word_info(
                         })
                 })
         })
+    })
+    .or_else(|| {
+        // try deinflecting the word ourselves
+        try_get_deinflected_word_id(seq, word_in_text, &reading_hiragana, ichiran_word_to_id)
     });
     if word_id.is_none() {
         tracing::warn!("Failed to find word_id for {word}");
     }
+    let (word_id, dictionary_form) = word_id.unzip();
+    let inflections = dictionary_form
+        .and_then(|dictionary_form| {
+            deinflector::deinflect(word_in_text)
+                .into_iter()
+                .find(|d| d.dictionary_form == dictionary_form)
+        })
+        .map(|d| d.inflections)
+        .unwrap_or_default()
+        .into_iter()
+        .chain(contraction)
+        .map(|i| i.description().to_string())
+        .collect();
 
     let meanings = word_id
         // get from map
@@ -257,6 +267,7 @@ fn process_word_info(
         word,
         reading_hiragana,
         meanings,
+        inflections,
     };
     match new_segments.entry(segment_range.clone()) {
         Entry::Occupied(mut new_segment) => {
@@ -312,13 +323,14 @@ fn parse_ichiran_reading(
     }
 }
 
+// returns the word id and the dictionary form of the word
 fn try_get_word_id(
     seq: i32,
     word_in_text: &str,
     ichiran_reading: &str,
     ichiran_word_to_id: &HashMap<(i32, String, String), i32>,
     kanji_to_readings: &HashMap<String, Vec<String>>,
-) -> Option<i32> {
+) -> Option<(i32, String)> {
     let (dictionary_form, reading_standard) = parse_ichiran_reading(word_in_text, ichiran_reading)?;
     tracing::info!(
         "trying {} {} {}",
@@ -332,6 +344,7 @@ fn try_get_word_id(
             dictionary_form.clone(),
             reading_standard.standardised.clone(),
         ))
+        // numbers and counters are not inflections, so the deinflector can't recover the dictionary form for these
         .or_else(|| {
             if dictionary_form.chars().any(is_digit).not() {
                 return None;
@@ -339,27 +352,12 @@ fn try_get_word_id(
             // sometimes the "dictionary form" we find includes numbers before the actual word,
             // for example for ２１度, 4日 etc.
             // so if we fail to find the word we'll try to remove the numbers and try again...
-            let mut dictionary_form_without_numbers = String::new();
-            let mut reading_without_numbers = String::new();
-            if let Some(segmentation) = &furigana::map(
+            let (dictionary_form_without_numbers, reading_without_numbers) = strip_numbers(
                 &dictionary_form,
                 &reading_standard.standardised,
                 kanji_to_readings,
-            )
-            .iter()
-            .max_by_key(|f| f.accuracy)
-            {
-                for furigana in segmentation
-                    .furigana
-                    .iter()
-                    // skip digit sections
-                    .skip_while(|f| f.segment.chars().all(is_digit))
-                {
-                    dictionary_form_without_numbers.push_str(furigana.segment);
-                    reading_without_numbers.push_str(furigana.furigana.unwrap_or(furigana.segment));
-                }
-            }
-
+                false,
+            );
             tracing::info!(
                 "trying without numbers {} {} {}",
                 seq,
@@ -373,36 +371,18 @@ fn try_get_word_id(
             ))
         })
         .or_else(|| {
-            let counters_char = &['目', '間'];
-            let counters = &["目", "間"];
-            if !dictionary_form.ends_with(counters_char) {
+            if !COUNTERS.iter().any(|c| dictionary_form.ends_with(c)) {
                 return None;
             }
             // same as above but without the counter at the end to account for 人目 and　年間 etc...
-            let mut dictionary_form_without_numbers = String::new();
-            let mut reading_without_numbers = String::new();
-            if let Some(segmentation) = furigana::map(
+            let (dictionary_form_without_numbers, reading_without_numbers) = strip_numbers(
                 &dictionary_form,
                 &reading_standard.standardised,
                 kanji_to_readings,
-            )
-            .iter()
-            .max_by_key(|f| f.accuracy)
-            {
-                for furigana in segmentation
-                    .furigana
-                    .iter()
-                    // skip digit sections
-                    .skip_while(|f| f.segment.chars().all(is_digit))
-                    .take_while(|f| !counters.contains(&f.segment))
-                {
-                    dictionary_form_without_numbers.push_str(furigana.segment);
-                    reading_without_numbers.push_str(furigana.furigana.unwrap_or(furigana.segment));
-                }
-            }
-
+                true,
+            );
             tracing::info!(
-                "trying trying without numbers and counters {} {} {}",
+                "trying without numbers and counters {} {} {}",
                 seq,
                 dictionary_form_without_numbers,
                 reading_without_numbers,
@@ -413,7 +393,63 @@ fn try_get_word_id(
                 reading_without_numbers,
             ))
         })
-        .copied()
+        .map(|word_id| (*word_id, dictionary_form))
+}
+
+// removes the numbers at the start of the word and its reading,
+// and the counter at the end if `strip_counter` is set
+fn strip_numbers(
+    dictionary_form: &str,
+    reading: &str,
+    kanji_to_readings: &HashMap<String, Vec<String>>,
+    strip_counter: bool,
+) -> (String, String) {
+    let mut dictionary_form_without_numbers = String::new();
+    let mut reading_without_numbers = String::new();
+    if let Some(segmentation) = furigana::map(dictionary_form, reading, kanji_to_readings)
+        .iter()
+        .max_by_key(|f| f.accuracy)
+    {
+        for furigana in segmentation
+            .furigana
+            .iter()
+            // skip digit sections
+            .skip_while(|f| f.segment.chars().all(is_digit))
+            .take_while(|f| !(strip_counter && COUNTERS.contains(&f.segment)))
+        {
+            dictionary_form_without_numbers.push_str(furigana.segment);
+            reading_without_numbers.push_str(furigana.furigana.unwrap_or(furigana.segment));
+        }
+    }
+    (dictionary_form_without_numbers, reading_without_numbers)
+}
+
+// returns the word id and the dictionary form of the word
+fn try_get_deinflected_word_id(
+    seq: i32,
+    word_in_text: &str,
+    reading_hiragana: &str,
+    ichiran_word_to_id: &HashMap<(i32, String, String), i32>,
+) -> Option<(i32, String)> {
+    deinflector::deinflect_with_reading(word_in_text, reading_hiragana)
+        .into_iter()
+        .filter(|(d, _)| d.word_types.is_dictionary_form())
+        .find_map(|(d, reading)| {
+            let reading_standard = standardise_reading(&reading);
+            tracing::info!(
+                "trying deinflected {} {} {}",
+                seq,
+                d.dictionary_form,
+                reading_standard.standardised,
+            );
+            ichiran_word_to_id
+                .get(&(
+                    seq,
+                    d.dictionary_form.clone(),
+                    reading_standard.standardised,
+                ))
+                .map(|word_id| (*word_id, d.dictionary_form))
+        })
 }
 
 fn replace_invisible_characters(s: &str) -> String {
@@ -0,0 +1,662 @@
+//! Contains a rule-driven deinflector that maps conjugated Japanese verbs and adjectives to their potential dictionary forms.
+
+use std::{collections::VecDeque, fmt::Display};
+
+/// A single conjugation step from a word to its inflected form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Inflection {
+    Negative,
+    Past,
+    Te,
+    Polite,
+    Potential,
+    Passive,
+    PotentialOrPassive,
+    Causative,
+    CausativePassive,
+    Volitional,
+    Imperative,
+    Conditional,
+    Tara,
+    Tari,
+    Tai,
+    Adverbial,
+    Noun,
+    /// 〜ている
+    Progressive,
+    /// 〜てる, contraction of 〜ている
+    ProgressiveContraction,
+    /// 〜てしまう
+    Shimau,
+    /// 〜ちゃう/〜じゃう, contraction of 〜てしまう
+    ShimauContraction,
+    /// 〜ておく
+    Oku,
+    /// 〜とく/〜どく, contraction of 〜ておく
+    OkuContraction,
+}
+
+impl Inflection {
+    /// A short human-readable description of the inflection.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Negative => "negative",
+            Self::Past => "past",
+            Self::Te => "te-form",
+            Self::Polite => "polite",
+            Self::Potential => "potential",
+            Self::Passive => "passive",
+            Self::PotentialOrPassive => "potential or passive",
+            Self::Causative => "causative",
+            Self::CausativePassive => "causative-passive",
+            Self::Volitional => "volitional",
+            Self::Imperative => "imperative",
+            Self::Conditional => "conditional",
+            Self::Tara => "tara-conditional",
+            Self::Tari => "tari-form",
+            Self::Tai => "desiderative (tai)",
+            Self::Adverbial => "adverbial",
+            Self::Noun => "noun (sa)",
+            Self::Progressive => "progressive (teiru)",
+            Self::ProgressiveContraction => "progressive (teru)",
+            Self::Shimau => "completion (teshimau)",
+            Self::ShimauContraction => "completion (chau)",
+            Self::Oku => "preparation (teoku)",
+            Self::OkuContraction => "preparation (toku)",
+        }
+    }
+}
+
+impl Display for Inflection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// A set of word types, used to determine which rules can be chained together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WordTypes(u16);
+
+impl WordTypes {
+    /// Ichidan verb.
+    pub const V1: Self = Self(1 << 0);
+    /// Godan verb.
+    pub const V5: Self = Self(1 << 1);
+    /// する verb.
+    pub const VS: Self = Self(1 << 2);
+    /// 来る verb.
+    pub const VK: Self = Self(1 << 3);
+    /// い-adjective.
+    pub const ADJ_I: Self = Self(1 << 4);
+    /// The ます form.
+    pub const MASU: Self = Self(1 << 5);
+    /// The て form.
+    pub const TE: Self = Self(1 << 6);
+    /// The た form.
+    pub const TA: Self = Self(1 << 7);
+    /// A form that cannot be conjugated further.
+    pub const FINAL: Self = Self(1 << 8);
+    /// Any word type, used for the text that is being deinflected.
+    pub const ANY: Self = Self(u16::MAX);
+
+    const fn or(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns true if the word types include a dictionary form word type.
+    pub fn is_dictionary_form(self) -> bool {
+        self.intersects(
+            Self::V1
+                .or(Self::V5)
+                .or(Self::VS)
+                .or(Self::VK)
+                .or(Self::ADJ_I),
+        )
+    }
+}
+
+/// A potential dictionary form for a conjugated word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deinflection {
+    /// The potential dictionary form.
+    pub dictionary_form: String,
+    /// The word types the dictionary form could be.
+    pub word_types: WordTypes,
+    /// The inflections that were applied to the dictionary form to produce the original word,
+    /// starting from the dictionary form.
+    pub inflections: Vec<Inflection>,
+    // indices of the applied rules, used to pair up written forms and readings
+    rules: Vec<usize>,
+}
+
+struct Rule {
+    // the ending of the inflected form
+    inflected: &'static str,
+    // the ending of the deinflected form
+    deinflected: &'static str,
+    // the word types that the inflected form must have for the rule to apply
+    types_in: WordTypes,
+    // the word types of the deinflected form
+    types_out: WordTypes,
+    inflection: Inflection,
+}
+
+const fn rule(
+    inflected: &'static str,
+    deinflected: &'static str,
+    types_in: WordTypes,
+    types_out: WordTypes,
+    inflection: Inflection,
+) -> Rule {
+    Rule {
+        inflected,
+        deinflected,
+        types_in,
+        types_out,
+        inflection,
+    }
+}
+
+use Inflection as I;
+use WordTypes as T;
+
+const V1: T = T::V1;
+const V5: T = T::V5;
+const VS: T = T::VS;
+const VK: T = T::VK;
+const ADJ_I: T = T::ADJ_I;
+const MASU: T = T::MASU;
+const TE: T = T::TE;
+const TA: T = T::TA;
+const FINAL: T = T::FINAL;
+
+#[rustfmt::skip]
+const RULES: &[Rule] = &[
+    // negative
+    rule("ない", "る", ADJ_I, V1, I::Negative),
+    rule("わない", "う", ADJ_I, V5, I::Negative),
+    rule("かない", "く", ADJ_I, V5, I::Negative),
+    rule("がない", "ぐ", ADJ_I, V5, I::Negative),
+    rule("さない", "す", ADJ_I, V5, I::Negative),
+    rule("たない", "つ", ADJ_I, V5, I::Negative),
+    rule("なない", "ぬ", ADJ_I, V5, I::Negative),
+    rule("ばない", "ぶ", ADJ_I, V5, I::Negative),
+    rule("まない", "む", ADJ_I, V5, I::Negative),
+    rule("らない", "る", ADJ_I, V5, I::Negative),
+    rule("しない", "する", ADJ_I, VS, I::Negative),
+    rule("こない", "くる", ADJ_I, VK, I::Negative),
+    rule("来ない", "来る", ADJ_I, VK, I::Negative),
+    rule("くない", "い", ADJ_I, ADJ_I, I::Negative),
+    rule("ません", "ます", MASU, MASU, I::Negative),
+    // past
+    rule("た", "る", TA, V1, I::Past),
+    rule("った", "う", TA, V5, I::Past),
+    rule("った", "つ", TA, V5, I::Past),
+    rule("った", "る", TA, V5, I::Past),
+    rule("いた", "く", TA, V5, I::Past),
+    rule("いだ", "ぐ", TA, V5, I::Past),
+    rule("した", "す", TA, V5, I::Past),
+    rule("んだ", "ぬ", TA, V5, I::Past),
+    rule("んだ", "ぶ", TA, V5, I::Past),
+    rule("んだ", "む", TA, V5, I::Past),
+    rule("行った", "行く", TA, V5, I::Past),
+    rule("いった", "いく", TA, V5, I::Past),
+    rule("した", "する", TA, VS, I::Past),
+    rule("きた", "くる", TA, VK, I::Past),
+    rule("来た", "来る", TA, VK, I::Past),
+    rule("かった", "い", TA, ADJ_I, I::Past),
+    rule("ました", "ます", TA, MASU, I::Past),
+    rule("ませんでした", "ません", TA, MASU, I::Past),
+    // te
+    rule("て", "る", TE, V1, I::Te),
+    rule("って", "う", TE, V5, I::Te),
+    rule("って", "つ", TE, V5, I::Te),
+    rule("って", "る", TE, V5, I::Te),
+    rule("いて", "く", TE, V5, I::Te),
+    rule("いで", "ぐ", TE, V5, I::Te),
+    rule("して", "す", TE, V5, I::Te),
+    rule("んで", "ぬ", TE, V5, I::Te),
+    rule("んで", "ぶ", TE, V5, I::Te),
+    rule("んで", "む", TE, V5, I::Te),
+    rule("行って", "行く", TE, V5, I::Te),
+    rule("いって", "いく", TE, V5, I::Te),
+    rule("して", "する", TE, VS, I::Te),
+    rule("きて", "くる", TE, VK, I::Te),
+    rule("来て", "来る", TE, VK, I::Te),
+    rule("くて", "い", TE, ADJ_I, I::Te),
+    rule("まして", "ます", TE, MASU, I::Te),
+    // polite
+    rule("ます", "る", MASU, V1, I::Polite),
+    rule("います", "う", MASU, V5, I::Polite),
+    rule("きます", "く", MASU, V5, I::Polite),
+    rule("ぎます", "ぐ", MASU, V5, I::Polite),
+    rule("します", "す", MASU, V5, I::Polite),
+    rule("ちます", "つ", MASU, V5, I::Polite),
+    rule("にます", "ぬ", MASU, V5, I::Polite),
+    rule("びます", "ぶ", MASU, V5, I::Polite),
+    rule("みます", "む", MASU, V5, I::Polite),
+    rule("ります", "る", MASU, V5, I::Polite),
+    rule("します", "する", MASU, VS, I::Polite),
+    rule("きます", "くる", MASU, VK, I::Polite),
+    rule("来ます", "来る", MASU, VK, I::Polite),
+    // potential
+    rule("られる", "る", V1, V1, I::PotentialOrPassive),
+    rule("れる", "る", V1, V1, I::Potential),
+    rule("える", "う", V1, V5, I::Potential),
+    rule("ける", "く", V1, V5, I::Potential),
+    rule("げる", "ぐ", V1, V5, I::Potential),
+    rule("せる", "す", V1, V5, I::Potential),
+    rule("てる", "つ", V1, V5, I::Potential),
+    rule("ねる", "ぬ", V1, V5, I::Potential),
+    rule("べる", "ぶ", V1, V5, I::Potential),
+    rule("める", "む", V1, V5, I::Potential),
+    rule("れる", "る", V1, V5, I::Potential),
+    rule("できる", "する", V1, VS, I::Potential),
+    rule("こられる", "くる", V1, VK, I::PotentialOrPassive),
+    rule("来られる", "来る", V1, VK, I::PotentialOrPassive),
+    rule("これる", "くる", V1, VK, I::Potential),
+    rule("来れる", "来る", V1, VK, I::Potential),
+    // passive
+    rule("われる", "う", V1, V5, I::Passive),
+    rule("かれる", "く", V1, V5, I::Passive),
+    rule("がれる", "ぐ", V1, V5, I::Passive),
+    rule("される", "す", V1, V5, I::Passive),
+    rule("たれる", "つ", V1, V5, I::Passive),
+    rule("なれる", "ぬ", V1, V5, I::Passive),
+    rule("ばれる", "ぶ", V1, V5, I::Passive),
+    rule("まれる", "む", V1, V5, I::Passive),
+    rule("られる", "る", V1, V5, I::Passive),
+    rule("される", "する", V1, VS, I::Passive),
+    // causative
+    rule("させる", "る", V1, V1, I::Causative),
+    rule("わせる", "う", V1, V5, I::Causative),
+    rule("かせる", "く", V1, V5, I::Causative),
+    rule("がせる", "ぐ", V1, V5, I::Causative),
+    rule("させる", "す", V1, V5, I::Causative),
+    rule("たせる", "つ", V1, V5, I::Causative),
+    rule("なせる", "ぬ", V1, V5, I::Causative),
+    rule("ばせる", "ぶ", V1, V5, I::Causative),
+    rule("ませる", "む", V1, V5, I::Causative),
+    rule("らせる", "る", V1, V5, I::Causative),
+    rule("させる", "する", V1, VS, I::Causative),
+    rule("こさせる", "くる", V1, VK, I::Causative),
+    rule("来させる", "来る", V1, VK, I::Causative),
+    // short causative-passive, the long form is handled by chaining causative and passive
+    rule("わされる", "う", V1, V5, I::CausativePassive),
+    rule("かされる", "く", V1, V5, I::CausativePassive),
+    rule("がされる", "ぐ", V1, V5, I::CausativePassive),
+    rule("たされる", "つ", V1, V5, I::CausativePassive),
+    rule("なされる", "ぬ", V1, V5, I::CausativePassive),
+    rule("ばされる", "ぶ", V1, V5, I::CausativePassive),
+    rule("まされる", "む", V1, V5, I::CausativePassive),
+    rule("らされる", "る", V1, V5, I::CausativePassive),
+    // volitional
+    rule("よう", "る", FINAL, V1, I::Volitional),
+    rule("おう", "う", FINAL, V5, I::Volitional),
+    rule("こう", "く", FINAL, V5, I::Volitional),
+    rule("ごう", "ぐ", FINAL, V5, I::Volitional),
+    rule("そう", "す", FINAL, V5, I::Volitional),
+    rule("とう", "つ", FINAL, V5, I::Volitional),
+    rule("のう", "ぬ", FINAL, V5, I::Volitional),
+    rule("ぼう", "ぶ", FINAL, V5, I::Volitional),
+    rule("もう", "む", FINAL, V5, I::Volitional),
+    rule("ろう", "る", FINAL, V5, I::Volitional),
+    rule("しよう", "する", FINAL, VS, I::Volitional),
+    rule("こよう", "くる", FINAL, VK, I::Volitional),
+    rule("来よう", "来る", FINAL, VK, I::Volitional),
+    rule("ましょう", "ます", FINAL, MASU, I::Volitional),
+    // imperative
+    rule("ろ", "る", FINAL, V1, I::Imperative),
+    rule("よ", "る", FINAL, V1, I::Imperative),
+    rule("え", "う", FINAL, V5, I::Imperative),
+    rule("け", "く", FINAL, V5, I::Imperative),
+    rule("げ", "ぐ", FINAL, V5, I::Imperative),
+    rule("せ", "す", FINAL, V5, I::Imperative),
+    rule("て", "つ", FINAL, V5, I::Imperative),
+    rule("ね", "ぬ", FINAL, V5, I::Imperative),
+    rule("べ", "ぶ", FINAL, V5, I::Imperative),
+    rule("め", "む", FINAL, V5, I::Imperative),
+    rule("れ", "る", FINAL, V5, I::Imperative),
+    rule("しろ", "する", FINAL, VS, I::Imperative),
+    rule("せよ", "する", FINAL, VS, I::Imperative),
+    rule("こい", "くる", FINAL, VK, I::Imperative),
+    rule("来い", "来る", FINAL, VK, I::Imperative),
+    // conditional
+    rule("れば", "る", FINAL, V1.or(V5), I::Conditional),
+    rule("えば", "う", FINAL, V5, I::Conditional),
+    rule("けば", "く", FINAL, V5, I::Conditional),
+    rule("げば", "ぐ", FINAL, V5, I::Conditional),
+    rule("せば", "す", FINAL, V5, I::Conditional),
+    rule("てば", "つ", FINAL, V5, I::Conditional),
+    rule("ねば", "ぬ", FINAL, V5, I::Conditional),
+    rule("べば", "ぶ", FINAL, V5, I::Conditional),
+    rule("めば", "む", FINAL, V5, I::Conditional),
+    rule("すれば", "する", FINAL, VS, I::Conditional),
+    rule("くれば", "くる", FINAL, VK, I::Conditional),
+    rule("来れば", "来る", FINAL, VK, I::Conditional),
+    rule("ければ", "い", FINAL, ADJ_I, I::Conditional),
+    // tara and tari are formed from the past form
+    rule("たら", "た", FINAL, TA, I::Tara),
+    rule("だら", "だ", FINAL, TA, I::Tara),
+    rule("たり", "た", FINAL, TA, I::Tari),
+    rule("だり", "だ", FINAL, TA, I::Tari),
+    // desiderative
+    rule("たい", "る", ADJ_I, V1, I::Tai),
+    rule("いたい", "う", ADJ_I, V5, I::Tai),
+    rule("きたい", "く", ADJ_I, V5, I::Tai),
+    rule("ぎたい", "ぐ", ADJ_I, V5, I::Tai),
+    rule("したい", "す", ADJ_I, V5, I::Tai),
+    rule("ちたい", "つ", ADJ_I, V5, I::Tai),
+    rule("にたい", "ぬ", ADJ_I, V5, I::Tai),
+    rule("びたい", "ぶ", ADJ_I, V5, I::Tai),
+    rule("みたい", "む", ADJ_I, V5, I::Tai),
+    rule("りたい", "る", ADJ_I, V5, I::Tai),
+    rule("したい", "する", ADJ_I, VS, I::Tai),
+    rule("きたい", "くる", ADJ_I, VK, I::Tai),
+    rule("来たい", "来る", ADJ_I, VK, I::Tai),
+    // adjective forms
+    rule("く", "い", FINAL, ADJ_I, I::Adverbial),
+    rule("さ", "い", FINAL, ADJ_I, I::Noun),
+    // auxiliaries attached to the te form
+    rule("ている", "て", V1, TE, I::Progressive),
+    rule("でいる", "で", V1, TE, I::Progressive),
+    rule("てる", "て", V1, TE, I::ProgressiveContraction),
+    rule("でる", "で", V1, TE, I::ProgressiveContraction),
+    rule("てしまう", "て", V5, TE, I::Shimau),
+    rule("でしまう", "で", V5, TE, I::Shimau),
+    rule("ちゃう", "て", V5, TE, I::ShimauContraction),
+    rule("じゃう", "で", V5, TE, I::ShimauContraction),
+    rule("ておく", "て", V5, TE, I::Oku),
+    rule("でおく", "で", V5, TE, I::Oku),
+    rule("とく", "て", V5, TE, I::OkuContraction),
+    rule("どく", "で", V5, TE, I::OkuContraction),
+];
+
+// guards against pathological inputs, real words rarely have more than a handful of inflections
+const MAX_INFLECTIONS: usize = 8;
+
+/// Returns all potential dictionary forms for the given text along with the inflections that
+/// would produce the text from them.
+/// The text itself is always included as the first result with no inflections.
+/// The results are not checked against a dictionary, so most of them will not be real words.
+pub fn deinflect(text: &str) -> Vec<Deinflection> {
+    let mut results = vec![Deinflection {
+        dictionary_form: text.to_string(),
+        word_types: WordTypes::ANY,
+        inflections: Vec::new(),
+        rules: Vec::new(),
+    }];
+    let mut queue = VecDeque::from([0]);
+    while let Some(idx) = queue.pop_front() {
+        let current = results[idx].clone();
+        if current.inflections.len() >= MAX_INFLECTIONS {
+            continue;
+        }
+        for (rule_idx, rule) in RULES.iter().enumerate() {
+            if !current.word_types.intersects(rule.types_in) {
+                continue;
+            }
+            let Some(stem) = current.dictionary_form.strip_suffix(rule.inflected) else {
+                continue;
+            };
+            if stem.is_empty() && rule.deinflected.chars().count() < 2 {
+                // a lone kana ending is not a word
+                continue;
+            }
+            let dictionary_form = format!("{stem}{}", rule.deinflected);
+            if results
+                .iter()
+                .any(|r| r.dictionary_form == dictionary_form && r.word_types == rule.types_out)
+            {
+                continue;
+            }
+            let mut inflections = Vec::with_capacity(current.inflections.len() + 1);
+            inflections.push(rule.inflection);
+            inflections.extend_from_slice(&current.inflections);
+            let mut rules = Vec::with_capacity(current.rules.len() + 1);
+            rules.push(rule_idx);
+            rules.extend_from_slice(&current.rules);
+            results.push(Deinflection {
+                dictionary_form,
+                word_types: rule.types_out,
+                inflections,
+                rules,
+            });
+            queue.push_back(results.len() - 1);
+        }
+    }
+    results
+}
+
+/// Whether the text ends with the ending of any inflected form.
+/// Text that doesn't has no deinflections other than itself, so checking first avoids calling
+/// `deinflect` for every candidate substring of a sentence.
+pub fn has_inflected_ending(text: &str) -> bool {
+    RULES.iter().any(|rule| text.ends_with(rule.inflected))
+}
+
+/// Deinflects the written form and the reading of a word in parallel,
+/// returning pairs of dictionary forms and dictionary form readings that share the same inflections.
+pub fn deinflect_with_reading(word: &str, reading: &str) -> Vec<(Deinflection, String)> {
+    let reading_deinflections = deinflect(reading);
+    deinflect(word)
+        .into_iter()
+        .filter_map(|d| {
+            // prefer the same rules, falling back to the same inflections for rules that only
+            // apply to written forms such as 来る
+            let reading = reading_deinflections
+                .iter()
+                .find(|rd| rd.rules == d.rules)
+                .or_else(|| {
+                    reading_deinflections
+                        .iter()
+                        .find(|rd| rd.inflections == d.inflections && rd.word_types == d.word_types)
+                })?
+                .dictionary_form
+                .clone();
+            Some((d, reading))
+        })
+        .collect()
+}
+
+/// Returns the form a word takes when it is contracted with the te-form before it,
+/// such as the いる in 言っている becoming る in 言ってる
+/// or the て in 食べてしまう disappearing in 食べちゃう.
+/// `text` is the text starting where the word is expected, used to tell ちゃう and とく contractions apart.
+pub fn contracted_forms(word: &str, text: &str) -> Vec<(String, Inflection)> {
+    let mut forms = Vec::new();
+    // the い of いる is dropped
+    if let Some(rest) = word.strip_prefix('い').filter(|rest| !rest.is_empty()) {
+        let is_iru = deinflect(word)
+            .iter()
+            .any(|d| d.dictionary_form == "いる" && d.word_types.intersects(WordTypes::V1));
+        if is_iru {
+            forms.push((rest.to_string(), Inflection::ProgressiveContraction));
+        }
+    }
+    // the te-form ending merges into ちゃう/じゃう or とく/どく
+    if let Some(stem) = word
+        .strip_suffix('て')
+        .or_else(|| word.strip_suffix('で'))
+        .filter(|stem| !stem.is_empty())
+    {
+        let following = text.strip_prefix(stem).and_then(|rest| rest.chars().next());
+        match following {
+            Some('ち' | 'じ') => forms.push((stem.to_string(), Inflection::ShimauContraction)),
+            Some('と' | 'ど') => forms.push((stem.to_string(), Inflection::OkuContraction)),
+            _ => {}
+        }
+    }
+    forms
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn find<'a>(deinflections: &'a [Deinflection], dictionary_form: &str) -> &'a Deinflection {
+        deinflections
+            .iter()
+            .filter(|d| d.word_types.is_dictionary_form())
+            .find(|d| d.dictionary_form == dictionary_form)
+            .unwrap_or_else(|| panic!("{dictionary_form} not found in {deinflections:#?}"))
+    }
+
+    #[test]
+    fn includes_text_itself() {
+        let deinflections = deinflect("食べる");
+        assert_eq!(deinflections[0].dictionary_form, "食べる");
+        assert!(deinflections[0].inflections.is_empty());
+    }
+
+    #[test]
+    fn checks_inflected_endings() {
+        assert!(has_inflected_ending("食べなかった"));
+        assert!(has_inflected_ending("書いて"));
+        assert!(!has_inflected_ending("日本"));
+        assert!(!has_inflected_ending("食べ物"));
+        assert_eq!(deinflect("日本").len(), 1);
+    }
+
+    #[test]
+    fn deinflects_te_form() {
+        let deinflections = deinflect("書いて");
+        assert_eq!(find(&deinflections, "書く").inflections, [I::Te]);
+    }
+
+    #[test]
+    fn deinflects_negative_past() {
+        let deinflections = deinflect("食べなかった");
+        assert_eq!(
+            find(&deinflections, "食べる").inflections,
+            [I::Negative, I::Past]
+        );
+    }
+
+    #[test]
+    fn deinflects_polite_negative_past() {
+        let deinflections = deinflect("読みませんでした");
+        assert_eq!(
+            find(&deinflections, "読む").inflections,
+            [I::Polite, I::Negative, I::Past]
+        );
+    }
+
+    #[test]
+    fn deinflects_potential() {
+        let deinflections = deinflect("話せる");
+        assert_eq!(find(&deinflections, "話す").inflections, [I::Potential]);
+    }
+
+    #[test]
+    fn deinflects_causative_passive() {
+        let deinflections = deinflect("食べさせられた");
+        assert_eq!(
+            find(&deinflections, "食べる").inflections,
+            [I::Causative, I::PotentialOrPassive, I::Past]
+        );
+        let deinflections = deinflect("待たされる");
+        assert_eq!(
+            find(&deinflections, "待つ").inflections,
+            [I::CausativePassive]
+        );
+    }
+
+    #[test]
+    fn deinflects_chau() {
+        let deinflections = deinflect("責められちゃう");
+        assert_eq!(
+            find(&deinflections, "責める").inflections,
+            [I::PotentialOrPassive, I::Te, I::ShimauContraction]
+        );
+        let deinflections = deinflect("死んじゃった");
+        assert_eq!(
+            find(&deinflections, "死ぬ").inflections,
+            [I::Te, I::ShimauContraction, I::Past]
+        );
+    }
+
+    #[test]
+    fn deinflects_teru() {
+        let deinflections = deinflect("言ってる");
+        assert_eq!(
+            find(&deinflections, "言う").inflections,
+            [I::Te, I::ProgressiveContraction]
+        );
+    }
+
+    #[test]
+    fn deinflects_adjectives() {
+        let deinflections = deinflect("高くなかった");
+        assert_eq!(
+            find(&deinflections, "高い").inflections,
+            [I::Negative, I::Past]
+        );
+    }
+
+    #[test]
+    fn deinflects_irregular_verbs() {
+        let deinflections = deinflect("勉強しました");
+        assert_eq!(
+            find(&deinflections, "勉強する").inflections,
+            [I::Polite, I::Past]
+        );
+        let deinflections = deinflect("来なかった");
+        assert_eq!(
+            find(&deinflections, "来る").inflections,
+            [I::Negative, I::Past]
+        );
+    }
+
+    #[test]
+    fn deinflects_reading_in_parallel() {
+        let deinflections = deinflect_with_reading("食べた", "たべた");
+        let (_, reading) = deinflections
+            .iter()
+            .find(|(d, _)| d.dictionary_form == "食べる")
+            .unwrap();
+        assert_eq!(reading, "たべる");
+    }
+
+    #[test]
+    fn pairs_readings_by_rule() {
+        let deinflections = deinflect_with_reading("待った", "まった");
+        let (_, reading) = deinflections
+            .iter()
+            .find(|(d, _)| d.dictionary_form == "待つ")
+            .unwrap();
+        assert_eq!(reading, "まつ");
+        let deinflections = deinflect_with_reading("来た", "きた");
+        let (_, reading) = deinflections
+            .iter()
+            .find(|(d, _)| d.dictionary_form == "来る" && d.word_types == WordTypes::VK)
+            .unwrap();
+        assert_eq!(reading, "くる");
+    }
+
+    #[test]
+    fn finds_contracted_forms() {
+        assert_eq!(
+            contracted_forms("いる", "るから"),
+            [("る".to_string(), I::ProgressiveContraction)]
+        );
+        assert_eq!(
+            contracted_forms("責められて", "責められちゃう"),
+            [("責められ".to_string(), I::ShimauContraction)]
+        );
+        assert_eq!(
+            contracted_forms("書いて", "書いとく"),
+            [("書い".to_string(), I::OkuContraction)]
+        );
+        assert_eq!(
+            contracted_forms("読んで", "読んどいた"),
+            [("読ん".to_string(), I::OkuContraction)]
+        );
+        assert!(contracted_forms("書いて", "書いた").is_empty());
+        assert!(contracted_forms("本", "本").is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+//! Extracts Japanese text from files such as plain text files, Aozora Bunko texts, EPUB books and subtitles.
+
+mod aozora;
+mod epub;
+pub mod subtitles;
+
+use thiserror::Error;
+
+/// Chapters longer than this (in characters) are split into multiple parts
+/// so that each part can be reviewed in a reasonable amount of time.
+pub const MAX_CHAPTER_LENGTH: usize = 5000;
+
+/// A chapter of an imported text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    pub title: Option<String>,
+    /// The text of the chapter with one paragraph per line.
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Aozora,
+    Epub,
+}
+
+impl Format {
+    /// Guesses the format of a file from its name and contents.
+    pub fn detect(filename: &str, data: &[u8]) -> Option<Self> {
+        let extension = filename.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "epub" => Some(Self::Epub),
+            "txt" => {
+                if aozora::is_aozora(&decode_text(data)) {
+                    Some(Self::Aozora)
+                } else {
+                    Some(Self::Text)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Failed to read EPUB archive")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("Failed to read file from EPUB archive")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse XML")]
+    Xml(#[from] xml::reader::Error),
+    #[error("Invalid EPUB: {0}")]
+    InvalidEpub(String),
+}
+
+/// Extracts the chapters from the file's contents.
+/// Chapters that are too long are split into parts of at most `MAX_CHAPTER_LENGTH` characters at paragraph boundaries.
+pub fn import(format: Format, data: &[u8]) -> Result<Vec<Chapter>, ImportError> {
+    let chapters = match format {
+        Format::Text => vec![Chapter {
+            title: None,
+            text: normalise_paragraphs(&decode_text(data)),
+        }],
+        Format::Aozora => aozora::chapters(&decode_text(data)),
+        Format::Epub => epub::chapters(data)?,
+    };
+    let chapters = chapters
+        .into_iter()
+        .filter(|c| !c.text.is_empty())
+        .flat_map(split_chapter)
+        .collect();
+    Ok(chapters)
+}
+
+/// Decodes UTF-8 text, falling back to Shift_JIS which is used by Aozora Bunko.
+pub fn decode_text(data: &[u8]) -> String {
+    match std::str::from_utf8(data) {
+        Ok(text) => text.trim_start_matches('\u{feff}').to_string(),
+        Err(_) => {
+            let (text, _, _) = encoding_rs::SHIFT_JIS.decode(data);
+            text.into_owned()
+        }
+    }
+}
+
+/// Trims each line and removes empty ones.
+fn normalise_paragraphs(text: &str) -> String {
+    text.lines()
+        .map(|l| l.trim_matches(|c: char| c.is_whitespace()))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn split_chapter(chapter: Chapter) -> Vec<Chapter> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut part_length = 0;
+    for paragraph in chapter.text.lines() {
+        let paragraph_length = paragraph.chars().count();
+        if part_length > 0 && part_length + paragraph_length > MAX_CHAPTER_LENGTH {
+            parts.push(std::mem::take(&mut part));
+            part_length = 0;
+        }
+        if !part.is_empty() {
+            part.push('\n');
+        }
+        part.push_str(paragraph);
+        part_length += paragraph_length;
+    }
+    if !part.is_empty() {
+        parts.push(part);
+    }
+
+    if parts.len() == 1 {
+        return vec![Chapter {
+            title: chapter.title,
+            text: parts.remove(0),
+        }];
+    }
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(idx, text)| {
+            let part = idx + 1;
+            let title = match &chapter.title {
+                Some(title) => format!("{title} ({part})"),
+                None => format!("({part})"),
+            };
+            Chapter {
+                title: Some(title),
+                text,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decodes_shift_jis() {
+        let (data, _, _) = encoding_rs::SHIFT_JIS.encode("吾輩は猫である。");
+        assert_eq!(decode_text(&data), "吾輩は猫である。");
+        assert_eq!(decode_text("\u{feff}猫".as_bytes()), "猫");
+    }
+
+    #[test]
+    fn detects_format() {
+        assert_eq!(Format::detect("book.EPUB", b""), Some(Format::Epub));
+        assert_eq!(
+            Format::detect("book.txt", "吾輩《わがはい》は猫である。".as_bytes()),
+            Some(Format::Aozora)
+        );
+        assert_eq!(
+            Format::detect("book.txt", "吾輩は猫である。".as_bytes()),
+            Some(Format::Text)
+        );
+        assert_eq!(Format::detect("book.pdf", b""), None);
+    }
+
+    #[test]
+    fn splits_long_chapters() {
+        let paragraph = "あ".repeat(MAX_CHAPTER_LENGTH / 2 + 1);
+        let chapter = Chapter {
+            title: Some("一".to_string()),
+            text: [paragraph.as_str(); 3].join("\n"),
+        };
+        let parts = split_chapter(chapter);
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].title.as_deref(), Some("一 (1)"));
+        assert_eq!(parts[2].text, paragraph);
+    }
+}
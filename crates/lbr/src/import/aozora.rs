@@ -0,0 +1,164 @@
+//! Aozora Bunko texts.
+//! See <https://www.aozora.gr.jp/annotation/> for the annotation format.
+
+use super::{Chapter, normalise_paragraphs};
+
+/// Checks whether the text uses Aozora Bunko's ruby or annotation markup.
+pub fn is_aozora(text: &str) -> bool {
+    text.contains('《') && text.contains('》') || text.contains("［＃") || text.contains("底本：")
+}
+
+/// Splits the text into chapters at headings, removing annotations.
+/// Ruby is kept in the text so that the readings can be used when segmenting it, see `crate::ruby`.
+pub fn chapters(text: &str) -> Vec<Chapter> {
+    let body = body(text);
+
+    let mut chapters = Vec::new();
+    let mut chapter = Chapter {
+        title: None,
+        text: String::new(),
+    };
+    for line in body.lines() {
+        let (line, is_heading) = strip_markup(line);
+        let line = line.trim();
+        if is_heading && !line.is_empty() {
+            let previous = std::mem::replace(
+                &mut chapter,
+                Chapter {
+                    title: Some(crate::ruby::parse(line).0),
+                    text: String::new(),
+                },
+            );
+            if !previous.text.trim().is_empty() {
+                chapters.push(previous);
+            }
+        } else {
+            chapter.text.push_str(line);
+            chapter.text.push('\n');
+        }
+    }
+    chapters.push(chapter);
+
+    for chapter in &mut chapters {
+        chapter.text = normalise_paragraphs(&chapter.text);
+    }
+    chapters
+}
+
+/// Removes the title, the explanation of the symbols used in the text and the bibliographic information at the end.
+fn body(text: &str) -> &str {
+    let mut body = text;
+    // the explanation is surrounded by lines of dashes
+    let mut separators = text.match_indices('\n').filter_map(|(idx, _)| {
+        let line = text[idx + 1..].lines().next()?;
+        (line.len() >= 10 && line.trim_end().chars().all(|c| c == '-'))
+            .then(|| idx + 1 + line.len())
+    });
+    if let (Some(_), Some(end)) = (separators.next(), separators.next()) {
+        body = &text[end..];
+    }
+    if let Some(idx) = body.find("\n底本：") {
+        body = &body[..idx];
+    }
+    body
+}
+
+/// Removes annotations from the line.
+/// Also returns whether the line is a heading.
+fn strip_markup(line: &str) -> (String, bool) {
+    let mut stripped = String::new();
+    let mut is_heading = false;
+    let mut chars = line.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            // gaiji, a character that is described by the following annotation
+            '※' if line[idx + c.len_utf8()..].starts_with("［＃") => {}
+            '［' if line[idx + c.len_utf8()..].starts_with('＃') => {
+                let mut depth = 0;
+                let mut annotation = String::new();
+                for (_, c) in chars.by_ref() {
+                    match c {
+                        '［' => depth += 1,
+                        '］' if depth == 0 => break,
+                        '］' => depth -= 1,
+                        _ => {}
+                    }
+                    annotation.push(c);
+                }
+                if annotation.contains("見出し") && !annotation.contains("窓見出し") {
+                    is_heading = true;
+                }
+            }
+            c => stripped.push(c),
+        }
+    }
+    (stripped, is_heading)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEXT: &str = "吾輩は猫である
+夏目漱石
+
+-------------------------------------------------------
+【テキスト中に現れる記号について】
+
+《》：ルビ
+（例）吾輩《わがはい》
+
+｜：ルビの付く文字列の始まりを特定する記号
+-------------------------------------------------------
+
+［＃８字下げ］一［＃「一」は中見出し］
+
+　吾輩《わがはい》は猫である。名前はまだ無い。
+　どこで生れたかとんと｜見当《けんとう》がつかぬ。※［＃「魚＋師のつくり」、第4水準2-93-37］
+
+［＃８字下げ］二［＃「二」は中見出し］
+
+　吾輩は新年来多少有名になったので、
+
+底本：「夏目漱石全集1」ちくま文庫、筑摩書房
+";
+
+    #[test]
+    fn detects_aozora() {
+        assert!(is_aozora(TEXT));
+        assert!(!is_aozora("吾輩は猫である。"));
+    }
+
+    #[test]
+    fn splits_chapters() {
+        let chapters = chapters(TEXT);
+        assert_eq!(
+            chapters,
+            vec![
+                Chapter {
+                    title: Some("一".to_string()),
+                    text: "吾輩《わがはい》は猫である。名前はまだ無い。\nどこで生れたかとんと｜見当《けんとう》がつかぬ。"
+                        .to_string(),
+                },
+                Chapter {
+                    title: Some("二".to_string()),
+                    text: "吾輩は新年来多少有名になったので、".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn strips_block_headings() {
+        let (line, is_heading) = strip_markup("［＃大見出し］第一章［＃大見出し終わり］");
+        assert_eq!(line, "第一章");
+        assert!(is_heading);
+    }
+
+    #[test]
+    fn removes_ruby_from_titles() {
+        let chapters = chapters("［＃５字下げ］序章《じょしょう》［＃「序章」は中見出し］\n本文");
+        assert_eq!(chapters[0].title.as_deref(), Some("序章"));
+        assert_eq!(chapters[0].text, "本文");
+    }
+}
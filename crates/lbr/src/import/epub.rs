@@ -0,0 +1,343 @@
+//! EPUB books.
+//! See <https://www.w3.org/TR/epub/> for the format.
+
+use super::{Chapter, ImportError, normalise_paragraphs};
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read, Seek},
+};
+use xml::{
+    ParserConfig,
+    reader::{EventReader, XmlEvent},
+};
+use zip::ZipArchive;
+
+/// The maximum size of a single file extracted from the archive.
+const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+/// The maximum total size of the files extracted from the archive.
+const MAX_EXTRACTED_SIZE: u64 = 128 * 1024 * 1024;
+
+/// Returns the book's documents in reading order, one chapter per document.
+pub fn chapters(data: &[u8]) -> Result<Vec<Chapter>, ImportError> {
+    let mut archive = ZipArchive::new(Cursor::new(data))?;
+    let mut extracted = 0;
+
+    let container = read_file(&mut archive, "META-INF/container.xml", &mut extracted)?;
+    let package_path = package_path(&container)?;
+    let package = read_file(&mut archive, &package_path, &mut extracted)?;
+    let package_dir = package_path
+        .rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or_default();
+
+    let mut chapters = Vec::new();
+    for href in spine(&package)? {
+        let path = resolve_path(package_dir, &href);
+        let document = read_file(&mut archive, &path, &mut extracted)?;
+        chapters.push(parse_document(&document)?);
+    }
+    Ok(chapters)
+}
+
+/// Reads the file from the archive and adds its size to `extracted`.
+/// The sizes in the archive can't be trusted, so the limits are enforced while reading.
+fn read_file<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    path: &str,
+    extracted: &mut u64,
+) -> Result<Vec<u8>, ImportError> {
+    let file = archive
+        .by_name(path)
+        .map_err(|_| ImportError::InvalidEpub(format!("Missing file {path}")))?;
+    let limit = MAX_FILE_SIZE.min(MAX_EXTRACTED_SIZE.saturating_sub(*extracted));
+    let mut buf = Vec::new();
+    file.take(limit + 1).read_to_end(&mut buf)?;
+    let size = buf.len() as u64;
+    if size > limit {
+        return Err(ImportError::InvalidEpub(format!(
+            "Extracting {path} exceeds the size limit"
+        )));
+    }
+    *extracted += size;
+    Ok(buf)
+}
+
+fn config() -> ParserConfig {
+    ParserConfig::new()
+        .cdata_to_characters(true)
+        .ignore_comments(true)
+        .add_entity("nbsp", "\u{a0}")
+        .add_entity("ensp", "\u{2002}")
+        .add_entity("emsp", "\u{2003}")
+        .add_entity("thinsp", "\u{2009}")
+        .add_entity("hellip", "…")
+        .add_entity("mdash", "—")
+        .add_entity("ndash", "–")
+        .add_entity("lsquo", "‘")
+        .add_entity("rsquo", "’")
+        .add_entity("ldquo", "“")
+        .add_entity("rdquo", "”")
+        .replace_unknown_entity_references(true)
+}
+
+/// Finds the path of the package document from the container file.
+fn package_path(container: &[u8]) -> Result<String, ImportError> {
+    for event in EventReader::new_with_config(container, config()) {
+        if let XmlEvent::StartElement {
+            name, attributes, ..
+        } = event?
+        {
+            if name.local_name == "rootfile" {
+                if let Some(path) = attributes
+                    .into_iter()
+                    .find(|a| a.name.local_name == "full-path")
+                {
+                    return Ok(path.value);
+                }
+            }
+        }
+    }
+    Err(ImportError::InvalidEpub(
+        "Missing rootfile in container".to_string(),
+    ))
+}
+
+/// Returns the paths of the documents in the package's spine, relative to the package document.
+fn spine(package: &[u8]) -> Result<Vec<String>, ImportError> {
+    let mut manifest = HashMap::new();
+    let mut spine = Vec::new();
+    for event in EventReader::new_with_config(package, config()) {
+        if let XmlEvent::StartElement {
+            name, attributes, ..
+        } = event?
+        {
+            let attribute = |attribute: &str| {
+                attributes
+                    .iter()
+                    .find(|a| a.name.local_name == attribute)
+                    .map(|a| a.value.clone())
+            };
+            match name.local_name.as_str() {
+                "item" => {
+                    if let (Some(id), Some(href)) = (attribute("id"), attribute("href")) {
+                        manifest.insert(id, href);
+                    }
+                }
+                // non-linear items are auxiliary content such as footnotes
+                "itemref" if attribute("linear").as_deref() != Some("no") => {
+                    spine.extend(attribute("idref"));
+                }
+                _ => {}
+            }
+        }
+    }
+    spine
+        .into_iter()
+        .map(|idref| {
+            manifest.remove(&idref).ok_or_else(|| {
+                ImportError::InvalidEpub(format!("Spine item {idref} missing from manifest"))
+            })
+        })
+        .collect()
+}
+
+/// Resolves a path relative to the given directory within the archive.
+fn resolve_path(dir: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or_default();
+    let mut segments = dir.split('/').filter(|s| !s.is_empty()).collect::<Vec<_>>();
+    for segment in href.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+/// Extracts the text from an XHTML document.
+/// The first heading is used as the chapter's title and ruby is converted to the notation used by Aozora Bunko.
+fn parse_document(document: &[u8]) -> Result<Chapter, ImportError> {
+    const SKIPPED: &[&str] = &["head", "rp", "script", "style"];
+    const BLOCKS: &[&str] = &[
+        "p",
+        "div",
+        "br",
+        "li",
+        "tr",
+        "section",
+        "blockquote",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+    ];
+    const HEADINGS: &[&str] = &["h1", "h2", "h3", "h4", "h5", "h6"];
+
+    let mut text = String::new();
+    let mut title = None::<String>;
+    let mut heading = None::<String>;
+    let mut skip_depth = 0;
+    // start of the text the next ruby reading applies to
+    let mut ruby_base = None::<usize>;
+    let mut in_reading = false;
+    for event in EventReader::new_with_config(document, config()) {
+        match event? {
+            XmlEvent::StartElement { name, .. } => {
+                let name = name.local_name.as_str();
+                if SKIPPED.contains(&name) {
+                    skip_depth += 1;
+                } else if BLOCKS.contains(&name) {
+                    text.push('\n');
+                }
+                match name {
+                    "ruby" => ruby_base = Some(text.len()),
+                    "rt" => match ruby_base.take() {
+                        Some(start) if start < text.len() && skip_depth == 0 => {
+                            text.insert(start, '｜');
+                            text.push('《');
+                            in_reading = true;
+                        }
+                        // a reading without any text to annotate
+                        _ => skip_depth += 1,
+                    },
+                    _ => {}
+                }
+                if title.is_none() && HEADINGS.contains(&name) {
+                    heading = Some(String::new());
+                }
+            }
+            XmlEvent::EndElement { name } => {
+                let name = name.local_name.as_str();
+                if SKIPPED.contains(&name) {
+                    skip_depth -= 1;
+                } else if BLOCKS.contains(&name) {
+                    text.push('\n');
+                }
+                match name {
+                    "ruby" => ruby_base = None,
+                    // ruby can contain several pairs of text and reading
+                    "rt" if in_reading => {
+                        text.push('》');
+                        in_reading = false;
+                        ruby_base = Some(text.len());
+                    }
+                    "rt" => skip_depth -= 1,
+                    _ => {}
+                }
+                if HEADINGS.contains(&name) {
+                    if let Some(heading) = heading.take() {
+                        let heading = heading.trim();
+                        if !heading.is_empty() {
+                            title = Some(heading.to_string());
+                        }
+                    }
+                }
+            }
+            XmlEvent::Characters(characters) if skip_depth == 0 => {
+                text.push_str(&characters);
+                if in_reading {
+                    continue;
+                }
+                if let Some(heading) = &mut heading {
+                    heading.push_str(&characters);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(Chapter {
+        title,
+        text: normalise_paragraphs(&text),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_document() {
+        let document = r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml">
+<head><title>本</title></head>
+<body>
+<h2>第一章</h2>
+<p><ruby>吾輩<rp>(</rp><rt>わがはい</rt><rp>)</rp></ruby>は猫である。</p>
+<p>名前はまだ&nbsp;無い。</p>
+<p><ruby>漢<rt>かん</rt>字<rt>じ</rt></ruby></p>
+</body>
+</html>"#;
+        let chapter = parse_document(document.as_bytes()).unwrap();
+        assert_eq!(chapter.title.as_deref(), Some("第一章"));
+        assert_eq!(
+            chapter.text,
+            "第一章\n｜吾輩《わがはい》は猫である。\n名前はまだ\u{a0}無い。\n｜漢《かん》｜字《じ》"
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_files() {
+        use std::io::Write;
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        writer.start_file("big.xhtml", options).unwrap();
+        writer
+            .write_all(&vec![b' '; MAX_FILE_SIZE as usize + 1])
+            .unwrap();
+        writer.start_file("small.xhtml", options).unwrap();
+        writer.write_all(b"<p/>").unwrap();
+        let data = writer.finish().unwrap().into_inner();
+
+        let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
+        let mut extracted = 0;
+        let err = read_file(&mut archive, "big.xhtml", &mut extracted).unwrap_err();
+        assert!(matches!(err, ImportError::InvalidEpub(_)));
+        let small = read_file(&mut archive, "small.xhtml", &mut extracted).unwrap();
+        assert_eq!(small, b"<p/>");
+        assert_eq!(extracted, 4);
+
+        // the files extracted so far count towards the total
+        let mut extracted = MAX_EXTRACTED_SIZE - 2;
+        let err = read_file(&mut archive, "small.xhtml", &mut extracted).unwrap_err();
+        assert!(matches!(err, ImportError::InvalidEpub(_)));
+    }
+
+    #[test]
+    fn parses_spine() {
+        let package = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+<manifest>
+<item id="c2" href="text/c2.xhtml" media-type="application/xhtml+xml"/>
+<item id="c1" href="text/c1.xhtml" media-type="application/xhtml+xml"/>
+<item id="notes" href="text/notes.xhtml" media-type="application/xhtml+xml"/>
+</manifest>
+<spine>
+<itemref idref="c1"/>
+<itemref idref="notes" linear="no"/>
+<itemref idref="c2"/>
+</spine>
+</package>"#;
+        let spine = spine(package.as_bytes()).unwrap();
+        assert_eq!(spine, vec!["text/c1.xhtml", "text/c2.xhtml"]);
+    }
+
+    #[test]
+    fn resolves_paths() {
+        assert_eq!(
+            resolve_path("OEBPS", "text/c1.xhtml"),
+            "OEBPS/text/c1.xhtml"
+        );
+        assert_eq!(resolve_path("", "c1.xhtml#start"), "c1.xhtml");
+        assert_eq!(
+            resolve_path("OEBPS/pkg", "../text/c1.xhtml"),
+            "OEBPS/text/c1.xhtml"
+        );
+    }
+}
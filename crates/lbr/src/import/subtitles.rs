@@ -0,0 +1,326 @@
+//! Subtitle files in the SubRip (SRT), Advanced SubStation Alpha (ASS/SSA) and WebVTT formats.
+
+use super::{MAX_CHAPTER_LENGTH, decode_text};
+use crate::sentence_splitter::SentenceSplitter;
+use std::{ops::Range, time::Duration};
+
+/// A line of text shown on screen during the given time range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subtitle {
+    pub range: Range<Duration>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    Ass,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    /// Guesses the format of a subtitle file from its name.
+    pub fn detect(filename: &str) -> Option<Self> {
+        let extension = filename.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "srt" => Some(Self::Srt),
+            "ass" | "ssa" => Some(Self::Ass),
+            "vtt" => Some(Self::Vtt),
+            _ => None,
+        }
+    }
+}
+
+/// Extracts the sentences from the file's contents, each with the time range of the subtitles it appears in.
+/// Subtitles that continue onto the next one are merged and subtitles with multiple sentences are split.
+/// The sentences are split into parts of at most `MAX_CHAPTER_LENGTH` characters.
+pub fn import(format: SubtitleFormat, data: &[u8]) -> Vec<Vec<Subtitle>> {
+    let text = decode_text(data);
+    let mut subtitles = match format {
+        SubtitleFormat::Srt | SubtitleFormat::Vtt => parse_cues(&text),
+        SubtitleFormat::Ass => parse_ass(&text),
+    };
+    // ASS events are not necessarily in chronological order
+    subtitles.sort_by_key(|s| s.range.start);
+    split_parts(sentences(subtitles))
+}
+
+/// Parses the cues of an SRT or WebVTT file, which only differ in their headers and cue settings.
+fn parse_cues(text: &str) -> Vec<Subtitle> {
+    let text = text.replace("\r\n", "\n");
+    let mut subtitles = Vec::new();
+    for block in text.split("\n\n") {
+        let mut lines = block.lines().skip_while(|l| !l.contains("-->"));
+        let Some(timing) = lines.next() else {
+            // header, comment, style or an empty block
+            continue;
+        };
+        let Some((start, end)) = timing.split_once("-->") else {
+            continue;
+        };
+        // WebVTT cue settings follow the end time
+        let end = end.split_whitespace().next().unwrap_or_default();
+        let (Some(start), Some(end)) = (parse_timestamp(start.trim()), parse_timestamp(end)) else {
+            tracing::warn!("Skipping cue with invalid timing '{timing}'");
+            continue;
+        };
+        let text = lines.map(strip_tags).collect::<Vec<_>>().join("\n");
+        push_subtitle(&mut subtitles, start..end, text);
+    }
+    subtitles
+}
+
+/// Parses the dialogue events of an ASS or SSA file.
+fn parse_ass(text: &str) -> Vec<Subtitle> {
+    let mut subtitles = Vec::new();
+    let mut in_events = false;
+    let mut format = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+        if let Some(fields) = line.strip_prefix("Format:") {
+            format = fields.split(',').map(|f| f.trim().to_string()).collect();
+        } else if let Some(fields) = line.strip_prefix("Dialogue:") {
+            // the text is always the last field and may contain commas
+            let fields = fields.splitn(format.len(), ',').collect::<Vec<_>>();
+            let field = |name: &str| {
+                format
+                    .iter()
+                    .position(|f| f == name)
+                    .and_then(|idx| fields.get(idx))
+                    .map(|f| f.trim())
+            };
+            let (Some(start), Some(end), Some(text)) = (
+                field("Start").and_then(parse_timestamp),
+                field("End").and_then(parse_timestamp),
+                field("Text"),
+            ) else {
+                tracing::warn!("Skipping invalid dialogue line '{line}'");
+                continue;
+            };
+            // drawing mode, the text consists of vector drawing commands
+            if text.contains("\\p1") {
+                continue;
+            }
+            let text = text
+                .replace("\\N", "\n")
+                .replace("\\n", "\n")
+                .replace("\\h", "\u{a0}");
+            let text = text.lines().map(strip_tags).collect::<Vec<_>>().join("\n");
+            push_subtitle(&mut subtitles, start..end, text);
+        }
+    }
+    subtitles
+}
+
+fn push_subtitle(subtitles: &mut Vec<Subtitle>, range: Range<Duration>, text: String) {
+    let text = text.trim();
+    if !text.is_empty() {
+        subtitles.push(Subtitle {
+            range,
+            text: text.to_string(),
+        });
+    }
+}
+
+/// Parses timestamps such as `01:02:03,456` (SRT), `02:03.456` (WebVTT) and `1:02:03.45` (ASS).
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let (hms, fraction) = timestamp.split_once([',', '.']).unwrap_or((timestamp, "0"));
+    let mut seconds = 0;
+    for part in hms.split(':') {
+        seconds = seconds * 60 + part.trim().parse::<u64>().ok()?;
+    }
+    // the fraction can have any precision, e.g. centiseconds in ASS files
+    let fraction = fraction.trim();
+    let millis = format!("{fraction:0<3}").get(..3)?.parse::<u64>().ok()?;
+    Some(Duration::from_secs(seconds) + Duration::from_millis(millis))
+}
+
+/// Removes HTML-like tags such as `<i>` and ASS override blocks such as `{\an8}`.
+fn strip_tags(line: &str) -> String {
+    let mut stripped = String::new();
+    let mut closing = None;
+    for c in line.chars() {
+        match (closing, c) {
+            (None, '<') => closing = Some('>'),
+            (None, '{') => closing = Some('}'),
+            (None, c) => stripped.push(c),
+            (Some(end), c) if c == end => closing = None,
+            (Some(_), _) => {}
+        }
+    }
+    stripped
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", "\u{a0}")
+        .trim()
+        .to_string()
+}
+
+/// Converts the subtitles into sentences.
+fn sentences(subtitles: Vec<Subtitle>) -> Vec<Subtitle> {
+    let mut sentences = Vec::new();
+    let mut pending = None::<Subtitle>;
+    for subtitle in subtitles {
+        for utterance in utterances(&subtitle.text) {
+            let merged = match pending.take() {
+                Some(mut pending) => {
+                    pending.range.end = subtitle.range.end;
+                    pending.text.push_str(&utterance);
+                    pending
+                }
+                None => Subtitle {
+                    range: subtitle.range.clone(),
+                    text: utterance,
+                },
+            };
+            // the sentence continues in the next subtitle
+            if merged.text.ends_with(['、', ',', '→']) {
+                pending = Some(merged);
+                continue;
+            }
+            push_sentences(&mut sentences, merged);
+        }
+    }
+    if let Some(pending) = pending {
+        push_sentences(&mut sentences, pending);
+    }
+    sentences
+}
+
+/// Splits the subtitle's text into the lines spoken by each speaker.
+/// Lines that start with a dash are spoken by a different speaker than the previous line,
+/// other line breaks are only used to fit the text on the screen.
+fn utterances(text: &str) -> Vec<String> {
+    let mut utterances = Vec::<String>::new();
+    for line in text.lines() {
+        let line = line.trim();
+        match line.strip_prefix(['-', '－', '‐']) {
+            Some(line) => utterances.push(line.trim_start().to_string()),
+            None => match utterances.last_mut() {
+                Some(utterance) => utterance.push_str(line),
+                None => utterances.push(line.to_string()),
+            },
+        }
+    }
+    utterances.retain(|u| !u.is_empty());
+    utterances
+}
+
+fn push_sentences(sentences: &mut Vec<Subtitle>, subtitle: Subtitle) {
+    let text = subtitle.text.trim_end_matches(['→']);
+    for sentence in SentenceSplitter::new(text) {
+        sentences.push(Subtitle {
+            range: subtitle.range.clone(),
+            text: sentence.to_string(),
+        });
+    }
+}
+
+fn split_parts(sentences: Vec<Subtitle>) -> Vec<Vec<Subtitle>> {
+    let mut parts = Vec::new();
+    let mut part = Vec::new();
+    let mut part_length = 0;
+    for sentence in sentences {
+        let sentence_length = sentence.text.chars().count();
+        if part_length > 0 && part_length + sentence_length > MAX_CHAPTER_LENGTH {
+            parts.push(std::mem::take(&mut part));
+            part_length = 0;
+        }
+        part.push(sentence);
+        part_length += sentence_length;
+    }
+    if !part.is_empty() {
+        parts.push(part);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn subtitle(start_ms: u64, end_ms: u64, text: &str) -> Subtitle {
+        Subtitle {
+            range: Duration::from_millis(start_ms)..Duration::from_millis(end_ms),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_timestamps() {
+        assert_eq!(
+            parse_timestamp("01:02:03,456"),
+            Some(Duration::from_millis(3_723_456))
+        );
+        assert_eq!(
+            parse_timestamp("02:03.456"),
+            Some(Duration::from_millis(123_456))
+        );
+        assert_eq!(
+            parse_timestamp("1:02:03.45"),
+            Some(Duration::from_millis(3_723_450))
+        );
+        assert_eq!(parse_timestamp("abc"), None);
+    }
+
+    #[test]
+    fn parses_srt() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:02,500\r\n<i>こんにちは。</i>\r\n\r\n2\r\n00:00:03,000 --> 00:00:04,000\r\n{\\an8}元気？\r\n";
+        assert_eq!(
+            parse_cues(srt),
+            vec![
+                subtitle(1000, 2500, "こんにちは。"),
+                subtitle(3000, 4000, "元気？")
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_vtt() {
+        let vtt = "WEBVTT\n\nNOTE a comment\n\nintro\n00:01.000 --> 00:02.000 align:start\n<c.yellow>行くよ</c>\n";
+        assert_eq!(parse_cues(vtt), vec![subtitle(1000, 2000, "行くよ")]);
+    }
+
+    #[test]
+    fn parses_ass() {
+        let ass = "[Script Info]
+Title: Test
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,コメント
+Dialogue: 0,0:00:01.50,0:00:03.00,Default,,0,0,0,,{\\i1}はい、\\Nそうです。
+Dialogue: 0,0:00:04.00,0:00:05.00,Sign,,0,0,0,,{\\p1}m 0 0 l 100 0 100 100
+";
+        assert_eq!(
+            parse_ass(ass),
+            vec![subtitle(1500, 3000, "はい、\nそうです。")]
+        );
+    }
+
+    #[test]
+    fn merges_and_splits_sentences() {
+        let subtitles = vec![
+            subtitle(0, 1000, "今日は、"),
+            subtitle(1000, 2000, "いい天気ですね。散歩しよう。"),
+            subtitle(3000, 4000, "-うん\n-行こう"),
+        ];
+        assert_eq!(
+            sentences(subtitles),
+            vec![
+                subtitle(0, 2000, "今日は、いい天気ですね。"),
+                subtitle(0, 2000, "散歩しよう。"),
+                subtitle(3000, 4000, "うん"),
+                subtitle(3000, 4000, "行こう"),
+            ]
+        );
+    }
+}
@@ -2,7 +2,12 @@
 
 pub mod anki;
 pub mod core;
+pub mod deinflector;
+pub mod import;
+pub mod ruby;
+pub mod segmenter;
 pub mod sentence_splitter;
+pub mod srs;
 
 fn is_kanji(c: char) -> bool {
     // Unicode CJK Unified Ideographs
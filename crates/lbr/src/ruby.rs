@@ -0,0 +1,190 @@
+//! Ruby readings given by the text itself, such as in Aozora Bunko texts.
+//! See <https://www.aozora.gr.jp/annotation/etc.html#ruby> for the notation.
+
+use lbr_core::ichiran_types::Segment;
+pub use lbr_core::ruby::Ruby;
+
+/// Removes the ruby notation from the text.
+/// Returns the plain text and the readings, with ranges relative to the plain text.
+pub fn parse(text: &str) -> (String, Vec<Ruby>) {
+    let mut plain = String::new();
+    let mut ruby = Vec::new();
+    // start of the annotated text if it was explicitly marked
+    let mut base_start = None;
+    let mut chars = text.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '｜' => base_start = Some(plain.len()),
+            '《' => {
+                let rest = &text[idx + c.len_utf8()..];
+                let Some(end) = rest.find('》') else {
+                    plain.push(c);
+                    continue;
+                };
+                let reading = &rest[..end];
+                // skip over the reading and the closing bracket
+                for _ in 0..=reading.chars().count() {
+                    chars.next();
+                }
+
+                // without an explicit start, the reading applies to the preceding kanji
+                let start = base_start.take().unwrap_or_else(|| {
+                    plain
+                        .char_indices()
+                        .rev()
+                        .take_while(|(_, c)| is_ruby_base(*c))
+                        .last()
+                        .map(|(idx, _)| idx)
+                        .unwrap_or(plain.len())
+                });
+                if start < plain.len() && !reading.is_empty() {
+                    ruby.push(Ruby {
+                        range: start..plain.len(),
+                        reading: reading.to_string(),
+                    });
+                }
+            }
+            c => plain.push(c),
+        }
+    }
+    (plain, ruby)
+}
+
+fn is_ruby_base(c: char) -> bool {
+    crate::is_kanji(c) || matches!(c, '々' | '〆' | 'ヵ' | 'ヶ')
+}
+
+/// Moves the interpretations whose reading agrees with the ruby to the front of each segment.
+/// Segments are only affected if the ruby covers all of the kanji in the segment.
+pub fn prefer_ruby_readings(sentence: &str, segments: &mut [Segment], ruby: &[Ruby]) {
+    for segment in segments {
+        let range = &segment.range;
+        if ruby
+            .iter()
+            .any(|r| r.range.start < range.end && r.range.end > range.start && !contains(range, r))
+        {
+            // the segmenter split the annotated text so the reading can't be attributed
+            continue;
+        }
+        let mut segment_ruby = ruby.iter().filter(|r| contains(range, r)).peekable();
+        if segment_ruby.peek().is_none() {
+            continue;
+        }
+
+        let mut expected = String::new();
+        let mut idx = range.start;
+        for r in segment_ruby {
+            let Some(text) = sentence.get(idx..r.range.start) else {
+                break;
+            };
+            expected.push_str(text);
+            expected.push_str(&r.reading);
+            idx = r.range.end;
+        }
+        let Some(text) = sentence.get(idx..range.end) else {
+            continue;
+        };
+        expected.push_str(text);
+        if expected.chars().any(is_ruby_base) {
+            continue;
+        }
+
+        let expected = crate::standardise_reading(&expected).standardised;
+        let matches = |reading: &str| crate::standardise_reading(reading).standardised == expected;
+        let top_score = segment
+            .interpretations
+            .iter()
+            .map(|i| i.score)
+            .max()
+            .unwrap_or_default();
+        for interpretation in &mut segment.interpretations {
+            if matches(&interpretation.reading_hiragana) {
+                interpretation.score = interpretation.score.max(top_score);
+            }
+        }
+        segment
+            .interpretations
+            .sort_by_key(|i| !matches(&i.reading_hiragana));
+    }
+}
+
+fn contains(range: &std::ops::Range<usize>, ruby: &Ruby) -> bool {
+    range.start <= ruby.range.start && ruby.range.end <= range.end
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lbr_core::ichiran_types::Interpretation;
+
+    #[test]
+    fn parses_ruby() {
+        let (plain, ruby) = parse("吾輩《わがはい》は猫である。｜見当《けんとう》がつかぬ。");
+        assert_eq!(plain, "吾輩は猫である。見当がつかぬ。");
+        assert_eq!(
+            ruby,
+            vec![
+                Ruby {
+                    range: 0..6,
+                    reading: "わがはい".to_string(),
+                },
+                Ruby {
+                    range: 24..30,
+                    reading: "けんとう".to_string(),
+                },
+            ]
+        );
+        assert_eq!(&plain[ruby[1].range.clone()], "見当");
+    }
+
+    #[test]
+    fn parses_implicit_base() {
+        let (plain, ruby) = parse("ここで生《うま》れた。時々《ときどき》");
+        assert_eq!(plain, "ここで生れた。時々");
+        assert_eq!(&plain[ruby[0].range.clone()], "生");
+        assert_eq!(&plain[ruby[1].range.clone()], "時々");
+    }
+
+    #[test]
+    fn ignores_ruby_without_base() {
+        let (plain, ruby) = parse("ねこ《ネコ》と《");
+        assert_eq!(plain, "ねこと《");
+        assert!(ruby.is_empty());
+    }
+
+    fn interpretation(score: i32, reading: &str) -> Interpretation {
+        Interpretation {
+            word_id: None,
+            score,
+            word: "生れた".to_string(),
+            reading_hiragana: reading.to_string(),
+            meanings: Vec::new(),
+            inflections: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn prefers_ruby_readings() {
+        let (sentence, ruby) = parse("ここで生《うま》れた");
+        let mut segments = vec![
+            Segment {
+                text: "ここで".to_string(),
+                interpretations: Vec::new(),
+                range: 0..9,
+            },
+            Segment {
+                text: "生れた".to_string(),
+                interpretations: vec![
+                    interpretation(100, "いきれた"),
+                    interpretation(50, "うまれた"),
+                ],
+                range: 9..18,
+            },
+        ];
+        prefer_ruby_readings(&sentence, &mut segments, &ruby);
+        let interpretations = &segments[1].interpretations;
+        assert_eq!(interpretations[0].reading_hiragana, "うまれた");
+        assert_eq!(interpretations[0].score, 100);
+        assert_eq!(interpretations[1].reading_hiragana, "いきれた");
+    }
+}
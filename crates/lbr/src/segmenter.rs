@@ -0,0 +1,46 @@
+//! Contains the Segmenter trait and its implementations, which split Japanese text into words.
+
+mod dictionary;
+mod ichiran;
+
+pub use self::{
+    dictionary::{DictionaryEntry, DictionarySegmenter},
+    ichiran::{IchiranCache, IchiranSegmenter},
+};
+use lbr_core::ichiran_types::Segment;
+use std::{process::ExitStatus, time::Duration};
+
+/// Splits Japanese text into segments with potential interpretations for each word.
+pub trait Segmenter: Send + Sync {
+    /// Segments the given text.
+    /// The returned segments are sorted by their range in the text
+    /// and their interpretations are sorted by score from highest to lowest.
+    fn segment(&self, text: &str) -> Result<Vec<Segment>, SegmenterError>;
+
+    /// Segments the given text, giving up once the timeout has passed.
+    /// Segmenters that run external processes kill them when giving up,
+    /// the default implementation ignores the timeout.
+    fn segment_with_timeout(
+        &self,
+        text: &str,
+        _timeout: Duration,
+    ) -> Result<Vec<Segment>, SegmenterError> {
+        self.segment(text)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SegmenterError {
+    #[error("Failed to run ichiran")]
+    IchiranProcess(#[source] std::io::Error),
+    #[error("ichiran exited with {status}: {stderr}")]
+    IchiranExit { status: ExitStatus, stderr: String },
+    #[error("Failed to parse ichiran's output")]
+    IchiranOutput(#[source] serde_json::Error),
+    #[error("ichiran failed to segment the text: {0}")]
+    IchiranFailed(String),
+    #[error("Segmentation timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("The segmenter stopped before finishing")]
+    Unavailable,
+}
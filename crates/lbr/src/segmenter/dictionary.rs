@@ -0,0 +1,262 @@
+//! Segmenter that uses LBR's own dictionary without any external tools.
+
+use super::{Segmenter, SegmenterError};
+use crate::deinflector::{self, Inflection};
+use lbr_core::ichiran_types::{Interpretation, Meaning, Segment};
+use std::collections::HashMap;
+
+/// A single word in the dictionary used by the DictionarySegmenter.
+#[derive(Debug, Clone)]
+pub struct DictionaryEntry {
+    /// LBR word id.
+    pub word_id: i32,
+    /// The written form of the word.
+    pub word: String,
+    /// The reading of the word in hiragana.
+    pub reading_hiragana: String,
+    /// List of English meanings for the word.
+    pub meanings: Vec<String>,
+}
+
+// how many characters longer than the longest dictionary word a conjugated word may be
+const MAX_INFLECTION_LENGTH: usize = 10;
+
+/// Segments text by finding the longest dictionary word at each position, including conjugated forms.
+pub struct DictionarySegmenter {
+    entries: Vec<DictionaryEntry>,
+    // maps written forms and readings to indices in entries
+    index: HashMap<String, Vec<usize>>,
+    // length of the longest possible match in characters
+    longest_match: usize,
+}
+
+impl DictionarySegmenter {
+    pub fn new(entries: Vec<DictionaryEntry>) -> Self {
+        let mut index = HashMap::<String, Vec<usize>>::new();
+        let mut longest_key = 0;
+        for (idx, entry) in entries.iter().enumerate() {
+            for key in [&entry.word, &entry.reading_hiragana] {
+                longest_key = longest_key.max(key.chars().count());
+                let indices = index.entry(key.clone()).or_default();
+                if !indices.contains(&idx) {
+                    indices.push(idx);
+                }
+            }
+        }
+        Self {
+            entries,
+            index,
+            longest_match: longest_key + MAX_INFLECTION_LENGTH,
+        }
+    }
+
+    fn entries(&self, key: &str) -> impl Iterator<Item = &DictionaryEntry> {
+        self.index
+            .get(key)
+            .into_iter()
+            .flatten()
+            .map(|idx| &self.entries[*idx])
+    }
+
+    // returns the interpretations for the text, sorted by score
+    fn interpretations(&self, text: &str) -> Vec<Interpretation> {
+        let base_score = text.chars().count() as i32 * 10;
+        let text_hiragana = katakana_to_hiragana(text);
+        let mut interpretations = Vec::<Interpretation>::new();
+        let mut push = |entry: &DictionaryEntry,
+                        reading_hiragana: String,
+                        score: i32,
+                        inflections: &[Inflection]| {
+            if interpretations
+                .iter()
+                .all(|i| i.word_id != Some(entry.word_id))
+            {
+                interpretations.push(Interpretation {
+                    word_id: Some(entry.word_id),
+                    score,
+                    word: text.to_string(),
+                    reading_hiragana,
+                    meanings: entry
+                        .meanings
+                        .iter()
+                        .map(|m| Meaning {
+                            meaning: m.clone(),
+                            meaning_info: None,
+                        })
+                        .collect(),
+                    inflections: inflections
+                        .iter()
+                        .map(|i| i.description().to_string())
+                        .collect(),
+                });
+            }
+        };
+
+        // exact matches
+        for entry in self.entries(text) {
+            let score = if entry.word == text {
+                base_score + 2
+            } else {
+                base_score
+            };
+            push(entry, entry.reading_hiragana.clone(), score, &[]);
+        }
+        if text_hiragana != text {
+            for entry in self.entries(&text_hiragana) {
+                push(entry, entry.reading_hiragana.clone(), base_score, &[]);
+            }
+        }
+
+        // conjugated forms, most candidate substrings can't be inflected so they are skipped early
+        let deinflections = if deinflector::has_inflected_ending(text) {
+            deinflector::deinflect(text)
+        } else {
+            Vec::new()
+        };
+        for deinflection in deinflections.into_iter().skip(1) {
+            if !deinflection.word_types.is_dictionary_form() {
+                continue;
+            }
+            // the part of the word that stays the same when conjugated
+            let stem_len = text
+                .char_indices()
+                .zip(deinflection.dictionary_form.chars())
+                .take_while(|((_, a), b)| a == b)
+                .last()
+                .map(|((idx, c), _)| idx + c.len_utf8())
+                .unwrap_or_default();
+            let inflected_ending = &text[stem_len..];
+            let dictionary_ending = &deinflection.dictionary_form[stem_len..];
+            for entry in self.entries(&deinflection.dictionary_form) {
+                let reading_stem = entry
+                    .reading_hiragana
+                    .strip_suffix(dictionary_ending)
+                    .unwrap_or(&entry.reading_hiragana);
+                push(
+                    entry,
+                    format!("{reading_stem}{inflected_ending}"),
+                    base_score - 1,
+                    &deinflection.inflections,
+                );
+            }
+        }
+
+        interpretations.sort_by(|a, b| a.score.cmp(&b.score).reverse());
+        interpretations
+    }
+}
+
+impl Segmenter for DictionarySegmenter {
+    fn segment(&self, text: &str) -> Result<Vec<Segment>, SegmenterError> {
+        tracing::debug!("Segmenting {text} with the dictionary segmenter");
+
+        let char_indices = text
+            .char_indices()
+            .map(|(idx, _)| idx)
+            .chain(std::iter::once(text.len()))
+            .collect::<Vec<_>>();
+        let char_count = char_indices.len() - 1;
+
+        let mut segments = Vec::new();
+        let mut start = 0;
+        while start < char_count {
+            let longest_end = char_count.min(start + self.longest_match);
+            let longest_match = (start + 1..=longest_end).rev().find_map(|end| {
+                let range = char_indices[start]..char_indices[end];
+                let interpretations = self.interpretations(&text[range.clone()]);
+                (!interpretations.is_empty()).then_some((end, range, interpretations))
+            });
+            if let Some((end, range, interpretations)) = longest_match {
+                segments.push(Segment {
+                    text: text[range.clone()].to_string(),
+                    interpretations,
+                    range,
+                });
+                start = end;
+            } else {
+                start += 1;
+            }
+        }
+        Ok(segments)
+    }
+}
+
+// only converts katakana so that romaji in the text is left as is
+fn katakana_to_hiragana(text: &str) -> String {
+    const UNICODE_KANA_TABLE_DISTANCE: u32 = 'ア' as u32 - 'あ' as u32;
+    text.chars()
+        .map(|c| match c {
+            'ァ'..='ヶ' => char::from_u32(c as u32 - UNICODE_KANA_TABLE_DISTANCE).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(word_id: i32, word: &str, reading_hiragana: &str) -> DictionaryEntry {
+        DictionaryEntry {
+            word_id,
+            word: word.to_string(),
+            reading_hiragana: reading_hiragana.to_string(),
+            meanings: vec![],
+        }
+    }
+
+    fn segmenter() -> DictionarySegmenter {
+        DictionarySegmenter::new(vec![
+            entry(1, "本", "ほん"),
+            entry(2, "を", "を"),
+            entry(3, "読む", "よむ"),
+            entry(4, "食べる", "たべる"),
+            entry(5, "本当", "ほんとう"),
+            entry(6, "テレビ", "てれび"),
+        ])
+    }
+
+    #[test]
+    fn segments_longest_match() {
+        let segments = segmenter().segment("本当").unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].interpretations[0].word_id, Some(5));
+    }
+
+    #[test]
+    fn segments_conjugated_words() {
+        let text = "本を読んだ。";
+        let segments = segmenter().segment(text).unwrap();
+        let words = segments
+            .iter()
+            .map(|s| (s.text.as_str(), s.interpretations[0].word_id))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            [("本", Some(1)), ("を", Some(2)), ("読んだ", Some(3))]
+        );
+        let reading = &segments[2].interpretations[0].reading_hiragana;
+        assert_eq!(reading, "よんだ");
+        assert_eq!(segments[2].interpretations[0].inflections, ["past"]);
+        assert_eq!(&text[segments[2].range.clone()], "読んだ");
+    }
+
+    #[test]
+    fn segments_words_written_in_kana() {
+        let segments = segmenter().segment("たべなかった").unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].interpretations[0].word_id, Some(4));
+        assert_eq!(
+            segments[0].interpretations[0].reading_hiragana,
+            "たべなかった"
+        );
+    }
+
+    #[test]
+    fn skips_unknown_text() {
+        let segments = segmenter().segment("「テレビ」").unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].text, "テレビ");
+        assert_eq!(segments[0].range, 3..12);
+    }
+}
@@ -0,0 +1,395 @@
+//! Segmenter that uses the ichiran CLI.
+
+use super::{Segmenter, SegmenterError};
+use lbr_core::ichiran_types::Segment;
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        mpsc::{self, Receiver, RecvTimeoutError},
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+/// The number of interpretations ichiran returns for each segment.
+const INTERPRETATION_LIMIT: u32 = 16;
+/// The number of lines of ichiran's stderr that are kept for reporting why it exited.
+const STDERR_LINES: usize = 32;
+
+/// Stores ichiran's raw output so that the same text does not need to be segmented again.
+pub trait IchiranCache: Send + Sync {
+    /// Returns the cached segments for the text, if any.
+    fn get(&self, text: &str) -> Option<Vec<::ichiran::Segment>>;
+    /// Caches the segments for the text.
+    fn insert(&self, text: &str, segments: &[::ichiran::Segment]);
+}
+
+/// Segments text with ichiran and maps the results to LBR's words.
+/// Starting ichiran is slow, so its processes are kept running and reused for later texts.
+/// A process that crashes or runs past the timeout is killed and a new one is started for the next text.
+pub struct IchiranSegmenter {
+    cli_path: PathBuf,
+    processes: Mutex<Vec<IchiranProcess>>,
+    ichiran_word_to_id: HashMap<(i32, String, String), i32>,
+    kanji_to_readings: Arc<HashMap<String, Vec<String>>>,
+    word_to_meanings: HashMap<i32, Vec<String>>,
+    cache: Option<Box<dyn IchiranCache>>,
+}
+
+impl IchiranSegmenter {
+    pub fn new(
+        cli_path: PathBuf,
+        ichiran_word_to_id: HashMap<(i32, String, String), i32>,
+        kanji_to_readings: Arc<HashMap<String, Vec<String>>>,
+        word_to_meanings: HashMap<i32, Vec<String>>,
+    ) -> Self {
+        Self {
+            cli_path,
+            processes: Mutex::new(Vec::new()),
+            ichiran_word_to_id,
+            kanji_to_readings,
+            word_to_meanings,
+            cache: None,
+        }
+    }
+
+    /// Caches ichiran's output in the given cache.
+    pub fn with_cache(mut self, cache: impl IchiranCache + 'static) -> Self {
+        self.cache = Some(Box::new(cache));
+        self
+    }
+
+    /// Starts ichiran processes until there are `count` idle ones,
+    /// so that the first texts don't have to wait for ichiran to start.
+    pub fn start_processes(&self, count: usize) {
+        let mut processes = self.idle_processes();
+        while processes.len() < count {
+            match IchiranProcess::spawn(&self.cli_path) {
+                Ok(process) => processes.push(process),
+                Err(err) => {
+                    tracing::error!("Failed to start ichiran: {err}");
+                    break;
+                }
+            }
+        }
+    }
+
+    fn idle_processes(&self) -> MutexGuard<'_, Vec<IchiranProcess>> {
+        // the list of processes is always valid, so the poison is ignored
+        self.processes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn ichiran_segments(
+        &self,
+        text: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<::ichiran::Segment>, SegmenterError> {
+        if let Some(segments) = self.cache.as_ref().and_then(|c| c.get(text)) {
+            tracing::debug!("Using cached segments for {text}");
+            return Ok(segments);
+        }
+        let idle_process = self.idle_processes().pop();
+        let mut process = match idle_process {
+            Some(process) => process,
+            None => IchiranProcess::spawn(&self.cli_path)?,
+        };
+        // the process is only reused if it segmented the text,
+        // otherwise it's dropped which kills it
+        let segments = process.segment(text, timeout)?;
+        self.idle_processes().push(process);
+        if let Some(cache) = &self.cache {
+            cache.insert(text, &segments);
+        }
+        Ok(segments)
+    }
+
+    fn to_lbr_segments(&self, text: &str, segments: Vec<::ichiran::Segment>) -> Vec<Segment> {
+        crate::core::to_lbr_segments(
+            text,
+            segments,
+            &self.ichiran_word_to_id,
+            &self.kanji_to_readings,
+            &self.word_to_meanings,
+        )
+    }
+}
+
+impl Segmenter for IchiranSegmenter {
+    fn segment(&self, text: &str) -> Result<Vec<Segment>, SegmenterError> {
+        let segments = self.ichiran_segments(text, None)?;
+        Ok(self.to_lbr_segments(text, segments))
+    }
+
+    fn segment_with_timeout(
+        &self,
+        text: &str,
+        timeout: Duration,
+    ) -> Result<Vec<Segment>, SegmenterError> {
+        let segments = self.ichiran_segments(text, Some(timeout))?;
+        Ok(self.to_lbr_segments(text, segments))
+    }
+}
+
+/// Makes the CLI read JSON strings from stdin one line at a time
+/// and write the same output as `ichiran-cli -f` for each on a single line,
+/// or an object with an error message if segmenting the text fails.
+fn read_eval_loop() -> String {
+    format!(
+        r#"(loop for line = (read-line *standard-input* nil)
+            while line
+            do (princ (handler-case
+                          (jsown:to-json (ichiran/all:romanize* (jsown:parse line) :limit {INTERPRETATION_LIMIT}))
+                        (error (e)
+                          (jsown:to-json (list :obj (cons "error" (princ-to-string e)))))))
+               (terpri)
+               (finish-output))"#
+    )
+}
+
+// a line of output from the read-eval loop
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Response {
+    Segments(Vec<::ichiran::Segment>),
+    Error { error: String },
+}
+
+// a running ichiran CLI that segments one text at a time,
+// killed when dropped
+struct IchiranProcess {
+    child: Child,
+    stdin: ChildStdin,
+    // read on a separate thread so that waiting for a line can time out
+    stdout: Receiver<std::io::Result<String>>,
+    stderr: Option<JoinHandle<String>>,
+}
+
+impl IchiranProcess {
+    fn spawn(cli_path: &Path) -> Result<Self, SegmenterError> {
+        let mut child = Command::new(cli_path)
+            .arg("-e")
+            .arg(read_eval_loop())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(SegmenterError::IchiranProcess)?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = read_lines_in_background(child.stdout.take().expect("stdout is piped"));
+        let stderr = read_stderr_in_background(child.stderr.take().expect("stderr is piped"));
+        tracing::info!("Started ichiran process {}", child.id());
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+            stderr: Some(stderr),
+        })
+    }
+
+    fn segment(
+        &mut self,
+        text: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<::ichiran::Segment>, SegmenterError> {
+        // the text is sent as a JSON string so that it fits on one line
+        let request = serde_json::Value::from(text);
+        let sent = writeln!(self.stdin, "{request}").and_then(|()| self.stdin.flush());
+        if sent.is_err() {
+            // the process has exited
+            return Err(self.exit_error());
+        }
+
+        let line = match timeout {
+            Some(timeout) => match self.stdout.recv_timeout(timeout) {
+                Err(RecvTimeoutError::Timeout) => {
+                    tracing::warn!("Killing ichiran after it ran for {timeout:?} on '{text}'");
+                    return Err(SegmenterError::Timeout(timeout));
+                }
+                line => line.ok(),
+            },
+            None => self.stdout.recv().ok(),
+        };
+        let Some(line) = line else {
+            // stdout was closed, so the process has exited
+            return Err(self.exit_error());
+        };
+        let line = line.map_err(SegmenterError::IchiranProcess)?;
+        match serde_json::from_str(&line).map_err(SegmenterError::IchiranOutput)? {
+            Response::Segments(segments) => Ok(segments),
+            Response::Error { error } => {
+                tracing::error!("Ichiran failed to segment '{text}': {error}");
+                Err(SegmenterError::IchiranFailed(error))
+            }
+        }
+    }
+
+    // collects the exit status and the end of stderr of a process that has exited or stopped responding
+    fn exit_error(&mut self) -> SegmenterError {
+        // killing a process that has already exited does not change its status
+        let _ = self.child.kill();
+        match self.child.wait() {
+            Ok(status) => {
+                let stderr = self
+                    .stderr
+                    .take()
+                    .and_then(|stderr| stderr.join().ok())
+                    .unwrap_or_default();
+                tracing::error!("Ichiran exited with {status}:\n{stderr}");
+                SegmenterError::IchiranExit { status, stderr }
+            }
+            Err(err) => SegmenterError::IchiranProcess(err),
+        }
+    }
+}
+
+impl Drop for IchiranProcess {
+    fn drop(&mut self) {
+        // the process may have exited already, in which case there's nothing to kill
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn read_lines_in_background(pipe: impl Read + Send + 'static) -> Receiver<std::io::Result<String>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(pipe).lines() {
+            let failed = line.is_err();
+            if sender.send(line).is_err() || failed {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn read_stderr_in_background(pipe: impl Read + Send + 'static) -> JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut lines = VecDeque::new();
+        for line in BufReader::new(pipe).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            tracing::debug!("ichiran: {line}");
+            if lines.len() == STDERR_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+        lines.into_iter().collect::<Vec<_>>().join("\n")
+    })
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use std::{os::unix::fs::PermissionsExt, time::Instant};
+
+    // creates a stand-in for the ichiran CLI that runs the given shell script,
+    // `$LOG` in the script is replaced with the path of a file the script can write to
+    fn fake_cli(name: &str, script: &str) -> (PathBuf, PathBuf) {
+        let path = std::env::temp_dir().join(format!("lbr-{name}-{}", std::process::id()));
+        let log = path.with_extension("log");
+        let script = script.replace("$LOG", &log.to_string_lossy());
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let _ = std::fs::remove_file(&log);
+        (path, log)
+    }
+
+    fn segmenter(cli: PathBuf) -> IchiranSegmenter {
+        IchiranSegmenter::new(
+            cli,
+            HashMap::new(),
+            Arc::new(HashMap::new()),
+            HashMap::new(),
+        )
+    }
+
+    fn starts(log: &Path) -> usize {
+        std::fs::read_to_string(log).unwrap().lines().count()
+    }
+
+    #[test]
+    fn reuses_ichiran_process() {
+        let (cli, log) = fake_cli(
+            "reused-ichiran",
+            "echo started >> $LOG\nwhile read -r line; do echo '[]'; done",
+        );
+        let segmenter = segmenter(cli.clone());
+        for _ in 0..3 {
+            let segments = segmenter.ichiran_segments("猫", None).unwrap();
+            assert!(segments.is_empty());
+        }
+        assert_eq!(starts(&log), 1);
+        std::fs::remove_file(cli).unwrap();
+        std::fs::remove_file(log).unwrap();
+    }
+
+    #[test]
+    fn kills_ichiran_after_timeout() {
+        // only the first process hangs
+        let (cli, log) = fake_cli(
+            "hanging-ichiran",
+            "echo started >> $LOG
+while read -r line; do
+    if [ \"$(wc -l < $LOG)\" -eq 1 ]; then sleep 10; fi
+    echo '[]'
+done",
+        );
+        let segmenter = segmenter(cli.clone());
+        let started = Instant::now();
+        let err = segmenter
+            .ichiran_segments("猫", Some(Duration::from_millis(50)))
+            .unwrap_err();
+        assert!(matches!(err, SegmenterError::Timeout(_)));
+
+        // the hung process is replaced by a new one
+        let segments = segmenter
+            .ichiran_segments("猫", Some(Duration::from_secs(5)))
+            .unwrap();
+        assert!(segments.is_empty());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(starts(&log), 2);
+        std::fs::remove_file(cli).unwrap();
+        std::fs::remove_file(log).unwrap();
+    }
+
+    #[test]
+    fn restarts_crashed_ichiran() {
+        let (cli, log) = fake_cli(
+            "crashing-ichiran",
+            "echo started >> $LOG\necho 'database error' >&2\nexit 1",
+        );
+        let segmenter = segmenter(cli.clone());
+        for expected_starts in 1..=2 {
+            let err = segmenter.ichiran_segments("猫", None).unwrap_err();
+            let SegmenterError::IchiranExit { stderr, .. } = err else {
+                panic!("unexpected error {err}");
+            };
+            assert_eq!(stderr.trim(), "database error");
+            assert_eq!(starts(&log), expected_starts);
+        }
+        std::fs::remove_file(cli).unwrap();
+        std::fs::remove_file(log).unwrap();
+    }
+
+    #[test]
+    fn reports_segmentation_errors() {
+        let (cli, _) = fake_cli(
+            "failing-ichiran",
+            r#"while read -r line; do echo '{"error": "no connection"}'; done"#,
+        );
+        let segmenter = segmenter(cli.clone());
+        let err = segmenter.ichiran_segments("猫", None).unwrap_err();
+        assert!(matches!(err, SegmenterError::IchiranFailed(e) if e == "no connection"));
+        std::fs::remove_file(cli).unwrap();
+    }
+}
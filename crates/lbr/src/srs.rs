@@ -0,0 +1,143 @@
+//! Spaced repetition scheduling for reviewing cards without Anki.
+//! Uses a variant of the SM-2 algorithm similar to the one used by Anki.
+//! See <https://super-memory.com/english/ol/sm2.htm> for the original algorithm.
+
+use std::time::Duration;
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+/// How soon a card is shown again after it is forgotten.
+const RELEARN_DELAY: Duration = Duration::from_secs(10 * 60);
+const INITIAL_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+const HARD_MULTIPLIER: f64 = 1.2;
+const EASY_BONUS: f64 = 1.3;
+
+/// How well the user remembered the card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rating {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+/// The scheduling state of a card.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CardState {
+    /// How long after the latest review the card is due.
+    pub interval: Duration,
+    /// Multiplier for the interval after a successful review.
+    pub ease: f64,
+    /// Successful reviews in a row.
+    pub repetitions: u32,
+    /// How many times the card has been forgotten.
+    pub lapses: u32,
+}
+
+impl CardState {
+    /// The state of a card that has never been reviewed.
+    pub fn new() -> Self {
+        Self {
+            interval: Duration::ZERO,
+            ease: INITIAL_EASE,
+            repetitions: 0,
+            lapses: 0,
+        }
+    }
+
+    pub fn is_new(&self) -> bool {
+        self.repetitions == 0 && self.lapses == 0
+    }
+}
+
+impl Default for CardState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Calculates the card's next state after it was reviewed with the given rating.
+pub fn review(state: &CardState, rating: Rating) -> CardState {
+    let CardState {
+        interval,
+        ease,
+        repetitions,
+        lapses,
+    } = *state;
+
+    match rating {
+        Rating::Again => CardState {
+            interval: RELEARN_DELAY,
+            ease: (ease - 0.2).max(MIN_EASE),
+            repetitions: 0,
+            // forgetting a new card is not a lapse
+            lapses: if repetitions > 0 { lapses + 1 } else { lapses },
+        },
+        Rating::Hard => CardState {
+            interval: match repetitions {
+                0 => DAY,
+                _ => interval.mul_f64(HARD_MULTIPLIER).max(DAY),
+            },
+            ease: (ease - 0.15).max(MIN_EASE),
+            repetitions: repetitions + 1,
+            lapses,
+        },
+        Rating::Good => CardState {
+            interval: match repetitions {
+                0 => DAY,
+                1 => 6 * DAY,
+                _ => interval.mul_f64(ease).max(DAY),
+            },
+            ease,
+            repetitions: repetitions + 1,
+            lapses,
+        },
+        Rating::Easy => CardState {
+            interval: match repetitions {
+                0 => 4 * DAY,
+                _ => interval.mul_f64(ease * EASY_BONUS).max(DAY),
+            },
+            ease: ease + 0.15,
+            repetitions: repetitions + 1,
+            lapses,
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn schedules_successful_reviews() {
+        let state = review(&CardState::new(), Rating::Good);
+        assert_eq!(state.interval, DAY);
+        let state = review(&state, Rating::Good);
+        assert_eq!(state.interval, 6 * DAY);
+        let state = review(&state, Rating::Good);
+        assert_eq!(state.interval, 15 * DAY);
+        assert_eq!(state.repetitions, 3);
+        assert_eq!(state.ease, INITIAL_EASE);
+    }
+
+    #[test]
+    fn resets_forgotten_cards() {
+        let state = review(&CardState::new(), Rating::Again);
+        assert_eq!(state.lapses, 0);
+        let state = review(&review(&state, Rating::Good), Rating::Good);
+        let state = review(&state, Rating::Again);
+        assert_eq!(state.interval, RELEARN_DELAY);
+        assert_eq!(state.repetitions, 0);
+        assert_eq!(state.lapses, 1);
+        assert!(!state.is_new());
+    }
+
+    #[test]
+    fn keeps_ease_above_minimum() {
+        let mut state = CardState::new();
+        for _ in 0..20 {
+            state = review(&state, Rating::Hard);
+        }
+        assert_eq!(state.ease, MIN_EASE);
+    }
+}
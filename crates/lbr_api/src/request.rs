@@ -1,5 +1,10 @@
 //! Types for requests from the frontend to the backend.
 
+pub use crate::response::{
+    CardKind, CardOrder, CardTemplate, DeckRules, ExamplePreference, NoteType, Timestamps,
+    WordCardTypes, WordStatus,
+};
+pub use lbr_core::ruby::Ruby;
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, collections::HashSet};
 
@@ -25,6 +30,12 @@ pub struct UpdateSource<'a> {
     pub name: Cow<'a, str>,
 }
 
+/// Query parameters for importing a file into a source, the file itself is sent as the request body.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Import<'a> {
+    pub filename: Cow<'a, str>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct NewDeck<'a> {
     pub name: Cow<'a, str>,
@@ -34,6 +45,12 @@ pub struct NewDeck<'a> {
 pub struct UpdateDeck<'a> {
     pub name: Cow<'a, str>,
     pub included_sources: Cow<'a, [IncludedSource]>,
+    #[serde(default)]
+    pub exclude_known: bool,
+    #[serde(default)]
+    pub rules: DeckRules,
+    #[serde(default)]
+    pub card_types: WordCardTypes,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -83,6 +100,59 @@ pub struct SegmentedSentence {
     pub sentence: String,
     pub words: Vec<Word>,
     pub ignore_words: HashSet<i32>,
+    /// Readings given by the source text, preferred over the mapped readings for furigana.
+    #[serde(default)]
+    pub ruby: Vec<Ruby>,
+    /// When the sentence is spoken, for sentences from subtitles.
+    #[serde(default)]
+    pub timestamps: Option<Timestamps>,
+}
+
+/// The result of reviewing a card.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct Review {
+    pub kind: CardKind,
+    pub card_id: i32,
+    pub rating: ReviewRating,
+}
+
+/// Sets the note type used for a deck's word or kanji cards.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateNoteType {
+    pub kind: CardKind,
+    /// The default note type is used if not set.
+    pub note_type: Option<NoteType>,
+}
+
+/// Chooses a new example for a deck's card.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct Reroll {
+    pub kind: CardKind,
+    pub card_id: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ReviewRating {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+/// Sets which dictionaries' definitions are shown to the user, the rest are hidden.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EnabledDictionaries {
+    pub dictionary_ids: Vec<i32>,
+}
+
+/// Sets the user's status for a word.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct WordStatusUpdate {
+    pub word_id: i32,
+    pub status: WordStatus,
+    /// The learning level from 1 to 5, the current level is kept if not given.
+    #[serde(default)]
+    pub level: Option<i32>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
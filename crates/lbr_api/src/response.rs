@@ -2,9 +2,14 @@
 
 pub use chrono::{DateTime, Utc};
 use lbr_core::ichiran_types;
-pub use lbr_core::ichiran_types::{Meaning, Segment, WordInfo};
+pub use lbr_core::{
+    ichiran_types::{Meaning, Segment, WordInfo},
+    ruby::Ruby,
+    word_definitions::WordDefinitions,
+    word_sense::WordSense,
+};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, ops::Range};
+use std::{collections::HashMap, ops::Range};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Error {
@@ -35,6 +40,190 @@ pub struct DeckDetails {
     pub id: i32,
     pub name: String,
     pub sources: Vec<DeckSource>,
+    /// Whether words the user already knows are left out of the deck.
+    pub exclude_known: bool,
+    pub rules: DeckRules,
+    pub card_types: WordCardTypes,
+}
+
+/// Which kinds of word cards are created in addition to the sentence cards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordCardTypes {
+    /// Cards that show the word and ask for its reading.
+    pub reading: bool,
+    /// Cards that show the translation and the sentence without the word and ask for the word.
+    pub production: bool,
+    /// Cloze deletion cards that hide the word in the sentence.
+    pub cloze: bool,
+}
+
+/// Rules for choosing which cards are included in a deck, in which order and with which examples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckRules {
+    pub order: CardOrder,
+    /// The maximum number of word cards, unlimited if not set.
+    pub max_word_cards: Option<i32>,
+    /// The maximum number of kanji cards, unlimited if not set.
+    pub max_kanji_cards: Option<i32>,
+    /// Words that appear fewer times than this in the deck's word sources are left out.
+    pub min_word_frequency: Option<i32>,
+    /// Words that appear more times than this in the deck's word sources are left out.
+    pub max_word_frequency: Option<i32>,
+    /// Kanji with a higher school grade than this or without a grade are left out.
+    /// The grades are 1-6 for kyouiku kanji, 8 for the rest of the jouyou kanji and 9-10 for jinmeiyou kanji.
+    pub max_kanji_grade: Option<i32>,
+    /// Kanji with a lower (old) JLPT level than this or without a level are left out.
+    /// The levels range from 4 (easiest) to 1 (hardest).
+    pub min_kanji_jlpt: Option<i32>,
+    /// How many example sentences are shown on word cards in addition to the main one.
+    #[serde(default)]
+    pub extra_examples: i32,
+    #[serde(default)]
+    pub example_preference: ExamplePreference,
+    /// Words with a lower frequency score than this are left out, see [`ApiInterpretation::frequency_score`].
+    #[serde(default)]
+    pub min_word_frequency_score: Option<i32>,
+    /// The frequency list used for ordering word cards by how common they are and for the rank threshold.
+    /// The frequency scores from JMdict are used if not set.
+    #[serde(default)]
+    pub frequency_list_id: Option<i32>,
+    /// Words ranked lower than this in the frequency list or not in the list at all are left out.
+    #[serde(default)]
+    pub max_word_frequency_rank: Option<i32>,
+}
+
+/// The order in which a deck's cards are introduced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CardOrder {
+    /// The words and kanji that appear the most in the deck's sources first.
+    #[default]
+    Frequency,
+    /// The words and kanji that appear the earliest in the deck's sources first.
+    Appearance,
+    Random,
+    /// The words that are the most common in Japanese first, according to the deck's frequency list if it has one
+    /// and their frequency scores otherwise. Kanji are ordered by their frequency in KANJIDIC2.
+    Common,
+    /// The kanji taught the earliest in school first, with the most common ones first within each grade.
+    /// Words are ordered the same way as with [`CardOrder::Common`].
+    Grade,
+}
+
+/// A kanji with its information from KANJIDIC2.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Kanji {
+    pub id: i32,
+    pub kanji: String,
+    /// Unofficial English name for the kanji.
+    pub name: Option<String>,
+    pub meanings: Vec<String>,
+    /// The kanji and radicals the kanji is made of.
+    pub components: Vec<String>,
+    pub readings: Vec<KanjiReading>,
+    /// The school grade the kanji is taught in, 1-6 for elementary school, 8 for the rest of the jouyou kanji
+    /// and 9-10 for jinmeiyou kanji.
+    pub grade: Option<i32>,
+    /// The level in the old four level JLPT, from 4 (easiest) to 1 (hardest).
+    pub jlpt: Option<i32>,
+    pub stroke_count: Option<i32>,
+    /// The kanji's rank among the 2500 most used kanji in newspapers, the most common kanji has the rank 1.
+    pub frequency: Option<i32>,
+    /// The number of the kanji's classical radical, from 1 to 214.
+    pub radical: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KanjiReading {
+    pub reading: String,
+    /// The kana following the kanji in kun'yomi readings such as 食べる.
+    pub okurigana: Option<String>,
+    /// Whether the reading is only used in names.
+    pub nanori: bool,
+}
+
+/// A list of words ranked by how common they are in some corpus, such as novels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrequencyList {
+    pub id: i32,
+    pub name: String,
+}
+
+/// A dictionary imported in addition to JMdict, such as a monolingual dictionary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dictionary {
+    pub id: i32,
+    pub name: String,
+    /// Whether the user has chosen to show the dictionary's definitions in the analysis view and on cards.
+    pub enabled: bool,
+}
+
+/// Which sentences are preferred when choosing extra examples for word cards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExamplePreference {
+    #[default]
+    Shortest,
+    /// The sentences with the fewest words the user doesn't know yet.
+    KnownWords,
+    /// Sentences from as many different sources as possible.
+    SourceDiversity,
+}
+
+/// The note types used for a deck's Anki cards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckNoteTypes {
+    pub word: DeckNoteType,
+    pub kanji: DeckNoteType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckNoteType {
+    pub note_type: NoteType,
+    /// Whether the deck has its own note type instead of the default one.
+    pub custom: bool,
+    /// The fields that can be used in the card templates.
+    pub fields: Vec<String>,
+}
+
+/// The styling and card templates of an Anki note type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteType {
+    pub css: String,
+    /// A card is created from each template for every note.
+    pub templates: Vec<CardTemplate>,
+}
+
+/// The front and back of a card, written with Anki's template syntax.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardTemplate {
+    pub name: String,
+    pub question: String,
+    pub answer: String,
+}
+
+/// The cards a deck would currently be generated with, in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckPreview {
+    pub word_cards: Vec<PreviewCard>,
+    pub kanji_cards: Vec<PreviewCard>,
+}
+
+/// A card in a deck preview, rendered the same way as in the generated Anki decks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewCard {
+    pub kind: CardKind,
+    pub card_id: i32,
+    /// The card's word or kanji.
+    pub text: String,
+    /// How many times the word or kanji appears in the deck's sources.
+    pub count: i32,
+    /// The example sentence of a word card or the example word of a kanji card.
+    pub example: String,
+    /// HTML for the front of the card.
+    pub question: String,
+    /// HTML for the back of the card.
+    pub answer: String,
+    /// Styling for the card, the card should be rendered within an element with the class `card`.
+    pub css: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +239,38 @@ pub enum DeckSourceKind {
     Word,
 }
 
+/// A card that is due for review, rendered the same way as in the generated Anki decks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewCard {
+    pub kind: CardKind,
+    pub card_id: i32,
+    /// HTML for the front of the card.
+    pub question: String,
+    /// HTML for the back of the card.
+    pub answer: String,
+    /// Styling for the card, the card should be rendered within an element with the class `card`.
+    pub css: String,
+    /// Whether the card has never been reviewed.
+    pub new: bool,
+}
+
+/// A summary of the review history imported from Anki.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewHistoryImport {
+    /// How many of the cards in the collection were found in LBR.
+    pub cards: i32,
+    /// How many new reviews were imported.
+    pub reviews: i32,
+    /// How many words were marked as known.
+    pub known_words: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CardKind {
+    Word,
+    Kanji,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IgnoredWord {
     pub word_id: i32,
@@ -67,6 +288,7 @@ pub struct IgnoredWordWrittenForm {
 pub struct Sentence {
     pub id: i32,
     pub sentence: String,
+    pub timestamps: Option<Timestamps>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,10 +297,27 @@ pub struct SentenceDetails {
     pub source_id: i32,
     pub sentence: String,
     pub words: Vec<SentenceWord>,
+    pub timestamps: Option<Timestamps>,
+}
+
+/// When a sentence from subtitles is spoken, in milliseconds from the start of the video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timestamps {
+    pub start_ms: i32,
+    pub end_ms: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceReader {
+    pub id: i32,
+    pub name: String,
+    pub sentences: Vec<SentenceDetails>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SentenceWord {
+    pub word_id: i32,
+    pub status: WordStatus,
     pub word: String,
     pub reading: String,
     pub sentence_word_reading: Option<String>,
@@ -88,6 +327,26 @@ pub struct SentenceWord {
     pub translations: Vec<String>,
 }
 
+/// How well the user knows a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WordStatus {
+    Unknown,
+    Learning,
+    Known,
+    Ignored,
+}
+
+/// The user's status for a word along with its history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserWord {
+    pub word_id: i32,
+    pub status: WordStatus,
+    /// How far along the user is in learning the word, from 1 to 5 for learning words.
+    pub level: i32,
+    pub first_seen: DateTime<Utc>,
+    pub last_changed: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Furigana {
     pub word_start_idx: i32,
@@ -99,20 +358,74 @@ pub struct Furigana {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SegmentedParagraph {
     pub sentences: Vec<SegmentedParagraphSentence>,
-    pub ignored_words: HashSet<i32>,
+    /// The user's statuses for the words in the paragraph, words without a status are unknown.
+    pub word_statuses: HashMap<i32, WordStatus>,
+    /// The words' definitions in the dictionaries the user has chosen to show.
+    pub word_definitions: HashMap<i32, Vec<WordDefinitions>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentationJob {
+    pub id: i32,
+    pub source_id: i32,
+    pub title: Option<String>,
+    pub status: SegmentationJobStatus,
+    pub sentences: Vec<SegmentationJobSentence>,
+    /// The number of sentences that could not be segmented.
+    pub failed: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SegmentationJobStatus {
+    Queued,
+    Running,
+    Finished,
+    Failed,
+}
+
+impl SegmentationJobStatus {
+    /// Whether the job is finished or failed and will not make any more progress.
+    pub fn is_done(self) -> bool {
+        matches!(self, Self::Finished | Self::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentationJobSummary {
+    pub id: i32,
+    pub title: Option<String>,
+    pub status: SegmentationJobStatus,
+    pub sentences: i32,
+    pub segmented: i32,
+    /// The number of sentences that could not be segmented, these count as processed.
+    pub failed: i32,
+}
+
+/// The progress of a single sentence in a segmentation job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentationJobSentence {
+    pub sentence: String,
+    pub segmented: bool,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SegmentedParagraphSentence {
     pub sentence: String,
     pub segments: Vec<ApiSegment>,
+    /// Readings given by the source text, e.g. by an imported Aozora Bunko text.
+    pub ruby: Vec<Ruby>,
+    pub timestamps: Option<Timestamps>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SegmentedSentence {
     pub sentence: String,
     pub segments: Vec<ApiSegment>,
-    pub ignored_words: HashSet<i32>,
+    /// The user's statuses for the words in the sentence, words without a status are unknown.
+    pub word_statuses: HashMap<i32, WordStatus>,
+    /// The words' definitions in the dictionaries the user has chosen to show.
+    pub word_definitions: HashMap<i32, Vec<WordDefinitions>>,
 }
 
 /// A segment of text, a single word or punctuation etc.
@@ -132,7 +445,7 @@ pub struct ApiSegment {
 pub struct ApiInterpretation {
     /// LBR word id.
     pub word_id: Option<i32>,
-    /// The higher the score, the more likely this is the correct interpretation according to ichiran.
+    /// The higher the score, the more likely this is the correct interpretation according to the segmenter.
     pub score: i32,
     /// The word as it appears in the text.
     pub text_word: String,
@@ -142,6 +455,14 @@ pub struct ApiInterpretation {
     pub db_word: String,
     /// The reading of the word as it appears in the database.
     pub db_reading_hiragana: String,
+    /// The mora after which the pitch drops for each of the word's pitch accents, 0 if it doesn't drop.
+    pub pitch_accents: Vec<u32>,
+    /// How common the word is from 0 to 100 based on its priority tags in JMdict.
+    pub frequency_score: i32,
     /// List of possible meanings for the word.
     pub meanings: Vec<ichiran_types::Meaning>,
+    /// The word's senses in JMdict with their parts of speech and other usage information.
+    pub senses: Vec<WordSense>,
+    /// Descriptions of the inflections that produce the word in the text from its dictionary form.
+    pub inflections: Vec<String>,
 }
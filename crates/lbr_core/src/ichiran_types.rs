@@ -20,7 +20,7 @@ pub struct Segment {
 pub struct Interpretation {
     /// LBR word id.
     pub word_id: Option<i32>,
-    /// The higher the score, the more likely this is the correct interpretation according to ichiran.
+    /// The higher the score, the more likely this is the correct interpretation according to the segmenter.
     pub score: i32,
     /// The word as it appears in the text.
     pub word: String,
@@ -28,6 +28,9 @@ pub struct Interpretation {
     pub reading_hiragana: String,
     /// List of possible meanings for the word.
     pub meanings: Vec<Meaning>,
+    /// Descriptions of the inflections that produce the word in the text from its dictionary form,
+    /// empty if the word is not inflected.
+    pub inflections: Vec<String>,
 }
 
 /// Information for an interpretation of a single word.
@@ -3,6 +3,11 @@
 use std::ops::RangeInclusive;
 
 pub mod ichiran_types;
+pub mod pitch_accent;
+pub mod ruby;
+pub mod word_definitions;
+pub mod word_priority;
+pub mod word_sense;
 
 // ichiran sometimes returns characters in a slightly different, equivalent form
 // this function lets us ignore the differences
@@ -0,0 +1,86 @@
+//! Functions for displaying pitch accents.
+
+/// A mora of a reading and its pitch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mora<'a> {
+    pub text: &'a str,
+    pub high: bool,
+    /// Whether the pitch drops after this mora.
+    pub downstep: bool,
+}
+
+/// Splits the reading into morae, small kana such as the ょ in きょ are a part of the preceding mora.
+pub fn morae(reading: &str) -> Vec<&str> {
+    const SMALL_KANA: &str = "ぁぃぅぇぉゃゅょゎァィゥェォャュョヮ";
+
+    let mut morae = Vec::new();
+    let mut start = 0;
+    for (idx, c) in reading.char_indices().skip(1) {
+        if !SMALL_KANA.contains(c) {
+            morae.push(&reading[start..idx]);
+            start = idx;
+        }
+    }
+    if start < reading.len() {
+        morae.push(&reading[start..]);
+    }
+    morae
+}
+
+/// Returns the pitch of each mora of the reading with the given accent,
+/// which is the mora after which the pitch drops or 0 for readings where it doesn't drop.
+pub fn pitch_accent(reading: &str, accent: u32) -> Vec<Mora<'_>> {
+    let accent = accent as usize;
+    morae(reading)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, text)| {
+            let position = idx + 1;
+            let high = match accent {
+                // heiban, the pitch rises after the first mora and stays high
+                0 => position > 1,
+                // atamadaka, only the first mora is high
+                1 => position == 1,
+                // nakadaka and odaka, high from the second mora until the downstep
+                _ => position > 1 && position <= accent,
+            };
+            Mora {
+                text,
+                high,
+                downstep: accent != 0 && position == accent,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pitches(reading: &str, accent: u32) -> String {
+        pitch_accent(reading, accent)
+            .into_iter()
+            .map(|m| match (m.high, m.downstep) {
+                (true, true) => "H\\",
+                (true, false) => "H",
+                (false, _) => "L",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_morae() {
+        assert_eq!(morae("きょうと"), ["きょ", "う", "と"]);
+        assert_eq!(morae("がっこう"), ["が", "っ", "こ", "う"]);
+        assert_eq!(morae("チョコレート"), ["チョ", "コ", "レ", "ー", "ト"]);
+        assert!(morae("").is_empty());
+    }
+
+    #[test]
+    fn marks_pitches() {
+        assert_eq!(pitches("さくら", 0), "LHH");
+        assert_eq!(pitches("いのち", 1), "H\\LL");
+        assert_eq!(pitches("ひこうき", 2), "LH\\LL");
+        assert_eq!(pitches("おとうと", 4), "LHHH\\");
+    }
+}
@@ -0,0 +1,13 @@
+//! Types for readings annotated in the source text.
+
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// A reading given by the text itself for part of a sentence, such as Aozora Bunko's `吾輩《わがはい》`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ruby {
+    /// The range of the annotated text in the sentence.
+    pub range: Range<usize>,
+    /// The reading of the annotated text.
+    pub reading: String,
+}
@@ -0,0 +1,12 @@
+//! Types for the definitions of words in additional dictionaries.
+
+use serde::{Deserialize, Serialize};
+
+/// A word's definitions in one of the dictionaries imported in addition to JMdict, such as a monolingual dictionary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordDefinitions {
+    /// The name of the dictionary.
+    pub dictionary: String,
+    /// The definitions as plain text, which may contain line breaks.
+    pub definitions: Vec<String>,
+}
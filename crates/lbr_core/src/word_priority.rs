@@ -0,0 +1,53 @@
+//! Functions for ranking words by their JMdict priority tags.
+//! See <https://www.edrdg.org/jmwsgi/edhelp.py?svc=jmdict#kw_freq>
+
+/// Words with at least this frequency score are considered common, like the words marked as common in JMdict.
+pub const COMMON_SCORE: i32 = 50;
+
+/// Combines a word's priority tags such as `news1`, `ichi1` or `nf12` into a score from 0 to 100,
+/// the higher the score the more common the word. Words without any priority tags have a score of 0.
+pub fn frequency_score<'a>(tags: impl IntoIterator<Item = &'a str>) -> i32 {
+    tags.into_iter()
+        .map(|tag| match tag {
+            // the lists JMdict uses to mark words as common
+            "news1" | "ichi1" | "spec1" | "spec2" | "gai1" => COMMON_SCORE,
+            "news2" | "ichi2" | "gai2" => COMMON_SCORE / 2,
+            // the newspaper frequency rank in bands of 500 words from nf01 to nf48,
+            // the first 24 bands are news1 words and score higher than other common words
+            _ => tag
+                .strip_prefix("nf")
+                .and_then(|rank| rank.parse::<i32>().ok())
+                .map(|rank| (99 - rank * 2).clamp(1, 100))
+                .unwrap_or_default(),
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+/// Whether a word with the given frequency score is considered common.
+pub fn is_common(frequency_score: i32) -> bool {
+    frequency_score >= COMMON_SCORE
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scores_priority_tags() {
+        assert_eq!(frequency_score([]), 0);
+        assert_eq!(frequency_score(["ichi1", "news1", "nf01"]), 97);
+        assert_eq!(frequency_score(["spec1"]), 50);
+        assert_eq!(frequency_score(["news2", "nf25"]), 49);
+        assert_eq!(frequency_score(["news2", "nf48"]), 25);
+        assert_eq!(frequency_score(["unknown"]), 0);
+    }
+
+    #[test]
+    fn marks_common_words() {
+        assert!(is_common(frequency_score(["news1", "nf24"])));
+        assert!(is_common(frequency_score(["spec2"])));
+        assert!(!is_common(frequency_score(["news2", "nf25"])));
+        assert!(!is_common(frequency_score(["ichi2"])));
+    }
+}
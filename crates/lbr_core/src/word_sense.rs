@@ -0,0 +1,81 @@
+//! Types for the senses of dictionary words.
+
+use serde::{Deserialize, Serialize};
+
+/// One of the meanings of a word in JMdict along with its usage information.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordSense {
+    /// English translations of the sense.
+    pub glosses: Vec<String>,
+    /// Abbreviated parts of speech such as `n` or `vs`.
+    pub parts_of_speech: Vec<String>,
+    /// Other information such as "colloquial" or "word usually written using kana alone".
+    pub misc: Vec<String>,
+    /// Fields of application such as "computing".
+    pub fields: Vec<String>,
+    /// Dialects such as "Kansai-ben".
+    pub dialects: Vec<String>,
+    /// Additional explanations of the sense.
+    pub info: Vec<String>,
+    /// Related words.
+    pub cross_references: Vec<String>,
+    /// Words with the opposite meaning.
+    pub antonyms: Vec<String>,
+}
+
+impl WordSense {
+    /// Tags describing the usage of the sense, such as "colloquial" or "Kansai-ben".
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.misc
+            .iter()
+            .chain(&self.fields)
+            .chain(&self.dialects)
+            .map(String::as_str)
+    }
+
+    /// Formats the sense on one line, e.g. "(n, vs) to study; (colloquial)".
+    pub fn summary(&self) -> String {
+        let mut summary = String::new();
+        if !self.parts_of_speech.is_empty() {
+            summary.push('(');
+            summary.push_str(&self.parts_of_speech.join(", "));
+            summary.push_str(") ");
+        }
+        summary.push_str(&self.glosses.join("; "));
+        let tags = self.tags().collect::<Vec<_>>();
+        if !tags.is_empty() {
+            summary.push_str("; (");
+            summary.push_str(&tags.join(", "));
+            summary.push(')');
+        }
+        if !self.info.is_empty() {
+            summary.push_str(" [");
+            summary.push_str(&self.info.join(", "));
+            summary.push(']');
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn summarises_sense() {
+        let sense = WordSense {
+            glosses: vec!["to study".to_string()],
+            parts_of_speech: vec!["n".to_string(), "vs".to_string()],
+            misc: vec!["colloquial".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(sense.summary(), "(n, vs) to study; (colloquial)");
+
+        let sense = WordSense {
+            glosses: vec!["cat".to_string(), "feline".to_string()],
+            info: vec!["esp. domestic".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(sense.summary(), "cat; feline [esp. domestic]");
+    }
+}
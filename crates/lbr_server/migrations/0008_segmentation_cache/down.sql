@@ -0,0 +1 @@
+DROP TABLE segmentation_cache;
\ No newline at end of file
@@ -0,0 +1,6 @@
+CREATE TABLE segmentation_cache (
+    sentence_hash BYTEA NOT NULL,
+    ichiran_version TEXT NOT NULL,
+    segments JSONB NOT NULL,
+    PRIMARY KEY (sentence_hash, ichiran_version)
+);
\ No newline at end of file
@@ -0,0 +1,3 @@
+DROP TABLE segmentation_job_sentences;
+DROP TABLE segmentation_jobs;
+DROP TYPE SEGMENTATION_JOB_STATUS;
\ No newline at end of file
@@ -0,0 +1,15 @@
+CREATE TYPE SEGMENTATION_JOB_STATUS AS ENUM ('queued', 'running', 'finished', 'failed');
+CREATE TABLE segmentation_jobs (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users,
+    source_id INTEGER NOT NULL REFERENCES sources,
+    status SEGMENTATION_JOB_STATUS NOT NULL DEFAULT 'queued'
+);
+CREATE TABLE segmentation_job_sentences (
+    job_id INTEGER NOT NULL REFERENCES segmentation_jobs,
+    idx INTEGER NOT NULL,
+    sentence TEXT NOT NULL,
+    segments JSONB,
+    error TEXT,
+    PRIMARY KEY (job_id, idx)
+);
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE segmentation_jobs DROP COLUMN title;
\ No newline at end of file
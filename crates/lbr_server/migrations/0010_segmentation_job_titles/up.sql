@@ -0,0 +1 @@
+ALTER TABLE segmentation_jobs ADD COLUMN title TEXT;
\ No newline at end of file
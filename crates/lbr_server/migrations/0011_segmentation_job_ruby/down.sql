@@ -0,0 +1 @@
+ALTER TABLE segmentation_job_sentences DROP COLUMN ruby;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE segmentation_job_sentences ADD COLUMN ruby JSONB NOT NULL DEFAULT '[]';
\ No newline at end of file
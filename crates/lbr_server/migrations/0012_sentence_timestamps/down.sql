@@ -0,0 +1,2 @@
+ALTER TABLE segmentation_job_sentences DROP COLUMN start_ms, DROP COLUMN end_ms;
+ALTER TABLE sentences DROP COLUMN start_ms, DROP COLUMN end_ms;
\ No newline at end of file
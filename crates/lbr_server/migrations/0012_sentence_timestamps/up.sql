@@ -0,0 +1,2 @@
+ALTER TABLE sentences ADD COLUMN start_ms INTEGER, ADD COLUMN end_ms INTEGER;
+ALTER TABLE segmentation_job_sentences ADD COLUMN start_ms INTEGER, ADD COLUMN end_ms INTEGER;
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE ignored_words (
+    word_id INTEGER NOT NULL REFERENCES words,
+    user_id INTEGER NOT NULL REFERENCES users,
+    PRIMARY KEY (word_id, user_id)
+);
+INSERT INTO ignored_words (word_id, user_id)
+SELECT word_id, user_id FROM user_words WHERE status = 'ignored';
+ALTER TABLE decks DROP COLUMN exclude_known;
+DROP TABLE user_words;
+DROP TYPE WORD_STATUS;
\ No newline at end of file
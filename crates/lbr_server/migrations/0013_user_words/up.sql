@@ -0,0 +1,21 @@
+CREATE TYPE WORD_STATUS AS ENUM ('unknown', 'learning', 'known', 'ignored');
+CREATE TABLE user_words (
+    user_id INTEGER NOT NULL REFERENCES users,
+    word_id INTEGER NOT NULL REFERENCES words,
+    status WORD_STATUS NOT NULL,
+    level INTEGER NOT NULL DEFAULT 0,
+    first_seen TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_changed TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, word_id)
+);
+INSERT INTO user_words (user_id, word_id, status)
+SELECT user_id, word_id, 'ignored' FROM ignored_words;
+INSERT INTO user_words (user_id, word_id, status, level)
+SELECT DISTINCT so.user_id, sw.word_id, 'learning'::WORD_STATUS, 1
+FROM sentence_words sw
+JOIN sentences se ON se.id = sw.sentence_id
+JOIN sources so ON so.id = se.source_id
+WHERE sw.word_id IS NOT NULL
+ON CONFLICT DO NOTHING;
+ALTER TABLE decks ADD COLUMN exclude_known BOOLEAN NOT NULL DEFAULT FALSE;
+DROP TABLE ignored_words;
\ No newline at end of file
@@ -0,0 +1,4 @@
+DROP TABLE review_logs;
+DROP TABLE card_schedules;
+DROP TYPE REVIEW_RATING;
+DROP TYPE CARD_KIND;
\ No newline at end of file
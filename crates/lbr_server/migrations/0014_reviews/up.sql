@@ -0,0 +1,24 @@
+CREATE TYPE CARD_KIND AS ENUM ('word', 'kanji');
+CREATE TYPE REVIEW_RATING AS ENUM ('again', 'hard', 'good', 'easy');
+CREATE TABLE card_schedules (
+    user_id INTEGER NOT NULL REFERENCES users,
+    kind CARD_KIND NOT NULL,
+    card_id INTEGER NOT NULL,
+    due TIMESTAMPTZ NOT NULL,
+    interval_secs BIGINT NOT NULL,
+    ease DOUBLE PRECISION NOT NULL,
+    repetitions INTEGER NOT NULL,
+    lapses INTEGER NOT NULL,
+    PRIMARY KEY (user_id, kind, card_id)
+);
+CREATE TABLE review_logs (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users,
+    kind CARD_KIND NOT NULL,
+    card_id INTEGER NOT NULL,
+    rating REVIEW_RATING NOT NULL,
+    reviewed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    interval_secs BIGINT NOT NULL,
+    ease DOUBLE PRECISION NOT NULL
+);
+CREATE INDEX review_logs_card ON review_logs (user_id, kind, card_id);
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE deck_cards;
\ No newline at end of file
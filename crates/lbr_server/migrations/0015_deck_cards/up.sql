@@ -0,0 +1,9 @@
+CREATE TABLE deck_cards (
+    deck_id INTEGER NOT NULL REFERENCES decks,
+    kind CARD_KIND NOT NULL,
+    card_id INTEGER NOT NULL,
+    sentence_id INTEGER,
+    example_word_id INTEGER,
+    chosen_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (deck_id, kind, card_id)
+);
\ No newline at end of file
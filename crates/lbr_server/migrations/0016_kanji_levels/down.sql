@@ -0,0 +1,2 @@
+ALTER TABLE kanji DROP COLUMN jlpt;
+ALTER TABLE kanji DROP COLUMN grade;
\ No newline at end of file
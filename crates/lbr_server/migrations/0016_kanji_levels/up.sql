@@ -0,0 +1,2 @@
+ALTER TABLE kanji ADD COLUMN grade INTEGER;
+ALTER TABLE kanji ADD COLUMN jlpt INTEGER;
\ No newline at end of file
@@ -0,0 +1,8 @@
+ALTER TABLE decks DROP COLUMN min_kanji_jlpt;
+ALTER TABLE decks DROP COLUMN max_kanji_grade;
+ALTER TABLE decks DROP COLUMN max_word_frequency;
+ALTER TABLE decks DROP COLUMN min_word_frequency;
+ALTER TABLE decks DROP COLUMN max_kanji_cards;
+ALTER TABLE decks DROP COLUMN max_word_cards;
+ALTER TABLE decks DROP COLUMN card_order;
+DROP TYPE CARD_ORDER;
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TYPE CARD_ORDER AS ENUM ('frequency', 'appearance', 'random');
+ALTER TABLE decks ADD COLUMN card_order CARD_ORDER NOT NULL DEFAULT 'frequency';
+ALTER TABLE decks ADD COLUMN max_word_cards INTEGER;
+ALTER TABLE decks ADD COLUMN max_kanji_cards INTEGER;
+ALTER TABLE decks ADD COLUMN min_word_frequency INTEGER;
+ALTER TABLE decks ADD COLUMN max_word_frequency INTEGER;
+ALTER TABLE decks ADD COLUMN max_kanji_grade INTEGER;
+ALTER TABLE decks ADD COLUMN min_kanji_jlpt INTEGER;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE deck_note_types;
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TABLE deck_note_types (
+    deck_id INTEGER NOT NULL REFERENCES decks,
+    kind CARD_KIND NOT NULL,
+    anki_model_id BIGINT NOT NULL,
+    css TEXT NOT NULL,
+    templates JSONB NOT NULL,
+    PRIMARY KEY (deck_id, kind)
+);
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE decks DROP COLUMN cloze_cards;
+ALTER TABLE decks DROP COLUMN production_cards;
+ALTER TABLE decks DROP COLUMN reading_cards;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE decks ADD COLUMN reading_cards BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE decks ADD COLUMN production_cards BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE decks ADD COLUMN cloze_cards BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
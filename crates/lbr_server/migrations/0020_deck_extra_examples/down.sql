@@ -0,0 +1,3 @@
+ALTER TABLE decks DROP COLUMN example_preference;
+ALTER TABLE decks DROP COLUMN extra_examples;
+DROP TYPE EXAMPLE_PREFERENCE;
\ No newline at end of file
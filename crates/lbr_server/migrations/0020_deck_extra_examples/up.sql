@@ -0,0 +1,3 @@
+CREATE TYPE EXAMPLE_PREFERENCE AS ENUM ('shortest', 'known_words', 'source_diversity');
+ALTER TABLE decks ADD COLUMN extra_examples INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE decks ADD COLUMN example_preference EXAMPLE_PREFERENCE NOT NULL DEFAULT 'shortest';
\ No newline at end of file
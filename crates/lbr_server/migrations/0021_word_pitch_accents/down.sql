@@ -0,0 +1 @@
+ALTER TABLE words DROP COLUMN pitch_accents;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE words ADD COLUMN pitch_accents INTEGER[] NOT NULL DEFAULT '{}';
\ No newline at end of file
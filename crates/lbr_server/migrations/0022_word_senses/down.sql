@@ -0,0 +1 @@
+DROP TABLE word_senses;
\ No newline at end of file
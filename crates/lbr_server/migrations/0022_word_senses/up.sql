@@ -0,0 +1,13 @@
+CREATE TABLE word_senses (
+    word_id INTEGER NOT NULL REFERENCES words,
+    idx INTEGER NOT NULL,
+    glosses TEXT [] NOT NULL,
+    parts_of_speech TEXT [] NOT NULL,
+    misc TEXT [] NOT NULL,
+    fields TEXT [] NOT NULL,
+    dialects TEXT [] NOT NULL,
+    info TEXT [] NOT NULL,
+    cross_references TEXT [] NOT NULL,
+    antonyms TEXT [] NOT NULL,
+    PRIMARY KEY (word_id, idx)
+);
\ No newline at end of file
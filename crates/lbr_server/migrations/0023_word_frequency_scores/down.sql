@@ -0,0 +1,11 @@
+ALTER TABLE decks DROP COLUMN min_word_frequency_score;
+-- enum values can't be removed so the type is recreated without it
+UPDATE decks SET card_order = 'frequency' WHERE card_order = 'common';
+ALTER TYPE CARD_ORDER RENAME TO CARD_ORDER_OLD;
+CREATE TYPE CARD_ORDER AS ENUM ('frequency', 'appearance', 'random');
+ALTER TABLE decks ALTER COLUMN card_order DROP DEFAULT;
+ALTER TABLE decks ALTER COLUMN card_order TYPE CARD_ORDER USING card_order::TEXT::CARD_ORDER;
+ALTER TABLE decks ALTER COLUMN card_order SET DEFAULT 'frequency';
+DROP TYPE CARD_ORDER_OLD;
+ALTER TABLE words DROP COLUMN frequency_score;
+ALTER TABLE words DROP COLUMN priority_tags;
\ No newline at end of file
@@ -0,0 +1,4 @@
+ALTER TABLE words ADD COLUMN priority_tags TEXT [] NOT NULL DEFAULT '{}';
+ALTER TABLE words ADD COLUMN frequency_score INTEGER NOT NULL DEFAULT 0;
+ALTER TYPE CARD_ORDER ADD VALUE 'common';
+ALTER TABLE decks ADD COLUMN min_word_frequency_score INTEGER;
\ No newline at end of file
@@ -0,0 +1,4 @@
+ALTER TABLE decks DROP COLUMN max_word_frequency_rank;
+ALTER TABLE decks DROP COLUMN frequency_list_id;
+DROP TABLE word_frequencies;
+DROP TABLE frequency_lists;
\ No newline at end of file
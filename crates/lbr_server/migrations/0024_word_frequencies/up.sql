@@ -0,0 +1,12 @@
+CREATE TABLE frequency_lists (
+    id SERIAL PRIMARY KEY,
+    name TEXT UNIQUE NOT NULL
+);
+CREATE TABLE word_frequencies (
+    list_id INTEGER NOT NULL REFERENCES frequency_lists,
+    word_id INTEGER NOT NULL REFERENCES words,
+    rank INTEGER NOT NULL,
+    PRIMARY KEY (list_id, word_id)
+);
+ALTER TABLE decks ADD COLUMN frequency_list_id INTEGER REFERENCES frequency_lists;
+ALTER TABLE decks ADD COLUMN max_word_frequency_rank INTEGER;
\ No newline at end of file
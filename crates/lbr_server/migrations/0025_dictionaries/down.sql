@@ -0,0 +1,3 @@
+DROP TABLE user_dictionaries;
+DROP TABLE word_definitions;
+DROP TABLE dictionaries;
\ No newline at end of file
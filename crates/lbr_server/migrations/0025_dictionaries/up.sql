@@ -0,0 +1,15 @@
+CREATE TABLE dictionaries (
+    id SERIAL PRIMARY KEY,
+    name TEXT UNIQUE NOT NULL
+);
+CREATE TABLE word_definitions (
+    dictionary_id INTEGER NOT NULL REFERENCES dictionaries,
+    word_id INTEGER NOT NULL REFERENCES words,
+    definitions TEXT [] NOT NULL,
+    PRIMARY KEY (dictionary_id, word_id)
+);
+CREATE TABLE user_dictionaries (
+    user_id INTEGER NOT NULL REFERENCES users,
+    dictionary_id INTEGER NOT NULL REFERENCES dictionaries,
+    PRIMARY KEY (user_id, dictionary_id)
+);
\ No newline at end of file
@@ -0,0 +1,11 @@
+-- enum values can't be removed so the type is recreated without it
+UPDATE decks SET card_order = 'common' WHERE card_order = 'grade';
+ALTER TYPE CARD_ORDER RENAME TO CARD_ORDER_OLD;
+CREATE TYPE CARD_ORDER AS ENUM ('frequency', 'appearance', 'random', 'common');
+ALTER TABLE decks ALTER COLUMN card_order DROP DEFAULT;
+ALTER TABLE decks ALTER COLUMN card_order TYPE CARD_ORDER USING card_order::TEXT::CARD_ORDER;
+ALTER TABLE decks ALTER COLUMN card_order SET DEFAULT 'frequency';
+DROP TYPE CARD_ORDER_OLD;
+ALTER TABLE kanji DROP COLUMN radical;
+ALTER TABLE kanji DROP COLUMN frequency;
+ALTER TABLE kanji DROP COLUMN stroke_count;
\ No newline at end of file
@@ -0,0 +1,4 @@
+ALTER TABLE kanji ADD COLUMN stroke_count INTEGER;
+ALTER TABLE kanji ADD COLUMN frequency INTEGER;
+ALTER TABLE kanji ADD COLUMN radical INTEGER;
+ALTER TYPE CARD_ORDER ADD VALUE 'grade';
\ No newline at end of file
@@ -1,15 +1,20 @@
 use diesel::prelude::*;
 use eyre::{Context, ContextCompat};
 use jadata::{
-    jmdict::{Entry, JMdict},
+    dictionaries::Dictionary,
+    frequency_lists::FrequencyList,
+    jmdict::{self, Entry, JMdict, Sense},
     kanji_extra::{ExtraKanji, KanjiExtra},
     kanji_names::KanjiNames,
     kanji_similar::KanjiSimilar,
     kanjidic::Kanjidic2,
     kradfile::Kradfile,
+    pitch_accents::PitchAccents,
     similar_kanji,
+    yomichan::Archive,
 };
-use lbr_server::domain;
+use lbr_core::{word_priority, word_sense::WordSense};
+use lbr_server::{domain, utils::database::CardKind};
 use std::{
     collections::{HashMap, HashSet},
     fs::File,
@@ -68,13 +73,63 @@ fn main() -> eyre::Result<()> {
     tracing::info!("Opening {sk}");
     let sk = similar_kanji::parse(Path::new(sk))?;
 
+    let pa = &args[8];
+    tracing::info!("Opening {pa}");
+    let pa = File::open(pa).context("Failed to open file")?;
+    tracing::info!("Deserializing");
+    let pa = PitchAccents::from(BufReader::new(pa)).context("Failed to deserialize data")?;
+
+    // the rest of the arguments are Yomichan dictionaries or frequency lists,
+    // which are either Yomichan frequency dictionaries or TSV files
+    let mut dictionaries = Vec::new();
+    let mut frequency_lists = Vec::new();
+    for extra_path in &args[9..] {
+        tracing::info!("Opening {extra_path}");
+        let path = Path::new(extra_path);
+        let file = File::open(path).context("Failed to open file")?;
+        tracing::info!("Deserializing");
+        if path.extension().is_some_and(|ext| ext == "zip") {
+            let mut archive =
+                Archive::open(BufReader::new(file)).context("Failed to deserialize data")?;
+            if archive.has_banks("term_bank") {
+                let dictionary = Dictionary::from_yomichan(&mut archive)
+                    .context("Failed to deserialize data")?;
+                dictionaries.push(dictionary);
+            }
+            if archive.has_banks("term_meta_bank") {
+                let fl = FrequencyList::from_yomichan(&mut archive)
+                    .context("Failed to deserialize data")?;
+                // the term meta banks may only contain other data such as pitch accents
+                if !fl.entries.is_empty() {
+                    frequency_lists.push(fl);
+                }
+            }
+        } else {
+            let name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let fl = FrequencyList::from_tsv(name, BufReader::new(file))
+                .context("Failed to deserialize data")?;
+            frequency_lists.push(fl);
+        }
+    }
+
     tracing::info!("Updating extra kanji");
     update_kanji_extra(&kd2, &jmdict, &mut ke, ke_path, &sk)?;
 
     conn.transaction(|conn| {
         tracing::info!("Starting transaction");
         update_kanji(conn, &kd2, &ke, &kf, &kn, &ks, &sk).context("Failed to update kanji")?;
-        // update_words(conn, &jmdict).context("Failed to update words")?;
+        update_words(conn, &jmdict).context("Failed to update words")?;
+        update_pitch_accents(conn, &pa).context("Failed to update pitch accents")?;
+        update_frequency_lists(conn, &frequency_lists)
+            .context("Failed to update frequency lists")?;
+        update_dictionaries(conn, &dictionaries).context("Failed to update dictionaries")?;
+        // the dictionary may have changed so cached segmentations are no longer reliable
+        domain::ichiran::clear_segmentation_cache(conn)
+            .context("Failed to clear segmentation cache")?;
         eyre::Ok(())
     })?;
     tracing::info!("Finished transaction");
@@ -168,6 +223,41 @@ fn update_kanji(
             .get(&kanji.literal)
             .map(|v| v.as_slice())
             .unwrap_or_default();
+        let grade = kanji
+            .misc
+            .grade
+            .as_deref()
+            .map(str::parse::<i32>)
+            .transpose()
+            .context("Failed to parse kanji grade")?;
+        let jlpt = kanji
+            .misc
+            .jlpt
+            .as_deref()
+            .map(str::parse::<i32>)
+            .transpose()
+            .context("Failed to parse kanji JLPT level")?;
+        // the first stroke count is the accepted one, the rest are common miscounts
+        let stroke_count = kanji
+            .misc
+            .stroke_count
+            .first()
+            .map(|sc| sc.parse::<i32>())
+            .transpose()
+            .context("Failed to parse kanji stroke count")?;
+        let frequency = kanji
+            .misc
+            .freq
+            .as_deref()
+            .map(str::parse::<i32>)
+            .transpose()
+            .context("Failed to parse kanji frequency")?;
+        let radical = kanji
+            .radical
+            .rad_value
+            .iter()
+            .find(|rv| rv.rad_type == "classical")
+            .map(|rv| i32::from(rv.text));
         let kanji_id = if let Some(existing_kanji) = existing_kanji {
             // update
             diesel::update(k::table.filter(k::id.eq(existing_kanji)))
@@ -175,6 +265,11 @@ fn update_kanji(
                     k::name.eq(name),
                     k::meanings.eq(meanings),
                     k::components.eq(components),
+                    k::grade.eq(grade),
+                    k::jlpt.eq(jlpt),
+                    k::stroke_count.eq(stroke_count),
+                    k::frequency.eq(frequency),
+                    k::radical.eq(radical),
                 ))
                 .execute(conn)
                 .context("Failed to update kanji")?;
@@ -187,6 +282,11 @@ fn update_kanji(
                     k::name.eq(name),
                     k::meanings.eq(meanings),
                     k::components.eq(components),
+                    k::grade.eq(grade),
+                    k::jlpt.eq(jlpt),
+                    k::stroke_count.eq(stroke_count),
+                    k::frequency.eq(frequency),
+                    k::radical.eq(radical),
                 ))
                 .returning(k::id)
                 .get_result(conn)
@@ -320,10 +420,13 @@ fn update_kanji(
 }
 
 fn update_words(conn: &mut PgConnection, jmdict: &JMdict) -> eyre::Result<()> {
-    use lbr_server::schema::{kanji as k, word_kanji as wk, words as w};
+    use lbr_server::schema::{
+        card_schedules as cs, deck_cards as dc, ignored_words as iw, kanji as k, review_logs as rl,
+        sentence_words as sw, user_words as uw, word_definitions as wd, word_frequencies as wf,
+        word_kanji as wk, word_senses as ws, words as w,
+    };
 
     let kanji_to_readings = domain::japanese::kanji_to_readings(conn)?;
-    std::fs::write("./data/kanjimap", format!("{kanji_to_readings:#?}"))?;
 
     tracing::info!("Updating words");
     let existing_words_vec = w::table
@@ -339,6 +442,10 @@ fn update_words(conn: &mut PgConnection, jmdict: &JMdict) -> eyre::Result<()> {
         .get_results::<(String, i32)>(conn)?
         .into_iter()
         .collect::<HashMap<_, _>>();
+    // the senses are inserted fresh for every word so we clear them first
+    diesel::delete(ws::table)
+        .execute(conn)
+        .context("Failed to clear word senses")?;
 
     let mut words_in_db = HashSet::new();
     for entry in &jmdict.entry {
@@ -353,20 +460,34 @@ fn update_words(conn: &mut PgConnection, jmdict: &JMdict) -> eyre::Result<()> {
         let mut jmdict_words = JmdictWords::new();
         for k_ele in &entry.k_ele {
             // only include reading elements that do not exclude the kanji element
+            // readings marked with re_nokanji are not true readings of any of the kanji elements
             let valid_reading_elements = entry
                 .r_ele
                 .iter()
+                .filter(|r_ele| r_ele.re_nokanji.is_none())
                 .filter(|r_ele| r_ele.re_restr.is_empty() || r_ele.re_restr.contains(&k_ele.keb))
                 .collect::<Vec<_>>();
             if valid_reading_elements.is_empty() {
                 // some kanji elements like search-only kanji forms do not have any valid reading element associated with them...
                 // in those cases, we will simply try out every single one and accept any that have a valid furigana mapping
-                for r_ele in &entry.r_ele {
+                for r_ele in entry
+                    .r_ele
+                    .iter()
+                    .filter(|r_ele| r_ele.re_nokanji.is_none())
+                {
                     if !furigana::map(&k_ele.keb, &r_ele.reb, &kanji_to_readings).is_empty() {
                         let word = &k_ele.keb;
                         let reading = &r_ele.reb;
                         let translations = translations_from_entry(entry, Some(word), reading);
-                        jmdict_words.insert_new(jmdict_id, word, reading, translations);
+                        let senses = senses_from_entry(entry, Some(word), reading);
+                        jmdict_words.insert_new(
+                            jmdict_id,
+                            word,
+                            reading,
+                            translations,
+                            senses,
+                            &k_ele.ke_pri,
+                        );
                     }
                 }
             }
@@ -374,7 +495,15 @@ fn update_words(conn: &mut PgConnection, jmdict: &JMdict) -> eyre::Result<()> {
                 let word = &k_ele.keb;
                 let reading = &r_ele.reb;
                 let translations = translations_from_entry(entry, Some(word), reading);
-                jmdict_words.insert_new(jmdict_id, word, reading, translations);
+                let senses = senses_from_entry(entry, Some(word), reading);
+                jmdict_words.insert_new(
+                    jmdict_id,
+                    word,
+                    reading,
+                    translations,
+                    senses,
+                    &k_ele.ke_pri,
+                );
             }
         }
 
@@ -389,7 +518,15 @@ fn update_words(conn: &mut PgConnection, jmdict: &JMdict) -> eyre::Result<()> {
             let word = &r_ele.reb;
             let reading = &r_ele.reb;
             let translations = translations_from_entry(entry, None, reading);
-            jmdict_words.insert_new(jmdict_id, word, reading, translations);
+            let senses = senses_from_entry(entry, None, reading);
+            jmdict_words.insert_new(
+                jmdict_id,
+                word,
+                reading,
+                translations,
+                senses,
+                &r_ele.re_pri,
+            );
         }
 
         for (key, val) in jmdict_words.jmdict_words {
@@ -402,24 +539,39 @@ fn update_words(conn: &mut PgConnection, jmdict: &JMdict) -> eyre::Result<()> {
                 reading,
                 hiragana_reading,
                 translations,
+                senses,
+                priority_tags,
             } = val;
-            let furigana =
-                match domain::japanese::map_to_db_furigana(&word, &reading, &kanji_to_readings) {
-                    Ok(furigana) => furigana,
-                    Err(err) => {
-                        tracing::error!("Failed to map furigana {err}");
-                        Vec::new()
-                    }
-                };
+            let frequency_score =
+                word_priority::frequency_score(priority_tags.iter().map(String::as_str));
+            let furigana = match domain::japanese::map_to_db_furigana(
+                &word,
+                &reading,
+                &[],
+                &kanji_to_readings,
+            ) {
+                Ok(furigana) => furigana,
+                Err(err) => {
+                    tracing::error!("Failed to map furigana {err}");
+                    Vec::new()
+                }
+            };
             let existing_word = existing_words
-                .get(&(jmdict_id, &word, &standardised_reading))
+                .get(&(jmdict_id, word.as_str(), standardised_reading.as_str()))
                 .copied();
-            if let Some(id) = existing_word {
+            let word_id = if let Some(id) = existing_word {
                 // update existing record
                 diesel::update(w::table.filter(w::id.eq(id)))
-                    .set((w::translations.eq(translations), w::furigana.eq(furigana)))
-                    .execute(conn)?;
-                words_in_db.insert(id);
+                    .set((
+                        w::reading.eq(&hiragana_reading),
+                        w::translations.eq(translations),
+                        w::furigana.eq(furigana),
+                        w::priority_tags.eq(&priority_tags),
+                        w::frequency_score.eq(frequency_score),
+                    ))
+                    .execute(conn)
+                    .context("Failed to update word")?;
+                id
             } else {
                 tracing::debug!("Creating {jmdict_id} {word} ({hiragana_reading})");
                 // create new
@@ -431,50 +583,332 @@ fn update_words(conn: &mut PgConnection, jmdict: &JMdict) -> eyre::Result<()> {
                         w::reading_standard.eq(&standardised_reading),
                         w::furigana.eq(&furigana),
                         w::translations.eq(&translations),
+                        w::priority_tags.eq(&priority_tags),
+                        w::frequency_score.eq(frequency_score),
                     ))
                     .returning(w::id)
                     .get_result::<i32>(conn)
                     .context("Failed to create new word")?;
-                // for new words, we also insert the word kanji
-                let mut word_kanji = Vec::new();
-                for kanji in lbr::kanji_from_word(&word) {
-                    let kanji_id = kanji_map
-                        .get(kanji)
-                        .copied()
-                        .wrap_err_with(|| format!("Failed to find kanji {kanji}"))?;
-                    word_kanji.push((wk::word_id.eq(word_id), wk::kanji_id.eq(kanji_id)));
-                }
+                word_id
+            };
+            words_in_db.insert(word_id);
+
+            let word_senses = senses
+                .into_iter()
+                .enumerate()
+                .map(|(idx, sense)| {
+                    (
+                        ws::word_id.eq(word_id),
+                        ws::idx.eq(idx as i32),
+                        ws::glosses.eq(sense.glosses),
+                        ws::parts_of_speech.eq(sense.parts_of_speech),
+                        ws::misc.eq(sense.misc),
+                        ws::fields.eq(sense.fields),
+                        ws::dialects.eq(sense.dialects),
+                        ws::info.eq(sense.info),
+                        ws::cross_references.eq(sense.cross_references),
+                        ws::antonyms.eq(sense.antonyms),
+                    )
+                })
+                .collect::<Vec<_>>();
+            if !word_senses.is_empty() {
+                diesel::insert_into(ws::table)
+                    .values(word_senses)
+                    .execute(conn)
+                    .context("Failed to create word senses")?;
+            }
+
+            // the word kanji are kept up to date for existing words as well
+            let mut word_kanji = Vec::new();
+            for kanji in lbr::kanji_from_word(&word) {
+                let kanji_id = kanji_map
+                    .get(kanji)
+                    .copied()
+                    .wrap_err_with(|| format!("Failed to find kanji {kanji}"))?;
+                word_kanji.push((wk::word_id.eq(word_id), wk::kanji_id.eq(kanji_id)));
+            }
+            if !word_kanji.is_empty() {
                 diesel::insert_into(wk::table)
                     .values(word_kanji)
                     .on_conflict_do_nothing()
                     .execute(conn)
-                    .context("Failed to create new word kanji")?;
+                    .context("Failed to create word kanji")?;
             }
         }
     }
-    let existing_word_ids = existing_words_vec
-        .iter()
-        .map(|a| (a.1, a))
-        .collect::<HashMap<_, _>>();
-    for (existing_word_id, etc) in existing_word_ids {
-        if !words_in_db.contains(&existing_word_id) {
-            tracing::error!(
-                "word in db {existing_word_id} {} {} not found while processing jmdict",
-                etc.2,
-                etc.3
+
+    // words that are no longer in JMdict are removed unless they're used in sentences, by users or in decks,
+    // so that existing references stay valid
+    let sentence_word_ids = sw::table
+        .select(sw::word_id)
+        .filter(sw::word_id.is_not_null())
+        .distinct()
+        .get_results::<Option<i32>>(conn)?;
+    let user_word_ids = uw::table
+        .select(uw::word_id)
+        .distinct()
+        .get_results::<i32>(conn)?;
+    let ignored_word_ids = iw::table
+        .select(iw::word_id)
+        .distinct()
+        .get_results::<i32>(conn)?;
+    let scheduled_word_ids = cs::table
+        .select(cs::card_id)
+        .filter(cs::kind.eq(CardKind::Word))
+        .distinct()
+        .get_results::<i32>(conn)?;
+    let reviewed_word_ids = rl::table
+        .select(rl::card_id)
+        .filter(rl::kind.eq(CardKind::Word))
+        .distinct()
+        .get_results::<i32>(conn)?;
+    let deck_word_ids = dc::table
+        .select(dc::card_id)
+        .filter(dc::kind.eq(CardKind::Word))
+        .distinct()
+        .get_results::<i32>(conn)?;
+    let example_word_ids = dc::table
+        .select(dc::example_word_id)
+        .filter(dc::example_word_id.is_not_null())
+        .distinct()
+        .get_results::<Option<i32>>(conn)?;
+    let referenced_words = sentence_word_ids
+        .into_iter()
+        .chain(example_word_ids)
+        .flatten()
+        .chain(user_word_ids)
+        .chain(ignored_word_ids)
+        .chain(scheduled_word_ids)
+        .chain(reviewed_word_ids)
+        .chain(deck_word_ids)
+        .collect::<HashSet<_>>();
+    for (_, existing_word_id, word, reading) in &existing_words_vec {
+        if words_in_db.contains(existing_word_id) {
+            continue;
+        }
+        if referenced_words.contains(existing_word_id) {
+            tracing::warn!(
+                "Keeping word {existing_word_id} {word} ({reading}) which was not found in JMdict but is in use"
             );
-            diesel::delete(wk::table)
-                .filter(wk::word_id.eq(existing_word_id))
-                .execute(conn)?;
-            diesel::delete(w::table)
-                .filter(w::id.eq(existing_word_id))
-                .execute(conn)?;
+            continue;
         }
+        tracing::info!(
+            "Deleting word {existing_word_id} {word} ({reading}) which was not found in JMdict"
+        );
+        diesel::delete(wk::table)
+            .filter(wk::word_id.eq(existing_word_id))
+            .execute(conn)?;
+        diesel::delete(wf::table)
+            .filter(wf::word_id.eq(existing_word_id))
+            .execute(conn)?;
+        diesel::delete(wd::table)
+            .filter(wd::word_id.eq(existing_word_id))
+            .execute(conn)?;
+        diesel::delete(w::table)
+            .filter(w::id.eq(existing_word_id))
+            .execute(conn)?;
     }
 
     Ok(())
 }
 
+fn update_pitch_accents(conn: &mut PgConnection, pa: &PitchAccents) -> eyre::Result<()> {
+    use lbr_server::schema::words as w;
+
+    tracing::info!("Updating pitch accents");
+    let words = w::table
+        .select((w::id, w::word, w::reading_standard))
+        .get_results::<(i32, String, String)>(conn)?;
+    // (word, standardised reading) => ids, several JMdict entries can share the same word and reading
+    let mut word_ids = HashMap::<(&str, &str), Vec<i32>>::new();
+    for (id, word, reading) in &words {
+        word_ids
+            .entry((word.as_str(), reading.as_str()))
+            .or_default()
+            .push(*id);
+    }
+
+    // the accents are set fresh each time so we clear them first
+    diesel::update(w::table)
+        .set(w::pitch_accents.eq(Vec::<i32>::new()))
+        .execute(conn)
+        .context("Failed to clear pitch accents")?;
+    for entry in &pa.entries {
+        let reading = lbr::standardise_reading(&entry.reading).standardised;
+        let Some(ids) = word_ids.get(&(entry.word.as_str(), reading.as_str())) else {
+            tracing::trace!("No word found for {} ({})", entry.word, entry.reading);
+            continue;
+        };
+        let accents = entry
+            .accents
+            .iter()
+            .map(|accent| *accent as i32)
+            .collect::<Vec<_>>();
+        diesel::update(w::table.filter(w::id.eq_any(ids)))
+            .set(w::pitch_accents.eq(accents))
+            .execute(conn)
+            .context("Failed to update pitch accents")?;
+    }
+
+    Ok(())
+}
+
+fn update_frequency_lists(conn: &mut PgConnection, lists: &[FrequencyList]) -> eyre::Result<()> {
+    use lbr_server::schema::{frequency_lists as fl, word_frequencies as wf, words as w};
+
+    let words = w::table
+        .select((w::id, w::word, w::reading_standard))
+        .get_results::<(i32, String, String)>(conn)?;
+    let word_ids = word_ids_by_word(&words);
+
+    for list in lists {
+        tracing::info!("Updating frequency list {}", list.name);
+        let existing_list_id = fl::table
+            .filter(fl::name.eq(&list.name))
+            .select(fl::id)
+            .get_result::<i32>(conn)
+            .optional()?;
+        let list_id = match existing_list_id {
+            Some(id) => id,
+            None => diesel::insert_into(fl::table)
+                .values(fl::name.eq(&list.name))
+                .returning(fl::id)
+                .get_result::<i32>(conn)
+                .context("Failed to create frequency list")?,
+        };
+
+        // the ranks are set fresh each time so we clear them first
+        diesel::delete(wf::table.filter(wf::list_id.eq(list_id)))
+            .execute(conn)
+            .context("Failed to clear word frequencies")?;
+        let mut ranks = HashMap::<i32, i32>::new();
+        for entry in &list.entries {
+            for id in matching_word_ids(&word_ids, &entry.word, entry.reading.as_deref()) {
+                // the best rank is kept for words that are listed several times
+                let rank = ranks.entry(id).or_insert(entry.rank as i32);
+                *rank = (*rank).min(entry.rank as i32);
+            }
+        }
+        tracing::info!("Matched {} words", ranks.len());
+
+        let word_frequencies = ranks
+            .into_iter()
+            .map(|(word_id, rank)| {
+                (
+                    wf::list_id.eq(list_id),
+                    wf::word_id.eq(word_id),
+                    wf::rank.eq(rank),
+                )
+            })
+            .collect::<Vec<_>>();
+        for chunk in word_frequencies.chunks(255) {
+            diesel::insert_into(wf::table)
+                .values(chunk)
+                .execute(conn)
+                .context("Failed to create word frequencies")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn update_dictionaries(conn: &mut PgConnection, dictionaries: &[Dictionary]) -> eyre::Result<()> {
+    use lbr_server::schema::{dictionaries as di, word_definitions as wd, words as w};
+
+    let words = w::table
+        .select((w::id, w::word, w::reading_standard))
+        .get_results::<(i32, String, String)>(conn)?;
+    let word_ids = word_ids_by_word(&words);
+
+    for dictionary in dictionaries {
+        tracing::info!("Updating dictionary {}", dictionary.name);
+        let existing_dictionary_id = di::table
+            .filter(di::name.eq(&dictionary.name))
+            .select(di::id)
+            .get_result::<i32>(conn)
+            .optional()?;
+        let dictionary_id = match existing_dictionary_id {
+            Some(id) => id,
+            None => diesel::insert_into(di::table)
+                .values(di::name.eq(&dictionary.name))
+                .returning(di::id)
+                .get_result::<i32>(conn)
+                .context("Failed to create dictionary")?,
+        };
+
+        // the definitions are set fresh each time so we clear them first
+        diesel::delete(wd::table.filter(wd::dictionary_id.eq(dictionary_id)))
+            .execute(conn)
+            .context("Failed to clear word definitions")?;
+        // the definitions of all of a word's entries are combined in the order they appear in
+        let mut definitions = HashMap::<i32, Vec<String>>::new();
+        for entry in &dictionary.entries {
+            for id in matching_word_ids(&word_ids, &entry.word, entry.reading.as_deref()) {
+                let word_definitions = definitions.entry(id).or_default();
+                for definition in &entry.definitions {
+                    if !word_definitions.contains(definition) {
+                        word_definitions.push(definition.clone());
+                    }
+                }
+            }
+        }
+        tracing::info!("Matched {} words", definitions.len());
+
+        let word_definitions = definitions
+            .into_iter()
+            .map(|(word_id, definitions)| {
+                (
+                    wd::dictionary_id.eq(dictionary_id),
+                    wd::word_id.eq(word_id),
+                    wd::definitions.eq(definitions),
+                )
+            })
+            .collect::<Vec<_>>();
+        for chunk in word_definitions.chunks(255) {
+            diesel::insert_into(wd::table)
+                .values(chunk)
+                .execute(conn)
+                .context("Failed to create word definitions")?;
+        }
+    }
+
+    Ok(())
+}
+
+// word => (standardised reading, id)
+fn word_ids_by_word(words: &[(i32, String, String)]) -> HashMap<&str, Vec<(&str, i32)>> {
+    let mut word_ids = HashMap::<&str, Vec<(&str, i32)>>::new();
+    for (id, word, reading) in words {
+        word_ids
+            .entry(word.as_str())
+            .or_default()
+            .push((reading.as_str(), *id));
+    }
+    word_ids
+}
+
+// entries without a reading match every reading of the word
+fn matching_word_ids(
+    word_ids: &HashMap<&str, Vec<(&str, i32)>>,
+    word: &str,
+    reading: Option<&str>,
+) -> Vec<i32> {
+    let Some(candidates) = word_ids.get(word) else {
+        tracing::trace!("No word found for {word}");
+        return Vec::new();
+    };
+    let reading = reading.map(|reading| lbr::standardise_reading(reading).standardised);
+    candidates
+        .iter()
+        .filter(|(word_reading, _)| {
+            reading
+                .as_deref()
+                .is_none_or(|reading| reading == *word_reading)
+        })
+        .map(|(_, id)| *id)
+        .collect()
+}
+
 #[derive(Hash, PartialEq, Eq)]
 struct JmdictWordKey {
     jmdict_id: i32,
@@ -482,11 +916,13 @@ struct JmdictWordKey {
     standardised_reading: String,
 }
 
-#[derive(Hash, PartialEq, Eq)]
 struct JmdictWordVal {
     reading: String,
     hiragana_reading: String,
     translations: Vec<String>,
+    senses: Vec<WordSense>,
+    // the JMdict priority tags of the word's kanji element, or of its reading element for kana words.
+    priority_tags: Vec<String>,
 }
 
 struct JmdictWords {
@@ -500,7 +936,15 @@ impl JmdictWords {
         }
     }
 
-    fn insert_new(&mut self, jmdict_id: i32, word: &str, reading: &str, translations: Vec<String>) {
+    fn insert_new(
+        &mut self,
+        jmdict_id: i32,
+        word: &str,
+        reading: &str,
+        translations: Vec<String>,
+        senses: Vec<WordSense>,
+        priority_tags: &[String],
+    ) {
         let word = word.to_owned();
         let reading = reading.to_owned();
         let sr = lbr::standardise_reading(&reading);
@@ -514,24 +958,36 @@ impl JmdictWords {
                 reading,
                 hiragana_reading: sr.hiragana,
                 translations,
+                senses,
+                priority_tags: priority_tags.to_vec(),
             },
         );
     }
 }
 
-fn translations_from_entry(entry: &Entry, word: Option<&str>, reading: &str) -> Vec<String> {
+// returns the senses that apply to the given word and reading along with their indices in the entry
+fn word_senses<'a>(
+    entry: &'a Entry,
+    word: Option<&'a str>,
+    reading: &'a str,
+) -> impl Iterator<Item = (usize, &'a Sense)> {
     entry
         .sense
         .iter()
-        .filter(|s| {
+        .enumerate()
+        .filter(move |(_, s)| {
             // with no word, we can ignore stagk
             s.stagk.is_empty()
                 || word
                     .map(|w| s.stagk.iter().any(|stagk| stagk == w))
                     .unwrap_or(true)
         })
-        .filter(|s| s.stagr.is_empty() || s.stagr.iter().any(|stagr| stagr == reading))
-        .map(|s| {
+        .filter(move |(_, s)| s.stagr.is_empty() || s.stagr.iter().any(|stagr| stagr == reading))
+}
+
+fn translations_from_entry(entry: &Entry, word: Option<&str>, reading: &str) -> Vec<String> {
+    word_senses(entry, word, reading)
+        .map(|(_, s)| {
             let sense_meanings = s
                 .gloss
                 .iter()
@@ -548,3 +1004,38 @@ fn translations_from_entry(entry: &Entry, word: Option<&str>, reading: &str) ->
         })
         .collect::<Vec<_>>()
 }
+
+fn senses_from_entry(entry: &Entry, word: Option<&str>, reading: &str) -> Vec<WordSense> {
+    // the parts of speech of a sense also apply to the following senses unless they have their own
+    let mut parts_of_speech = Vec::<&[String]>::new();
+    for sense in &entry.sense {
+        let previous = parts_of_speech.last().copied().unwrap_or_default();
+        parts_of_speech.push(if sense.pos.is_empty() {
+            previous
+        } else {
+            &sense.pos
+        });
+    }
+
+    word_senses(entry, word, reading)
+        .map(|(idx, s)| WordSense {
+            glosses: s
+                .gloss
+                .iter()
+                .filter(|g| g.lang.is_none())
+                .map(|g| g.text.clone())
+                .collect(),
+            // the parts of speech are expanded when parsing, but they are more readable abbreviated
+            parts_of_speech: parts_of_speech[idx]
+                .iter()
+                .map(|pos| jmdict::entity_name(pos).unwrap_or(pos).to_string())
+                .collect(),
+            misc: s.misc.clone(),
+            fields: s.field.clone(),
+            dialects: s.dial.clone(),
+            info: s.s_inf.clone(),
+            cross_references: s.xref.clone(),
+            antonyms: s.ant.clone(),
+        })
+        .collect()
+}
@@ -3,5 +3,8 @@
 pub mod decks;
 pub mod ichiran;
 pub mod japanese;
+pub mod reviews;
+pub mod segmentation_jobs;
 pub mod sentences;
+pub mod user_words;
 pub mod words;
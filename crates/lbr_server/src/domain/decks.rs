@@ -1,11 +1,27 @@
 //! Functions and types related to LBR decks.
 
-use crate::utils::database::{self, DeckSourceKind};
-use diesel::prelude::*;
+use super::words;
+use crate::{
+    eq, queries,
+    utils::{
+        database::{self, CardKind, CardOrder, DeckSourceKind, ExamplePreference, WordStatus},
+        diesel::PostgresChunks,
+    },
+};
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, upsert::excluded};
 use itertools::Itertools;
-use lbr::anki::{self, Deck, KanjiCard, Sentence, SentenceWord, WordCard, WordKanji};
-use rand::seq::IndexedRandom;
-use std::collections::{HashMap, HashSet};
+use lbr::anki::{
+    self, Deck, ExtraSentence, KanjiCard, Sentence, SentenceWord, WordCard, WordKanji,
+};
+use lbr_api::{request as req, response as res};
+use lbr_core::{word_definitions::WordDefinitions, word_sense::WordSense};
+use rand::seq::{IndexedRandom, SliceRandom};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    time::{Duration, SystemTime},
+};
 
 /// Generates an Anki deck for the given deck id.
 pub fn gen_deck(
@@ -16,33 +32,112 @@ pub fn gen_deck(
     user_id: i32,
 ) -> eyre::Result<Deck> {
     tracing::info!("Creating cards");
-    let word_cards = get_word_cards(conn, user_id, deck_id)?;
-    let kanji_cards = get_kanji_cards(conn, deck_id)?;
+    let (word_cards, new_word_examples) =
+        get_word_cards(conn, user_id, deck_id, CardSelection::Rules)?;
+    let (kanji_cards, new_kanji_examples) = get_kanji_cards(conn, deck_id, CardSelection::Rules)?;
+    new_word_examples.store(conn, deck_id)?;
+    new_kanji_examples.store(conn, deck_id)?;
     tracing::debug!("Created {} cards", word_cards.len() + kanji_cards.len());
 
     tracing::info!("Creating deck");
-    let package = lbr::anki::create_deck(name, anki_deck_id, word_cards, kanji_cards);
+    let note_types = get_note_types(conn, deck_id)?;
+    let card_types = get_word_card_types(conn, deck_id)?;
+    let package = lbr::anki::create_deck(
+        name,
+        anki_deck_id,
+        word_cards,
+        kanji_cards,
+        &note_types,
+        card_types,
+    );
     tracing::info!("Created deck");
 
     Ok(package)
 }
 
-fn get_word_cards(
+/// Returns the cards the deck would be generated with, rendered the same way as in Anki.
+/// Examples chosen for the preview are not stored, so cards without one may show a different example next time.
+pub fn preview_deck(
     conn: &mut PgConnection,
     user_id: i32,
     deck_id: i32,
-) -> eyre::Result<Vec<WordCard>> {
+) -> eyre::Result<res::DeckPreview> {
+    let note_types = get_note_types(conn, deck_id)?;
+    let word_note_type = note_types.word();
+    let kanji_note_type = note_types.kanji();
+    let word_cards = get_word_cards(conn, user_id, deck_id, CardSelection::Rules)?
+        .0
+        .into_iter()
+        .map(|card| {
+            let card_id = card.id;
+            let text = card.word.clone();
+            let count = card.word_sentences as i32;
+            let example = card.sentence.sentence.clone();
+            let rendered = card.into_fields().render(&word_note_type);
+            preview_card(res::CardKind::Word, card_id, text, count, example, rendered)
+        })
+        .collect();
+    let kanji_cards = get_kanji_cards(conn, deck_id, CardSelection::Rules)?
+        .0
+        .into_iter()
+        .map(|card| {
+            let card_id = card.id;
+            let text = card.kanji.clone();
+            let count = card.kanji_words as i32;
+            let example = card.example_source_word.word.clone();
+            let rendered = card.into_fields().render(&kanji_note_type);
+            preview_card(res::CardKind::Kanji, card_id, text, count, example, rendered)
+        })
+        .collect();
+    Ok(res::DeckPreview {
+        word_cards,
+        kanji_cards,
+    })
+}
+
+/// Which of the words or kanji in a deck's sources get cards.
+#[derive(Debug, Clone, Copy)]
+pub enum CardSelection<'a> {
+    /// The ones that pass the deck's rules, in the order set by the rules.
+    Rules,
+    /// The given ones regardless of the deck's rules, in no particular order.
+    Only(&'a HashSet<i32>),
+}
+
+/// Creates a card for each word in the deck's word sources that is selected.
+/// Cards use their stored example sentence if it still exists,
+/// the examples chosen for the other cards are returned so that they can be stored.
+pub fn get_word_cards(
+    conn: &mut PgConnection,
+    user_id: i32,
+    deck_id: i32,
+    selection: CardSelection,
+) -> eyre::Result<(Vec<WordCard>, NewExamples)> {
     use crate::schema::{
-        deck_sources as ds, ignored_words as iw, kanji as k, sentence_words as sw, sentences as s,
-        word_kanji as wk, words as w,
+        deck_sources as ds, decks as d, kanji as k, sentence_words as sw, sentences as s,
+        word_frequencies as wf, word_kanji as wk, words as w,
     };
 
-    let ignored_words = iw::table
-        .filter(iw::user_id.eq(user_id))
-        .select(iw::word_id)
-        .get_results::<i32>(conn)?
-        .into_iter()
+    let exclude_known = d::table
+        .filter(d::id.eq(deck_id))
+        .select(d::exclude_known)
+        .get_result::<bool>(conn)?;
+    let rules = get_rules(conn, deck_id)?;
+    let word_statuses = queries::word_statuses(conn, user_id)?;
+    // words the user doesn't want cards for
+    let excluded_words = word_statuses
+        .iter()
+        .filter(|(_, status)| match status {
+            WordStatus::Ignored => true,
+            WordStatus::Known => exclude_known,
+            WordStatus::Unknown | WordStatus::Learning => false,
+        })
+        .map(|(word_id, _)| *word_id)
         .collect::<HashSet<_>>();
+    let included = |word_id: &i32| match selection {
+        CardSelection::Rules => !excluded_words.contains(word_id),
+        CardSelection::Only(word_ids) => word_ids.contains(word_id),
+    };
 
     // get all sentence words for the deck
     let sentence_words: Vec<SentenceWordQuery> = ds::table
@@ -60,11 +155,21 @@ fn get_word_cards(
         .left_join(w::table.on(w::id.nullable().eq(sw::word_id)))
         .select(SentenceWordQuery::as_select())
         .load(conn)?;
+    // the words' ranks in the deck's frequency list
+    let frequency_ranks = match rules.frequency_list_id {
+        Some(list_id) => wf::table
+            .filter(wf::list_id.eq(list_id))
+            .select((wf::word_id, wf::rank))
+            .get_results::<(i32, i32)>(conn)?
+            .into_iter()
+            .collect::<HashMap<_, _>>(),
+        None => HashMap::new(),
+    };
 
     let sentence_word_word_ids = sentence_words
         .iter()
         .filter_map(|sw| sw.word_id)
-        .filter(|wi| !ignored_words.contains(wi))
+        .filter(included)
         .collect::<Vec<_>>();
     // get all kanji related to the sentences
     let kanji: Vec<KanjiQuery> = w::table
@@ -82,34 +187,137 @@ fn get_word_cards(
     let sentence_words_by_word_id = sentence_words
         .iter()
         .filter_map(|sw| sw.word_id.map(|wi| (wi, sw)))
-        .into_group_map();
+        .filter(|(wi, _)| included(wi))
+        .into_group_map()
+        .into_iter();
+    let sentence_words_by_word_id = match selection {
+        CardSelection::Rules => {
+            let sentence_words_by_word_id = sentence_words_by_word_id
+                .map(|(wi, word_sentences)| (wi, apply_word_thresholds(word_sentences)))
+                .filter(|(wi, word_sentences)| {
+                    let count = word_sentences.len() as i32;
+                    !word_sentences.is_empty()
+                        && rules.min_word_frequency.is_none_or(|min| count >= min)
+                        && rules.max_word_frequency.is_none_or(|max| count <= max)
+                        && rules
+                            .min_word_frequency_score
+                            .is_none_or(|min| word_frequency_score(word_sentences) >= min)
+                        && rules.max_word_frequency_rank.is_none_or(|max| {
+                            frequency_ranks.get(wi).is_some_and(|rank| *rank <= max)
+                        })
+                })
+                .collect::<Vec<_>>();
+            order_cards(
+                sentence_words_by_word_id,
+                rules.order,
+                rules.max_word_cards,
+                |(wi, word_sentences)| CardStats {
+                    id: *wi,
+                    count: word_sentences.len(),
+                    first_sentence_id: word_sentences.iter().map(|sw| sw.sentence_id).min(),
+                    frequency_score: word_frequency_score(word_sentences),
+                    frequency_rank: frequency_ranks.get(wi).copied(),
+                    grade: None,
+                },
+            )
+        }
+        CardSelection::Only(_) => sentence_words_by_word_id.collect(),
+    };
     let sentence_words_by_sentence = sentence_words.iter().into_group_map_by(|r| r.sentence_id);
+    let chosen_examples = get_chosen_examples(conn, deck_id, CardKind::Word)?;
+    let card_word_ids = sentence_words_by_word_id
+        .iter()
+        .map(|(wi, _)| *wi)
+        .collect::<Vec<_>>();
+    let mut word_senses = words::get_word_senses(conn, &card_word_ids)?;
+    let mut word_definitions = words::get_word_definitions(conn, user_id, &card_word_ids)?;
 
+    let now = Utc::now();
     let mut cards = Vec::new();
-    for (_, word_sentences) in sentence_words_by_word_id {
-        // for each word, choose random sentence
-        let sentence = word_sentences.choose(&mut rand::rng()).cloned().unwrap();
+    let mut new_examples = Vec::new();
+    for (word_id, word_sentences) in sentence_words_by_word_id {
+        // keep the previously chosen sentence if it still exists so that the card doesn't change
+        let previous = chosen_examples.get(&word_id).and_then(|example| {
+            let sentence = word_sentences
+                .iter()
+                .find(|sw| Some(sw.sentence_id) == example.sentence_id)?;
+            Some((*sentence, example.chosen_at))
+        });
+        let (sentence, chosen_at) = match previous {
+            Some(previous) => previous,
+            None => {
+                // for each new word, choose random sentence
+                let sentence = *word_sentences.choose(&mut rand::rng()).unwrap();
+                new_examples.push(Example {
+                    card_id: word_id,
+                    sentence_id: Some(sentence.sentence_id),
+                    example_word_id: None,
+                });
+                (sentence, now)
+            }
+        };
         let sentence_words = sentence_words_by_sentence
             .get(&sentence.sentence_id)
             .unwrap();
 
+        // the words in the sentence that the user doesn't know yet, other than the card's word
+        let unknown_words = |sentence_id: i32| {
+            sentence_words_by_sentence[&sentence_id]
+                .iter()
+                .filter_map(|sw| sw.word_id)
+                .filter(|wi| *wi != word_id && word_statuses.get(wi) != Some(&WordStatus::Known))
+                .unique()
+                .count()
+        };
+        let extra_sentences = choose_extra_examples(
+            &word_sentences,
+            sentence,
+            rules.extra_examples,
+            rules.example_preference,
+            unknown_words,
+        )
+        .into_iter()
+        .map(|extra| ExtraSentence {
+            word_range: extra.idx_start as usize..extra.idx_end as usize,
+            sentence: sentence_from_query(extra, &sentence_words_by_sentence[&extra.sentence_id]),
+        })
+        .collect();
+
         let card = word_card_from_query(
             sentence,
             sentence_words,
             &kanji_names_by_kanji,
             word_sentences.len(),
+            word_senses.remove(&word_id).unwrap_or_default(),
+            word_definitions.remove(&word_id).unwrap_or_default(),
+            extra_sentences,
+            chosen_at,
         );
         cards.push(card);
     }
-    Ok(cards)
+    let new_examples = NewExamples {
+        kind: CardKind::Word,
+        examples: new_examples,
+        chosen_at: now,
+    };
+    Ok((cards, new_examples))
 }
 
-fn get_kanji_cards(conn: &mut PgConnection, deck_id: i32) -> eyre::Result<Vec<KanjiCard>> {
+/// Creates a card for each kanji in the deck's kanji sources that is selected.
+/// Cards use their stored example word if it's still in the sources,
+/// the examples chosen for the other cards are returned so that they can be stored.
+pub fn get_kanji_cards(
+    conn: &mut PgConnection,
+    deck_id: i32,
+    selection: CardSelection,
+) -> eyre::Result<(Vec<KanjiCard>, NewExamples)> {
     use crate::schema::{
         deck_sources as ds, kanji as k, kanji_similar as ks, sentence_words as sw, sentences as s,
         word_kanji as wk, words as w,
     };
 
+    let rules = get_rules(conn, deck_id)?;
+
     // get all words from kanji sources for the deck
     let kind = DeckSourceKind::Kanji;
     let kanji_words: Vec<KanjiWordQuery> = ds::table
@@ -134,10 +342,46 @@ fn get_kanji_cards(conn: &mut PgConnection, deck_id: i32) -> eyre::Result<Vec<Ka
         panic!("uh oh");
     }
 
-    let source_words_by_kanji_id: HashMap<i32, Vec<KanjiWordQuery>> = kanji_words
-        .iter()
-        .cloned()
-        .into_group_map_by(|swq| swq.kanji_id);
+    let source_words_by_kanji_id = match selection {
+        CardSelection::Rules => {
+            let source_words_by_kanji_id = kanji_words
+                .iter()
+                .filter(|kwq| {
+                    rules
+                        .max_kanji_grade
+                        .is_none_or(|max| kwq.kanji_grade.is_some_and(|grade| grade <= max))
+                        && rules
+                            .min_kanji_jlpt
+                            .is_none_or(|min| kwq.kanji_jlpt.is_some_and(|jlpt| jlpt >= min))
+                })
+                .cloned()
+                .into_group_map_by(|kwq| kwq.kanji_id)
+                .into_iter()
+                .map(|(kanji_id, words)| (kanji_id, apply_kanji_thresholds(words)))
+                .filter(|(_, words)| !words.is_empty())
+                .collect::<Vec<_>>();
+            order_cards(
+                source_words_by_kanji_id,
+                rules.order,
+                rules.max_kanji_cards,
+                |(kanji_id, words)| CardStats {
+                    id: *kanji_id,
+                    count: words.len(),
+                    first_sentence_id: words.iter().map(|w| w.sentence_id).min(),
+                    frequency_score: 0,
+                    frequency_rank: words[0].kanji_frequency,
+                    grade: words[0].kanji_grade,
+                },
+            )
+        }
+        CardSelection::Only(selected_kanji_ids) => kanji_words
+            .iter()
+            .filter(|kwq| selected_kanji_ids.contains(&kwq.kanji_id))
+            .cloned()
+            .into_group_map_by(|kwq| kwq.kanji_id)
+            .into_iter()
+            .collect(),
+    };
     let similar_kanji_lower: Vec<SimilarKanjiQuery> = ks::table
         .inner_join(k::table.on(k::id.eq(ks::lower_kanji_id)))
         .select(SimilarKanjiQuery::as_select())
@@ -153,10 +397,32 @@ fn get_kanji_cards(conn: &mut PgConnection, deck_id: i32) -> eyre::Result<Vec<Ka
         .into_iter()
         .into_group_map_by(|skq| skq.lower_kanji_id);
 
+    let chosen_examples = get_chosen_examples(conn, deck_id, CardKind::Kanji)?;
+
+    let now = Utc::now();
     let mut cards = Vec::new();
+    let mut new_examples = Vec::new();
     for (kanji_id, words) in source_words_by_kanji_id {
-        // for each kanji, choose random example word
-        let word = words.choose(&mut rand::rng()).cloned().unwrap();
+        // keep the previously chosen word if it's still in the sources so that the card doesn't change
+        let previous = chosen_examples.get(&kanji_id).and_then(|example| {
+            let word = words
+                .iter()
+                .find(|w| Some(w.word_id) == example.example_word_id)?;
+            Some((word.clone(), example.chosen_at))
+        });
+        let (word, chosen_at) = match previous {
+            Some(previous) => previous,
+            None => {
+                // for each new kanji, choose random example word
+                let word = words.choose(&mut rand::rng()).cloned().unwrap();
+                new_examples.push(Example {
+                    card_id: kanji_id,
+                    sentence_id: None,
+                    example_word_id: Some(word.word_id),
+                });
+                (word, now)
+            }
+        };
         let mut higher_similar_kanji = lower_kanji_id_to_similar_kanji
             .remove(&kanji_id)
             .unwrap_or_default();
@@ -166,10 +432,466 @@ fn get_kanji_cards(conn: &mut PgConnection, deck_id: i32) -> eyre::Result<Vec<Ka
         higher_similar_kanji.retain(|sk| kanji_ids.contains(&sk.higher_kanji_id));
         lower_similar_kanji.retain(|sk| kanji_ids.contains(&sk.lower_kanji_id));
         higher_similar_kanji.extend(lower_similar_kanji.into_iter());
-        let card = kanji_card_from_query(word, higher_similar_kanji, words.len());
+        let card = kanji_card_from_query(word, higher_similar_kanji, words.len(), chosen_at);
         cards.push(card);
     }
-    Ok(cards)
+    let new_examples = NewExamples {
+        kind: CardKind::Kanji,
+        examples: new_examples,
+        chosen_at: now,
+    };
+    Ok((cards, new_examples))
+}
+
+/// Chooses a different example sentence or word for the card the next time the deck is generated, previewed or reviewed.
+/// The card keeps its example if there are no other examples in the deck's sources.
+pub fn reroll_example(
+    conn: &mut PgConnection,
+    user_id: i32,
+    deck_id: i32,
+    kind: CardKind,
+    card_id: i32,
+) -> eyre::Result<()> {
+    use crate::schema::{
+        deck_sources as ds, decks as d, sentence_words as sw, sentences as s, word_kanji as wk,
+    };
+
+    let decks = d::table
+        .filter(d::id.eq(deck_id).and(d::user_id.eq(user_id)))
+        .select(d::id)
+        .execute(conn)?;
+    if decks != 1 {
+        return Err(eyre::eyre!("No such deck"));
+    }
+
+    let current = get_chosen_examples(conn, deck_id, kind)?.remove(&card_id);
+    let example = match kind {
+        CardKind::Word => {
+            let sentence_ids = ds::table
+                .filter(
+                    ds::deck_id
+                        .eq(deck_id)
+                        .and(ds::kind.eq(DeckSourceKind::Word)),
+                )
+                .inner_join(s::table.on(s::source_id.eq(ds::source_id)))
+                .inner_join(sw::table.on(sw::sentence_id.eq(s::id)))
+                .filter(sw::word_id.eq(card_id))
+                .select(s::id)
+                .distinct()
+                .get_results::<i32>(conn)?;
+            let current_sentence_id = current.and_then(|c| c.sentence_id);
+            let sentence_id = sentence_ids
+                .into_iter()
+                .filter(|id| Some(*id) != current_sentence_id)
+                .collect::<Vec<_>>()
+                .choose(&mut rand::rng())
+                .copied();
+            sentence_id.map(|sentence_id| Example {
+                card_id,
+                sentence_id: Some(sentence_id),
+                example_word_id: None,
+            })
+        }
+        CardKind::Kanji => {
+            let word_ids = ds::table
+                .filter(
+                    ds::deck_id
+                        .eq(deck_id)
+                        .and(ds::kind.eq(DeckSourceKind::Kanji)),
+                )
+                .inner_join(s::table.on(s::source_id.eq(ds::source_id)))
+                .inner_join(sw::table.on(sw::sentence_id.eq(s::id)))
+                .inner_join(wk::table.on(wk::word_id.nullable().eq(sw::word_id)))
+                .filter(wk::kanji_id.eq(card_id))
+                .select(wk::word_id)
+                .distinct()
+                .get_results::<i32>(conn)?;
+            let current_word_id = current.and_then(|c| c.example_word_id);
+            let word_id = word_ids
+                .into_iter()
+                .filter(|id| Some(*id) != current_word_id)
+                .collect::<Vec<_>>()
+                .choose(&mut rand::rng())
+                .copied();
+            word_id.map(|word_id| Example {
+                card_id,
+                sentence_id: None,
+                example_word_id: Some(word_id),
+            })
+        }
+    };
+
+    match example {
+        Some(example) => store_examples(conn, deck_id, kind, &[example], Utc::now())?,
+        None => tracing::info!("No other examples for card {card_id}"),
+    }
+    Ok(())
+}
+
+/// The note types the deck's cards are generated with.
+pub fn get_note_types(conn: &mut PgConnection, deck_id: i32) -> eyre::Result<anki::DeckNoteTypes> {
+    use crate::schema::deck_note_types as dnt;
+
+    let custom_note_types = dnt::table
+        .filter(dnt::deck_id.eq(deck_id))
+        .select((dnt::kind, dnt::anki_model_id, dnt::css, dnt::templates))
+        .get_results::<(CardKind, i64, String, serde_json::Value)>(conn)?;
+    let mut note_types = anki::DeckNoteTypes::default();
+    for (kind, model_id, css, templates) in custom_note_types {
+        let note_type = anki::CustomNoteType {
+            model_id,
+            note_type: anki::NoteType {
+                css,
+                templates: serde_json::from_value(templates)?,
+            },
+        };
+        match kind {
+            CardKind::Word => note_types.word = Some(note_type),
+            CardKind::Kanji => note_types.kanji = Some(note_type),
+        }
+    }
+    Ok(note_types)
+}
+
+/// Sets the note type of the deck's word or kanji cards, the default note type is used if not given.
+pub fn set_note_type(
+    conn: &mut PgConnection,
+    deck_id: i32,
+    kind: CardKind,
+    note_type: Option<anki::NoteType>,
+) -> eyre::Result<()> {
+    use crate::schema::deck_note_types as dnt;
+
+    let Some(note_type) = note_type else {
+        diesel::delete(dnt::table.filter(dnt::deck_id.eq(deck_id).and(dnt::kind.eq(kind))))
+            .execute(conn)?;
+        return Ok(());
+    };
+    let fields: &[&str] = match kind {
+        CardKind::Word => &anki::WordFields::NAMES,
+        CardKind::Kanji => &anki::KanjiFields::NAMES,
+    };
+    note_type.validate(fields)?;
+
+    // changing the number of templates changes the note type's schema in Anki,
+    // so the note type gets a new id to avoid conflicting with the one already imported
+    let current = get_note_types(conn, deck_id)?;
+    let current = match kind {
+        CardKind::Word => current.word,
+        CardKind::Kanji => current.kanji,
+    };
+    let anki_model_id = match current {
+        Some(current) if current.note_type.templates.len() == note_type.templates.len() => {
+            current.model_id
+        }
+        _ => rand::random::<i64>(),
+    };
+    let css = note_type.css;
+    let templates = serde_json::to_value(note_type.templates)?;
+    diesel::insert_into(dnt::table)
+        .values(eq!(dnt, deck_id, kind, anki_model_id, css, templates))
+        .on_conflict((dnt::deck_id, dnt::kind))
+        .do_update()
+        .set((
+            dnt::anki_model_id.eq(excluded(dnt::anki_model_id)),
+            dnt::css.eq(excluded(dnt::css)),
+            dnt::templates.eq(excluded(dnt::templates)),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// The rules for choosing a deck's cards.
+pub fn get_rules(conn: &mut PgConnection, deck_id: i32) -> eyre::Result<DeckRules> {
+    use crate::schema::decks as d;
+
+    let rules = d::table
+        .filter(d::id.eq(deck_id))
+        .select(DeckRules::as_select())
+        .get_result(conn)?;
+    Ok(rules)
+}
+
+/// The kinds of word cards created for the deck in addition to the sentence cards.
+pub fn get_word_card_types(
+    conn: &mut PgConnection,
+    deck_id: i32,
+) -> eyre::Result<anki::WordCardTypes> {
+    use crate::schema::decks as d;
+
+    let (reading, production, cloze) = d::table
+        .filter(d::id.eq(deck_id))
+        .select((d::reading_cards, d::production_cards, d::cloze_cards))
+        .get_result::<(bool, bool, bool)>(conn)?;
+    Ok(anki::WordCardTypes {
+        reading,
+        production,
+        cloze,
+    })
+}
+
+// all of the word's sentences have the same word so they also have the same score
+fn word_frequency_score(word_sentences: &[&SentenceWordQuery]) -> i32 {
+    word_sentences
+        .first()
+        .and_then(|sw| sw.frequency_score)
+        .unwrap_or_default()
+}
+
+// keeps the word's sentences from the sources where it appears in at least as many sentences as the source's threshold
+fn apply_word_thresholds(word_sentences: Vec<&SentenceWordQuery>) -> Vec<&SentenceWordQuery> {
+    let mut sentences_by_source = HashMap::<i32, HashSet<i32>>::new();
+    for sw in &word_sentences {
+        sentences_by_source
+            .entry(sw.source_id)
+            .or_default()
+            .insert(sw.sentence_id);
+    }
+    word_sentences
+        .into_iter()
+        .filter(|sw| sentences_by_source[&sw.source_id].len() as i32 >= sw.threshold)
+        .collect()
+}
+
+// keeps the kanji's words from the sources where it appears in at least as many words as the source's threshold
+fn apply_kanji_thresholds(words: Vec<KanjiWordQuery>) -> Vec<KanjiWordQuery> {
+    let mut words_by_source = HashMap::<i32, HashSet<i32>>::new();
+    for w in &words {
+        words_by_source
+            .entry(w.source_id)
+            .or_default()
+            .insert(w.word_id);
+    }
+    words
+        .into_iter()
+        .filter(|w| words_by_source[&w.source_id].len() as i32 >= w.threshold)
+        .collect()
+}
+
+// chooses up to `count` of the word's other sentences as extra examples, in order of preference
+fn choose_extra_examples<'a>(
+    word_sentences: &[&'a SentenceWordQuery],
+    example: &SentenceWordQuery,
+    count: i32,
+    preference: ExamplePreference,
+    unknown_words: impl Fn(i32) -> usize,
+) -> Vec<&'a SentenceWordQuery> {
+    if count <= 0 {
+        return Vec::new();
+    }
+
+    // the word can appear several times in a sentence, but each sentence is only used once
+    let mut candidates = word_sentences
+        .iter()
+        .copied()
+        .filter(|sw| sw.sentence_id != example.sentence_id)
+        .unique_by(|sw| sw.sentence_id)
+        .collect::<Vec<_>>();
+    match preference {
+        ExamplePreference::Shortest => {
+            candidates.sort_by_cached_key(|sw| (sw.sentence.chars().count(), sw.sentence_id))
+        }
+        ExamplePreference::KnownWords => candidates.sort_by_cached_key(|sw| {
+            (
+                unknown_words(sw.sentence_id),
+                sw.sentence.chars().count(),
+                sw.sentence_id,
+            )
+        }),
+        ExamplePreference::SourceDiversity => {
+            // takes the shortest remaining sentence from each source in turn,
+            // starting with the sources other than the main example's
+            candidates.sort_by_cached_key(|sw| (sw.sentence.chars().count(), sw.sentence_id));
+            let mut taken_from_source = HashMap::<i32, usize>::new();
+            let mut ranked = candidates
+                .into_iter()
+                .map(|sw| {
+                    let taken = taken_from_source.entry(sw.source_id).or_default();
+                    *taken += 1;
+                    (*taken, sw)
+                })
+                .collect::<Vec<_>>();
+            // the sort is stable so shorter sentences stay first
+            ranked.sort_by_key(|(taken, sw)| (*taken, sw.source_id == example.source_id));
+            candidates = ranked.into_iter().map(|(_, sw)| sw).collect();
+        }
+    }
+    candidates.truncate(count as usize);
+    candidates
+}
+
+// what the cards are ordered by
+struct CardStats {
+    id: i32,
+    // how many times the card's word or kanji appears in the deck's sources
+    count: usize,
+    // the earliest sentence the card's word or kanji appears in
+    first_sentence_id: Option<i32>,
+    // how common the card's word is in Japanese, 0 for kanji
+    frequency_score: i32,
+    // the card's word's rank in the deck's frequency list, if it has one and the word is in it,
+    // or the kanji's newspaper frequency rank
+    frequency_rank: Option<i32>,
+    // the kanji's school grade, None for words
+    grade: Option<i32>,
+}
+
+// orders the cards and drops the ones over the limit
+fn order_cards<T>(
+    mut cards: Vec<T>,
+    order: CardOrder,
+    max_cards: Option<i32>,
+    stats: impl Fn(&T) -> CardStats,
+) -> Vec<T> {
+    match order {
+        CardOrder::Frequency => cards.sort_by_cached_key(|c| {
+            let stats = stats(c);
+            (Reverse(stats.count), stats.first_sentence_id, stats.id)
+        }),
+        CardOrder::Appearance => cards.sort_by_cached_key(|c| {
+            let stats = stats(c);
+            (stats.first_sentence_id, Reverse(stats.count), stats.id)
+        }),
+        CardOrder::Random => cards.shuffle(&mut rand::rng()),
+        CardOrder::Common => cards.sort_by_cached_key(|c| {
+            let stats = stats(c);
+            // words that are not in the frequency list come last
+            (
+                stats.frequency_rank.is_none(),
+                stats.frequency_rank,
+                Reverse(stats.frequency_score),
+                Reverse(stats.count),
+                stats.first_sentence_id,
+                stats.id,
+            )
+        }),
+        CardOrder::Grade => cards.sort_by_cached_key(|c| {
+            let stats = stats(c);
+            // kanji without a grade are not taught in school and come last
+            (
+                stats.grade.is_none(),
+                stats.grade,
+                stats.frequency_rank.is_none(),
+                stats.frequency_rank,
+                Reverse(stats.frequency_score),
+                Reverse(stats.count),
+                stats.first_sentence_id,
+                stats.id,
+            )
+        }),
+    }
+    if let Some(max_cards) = max_cards {
+        cards.truncate(max_cards.max(0) as usize);
+    }
+    cards
+}
+
+// the example chosen for a card
+struct Example {
+    card_id: i32,
+    sentence_id: Option<i32>,
+    example_word_id: Option<i32>,
+}
+
+/// Examples chosen for cards that didn't have a stored one.
+/// They're only stored when the deck is generated so that previewing or reviewing the deck doesn't change it.
+pub struct NewExamples {
+    kind: CardKind,
+    examples: Vec<Example>,
+    chosen_at: DateTime<Utc>,
+}
+
+impl NewExamples {
+    /// Stores the examples so that the cards keep them.
+    pub fn store(&self, conn: &mut PgConnection, deck_id: i32) -> eyre::Result<()> {
+        store_examples(conn, deck_id, self.kind, &self.examples, self.chosen_at)
+    }
+}
+
+fn get_chosen_examples(
+    conn: &mut PgConnection,
+    deck_id: i32,
+    kind: CardKind,
+) -> eyre::Result<HashMap<i32, DeckCardQuery>> {
+    use crate::schema::deck_cards as dc;
+
+    let chosen_examples = dc::table
+        .filter(dc::deck_id.eq(deck_id).and(dc::kind.eq(kind)))
+        .select(DeckCardQuery::as_select())
+        .get_results(conn)?
+        .into_iter()
+        .map(|dc| (dc.card_id, dc))
+        .collect();
+    Ok(chosen_examples)
+}
+
+// stores the examples, replacing the ones chosen earlier for the same cards
+fn store_examples(
+    conn: &mut PgConnection,
+    deck_id: i32,
+    kind: CardKind,
+    examples: &[Example],
+    chosen_at: DateTime<Utc>,
+) -> eyre::Result<()> {
+    use crate::schema::deck_cards as dc;
+
+    let values = examples
+        .iter()
+        .map(|example| {
+            let Example {
+                card_id,
+                sentence_id,
+                example_word_id,
+            } = *example;
+            eq!(
+                dc,
+                deck_id,
+                kind,
+                card_id,
+                sentence_id,
+                example_word_id,
+                chosen_at
+            )
+        })
+        .collect::<Vec<_>>();
+    for chunk in values.pg_chunks() {
+        diesel::insert_into(dc::table)
+            .values(chunk)
+            .on_conflict((dc::deck_id, dc::kind, dc::card_id))
+            .do_update()
+            .set((
+                dc::sentence_id.eq(excluded(dc::sentence_id)),
+                dc::example_word_id.eq(excluded(dc::example_word_id)),
+                dc::chosen_at.eq(excluded(dc::chosen_at)),
+            ))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+fn preview_card(
+    kind: res::CardKind,
+    card_id: i32,
+    text: String,
+    count: i32,
+    example: String,
+    rendered: anki::RenderedCard,
+) -> res::PreviewCard {
+    let anki::RenderedCard {
+        question,
+        answer,
+        css,
+    } = rendered;
+    res::PreviewCard {
+        kind,
+        card_id,
+        text,
+        count,
+        example,
+        question,
+        answer,
+        css,
+    }
 }
 
 fn word_card_from_query(
@@ -177,22 +899,34 @@ fn word_card_from_query(
     sentence_words: &[&SentenceWordQuery],
     kanji_names_by_kanji: &HashMap<String, Option<String>>,
     word_sentences: usize,
+    senses: Vec<WordSense>,
+    definitions: Vec<WordDefinitions>,
+    extra_sentences: Vec<ExtraSentence>,
+    chosen_at: DateTime<Utc>,
 ) -> WordCard {
+    let sentence = sentence_from_query(word, sentence_words);
     let SentenceWordQuery {
         word_id,
         word,
         reading,
-        sentence,
+        sentence: _,
         sentence_word_reading: _,
         sentence_word_furigana: _,
         idx_start,
         idx_end,
         furigana,
         translations,
-        sentence_id,
+        pitch_accents,
+        frequency_score: _,
+        sentence_id: _,
+        sentence_start_ms: _,
+        sentence_end_ms: _,
+        source_id: _,
+        threshold: _,
     } = word.clone();
 
-    let word_in_sentence = &sentence[idx_start as usize..idx_end as usize];
+    let reading = reading.unwrap();
+    let word_in_sentence = &sentence.sentence[idx_start as usize..idx_end as usize];
     let kanji = lbr::kanji_from_word(word_in_sentence)
         .map(|k| WordKanji {
             name: kanji_names_by_kanji
@@ -208,28 +942,49 @@ fn word_card_from_query(
         word_id: word_id.unwrap(),
         word: word.unwrap(),
         word_range: idx_start as usize..idx_end as usize,
-        word_furigana: db_furigana_to_anki_furigana(
-            furigana.unwrap().as_slice(),
-            Some(&reading.unwrap()),
-        ),
+        word_furigana: db_furigana_to_anki_furigana(furigana.unwrap().as_slice(), Some(&reading)),
+        reading,
+        pitch_accents: pitch_accents
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(|accent| accent as u32)
+            .collect(),
         translations: translations.unwrap().into_iter().flatten().collect(),
+        senses,
+        definitions,
         kanji,
         word_sentences,
-        sentence: Sentence {
-            id: sentence_id,
-            sentence,
-            words: sentence_words
-                .iter()
-                .map(|r| SentenceWord {
-                    furigana: db_furigana_to_anki_furigana(
-                        r.sentence_word_furigana.as_slice(),
-                        r.sentence_word_reading.as_deref(),
-                    ),
-                    idx_start: r.idx_start,
-                    idx_end: r.idx_end,
-                })
-                .collect(),
-        },
+        extra_sentences,
+        generated_at: SystemTime::from(chosen_at),
+        sentence,
+    }
+}
+
+fn sentence_from_query(
+    sentence: &SentenceWordQuery,
+    sentence_words: &[&SentenceWordQuery],
+) -> Sentence {
+    Sentence {
+        id: sentence.sentence_id,
+        sentence: sentence.sentence.clone(),
+        words: sentence_words
+            .iter()
+            .map(|r| SentenceWord {
+                furigana: db_furigana_to_anki_furigana(
+                    r.sentence_word_furigana.as_slice(),
+                    r.sentence_word_reading.as_deref(),
+                ),
+                idx_start: r.idx_start,
+                idx_end: r.idx_end,
+            })
+            .collect(),
+        timestamps: sentence
+            .sentence_start_ms
+            .zip(sentence.sentence_end_ms)
+            .map(|(start, end)| {
+                Duration::from_millis(start as u64)..Duration::from_millis(end as u64)
+            }),
     }
 }
 
@@ -254,6 +1009,7 @@ fn kanji_card_from_query(
     kanji: KanjiWordQuery,
     similar_kanji: Vec<SimilarKanjiQuery>,
     word_count: usize,
+    chosen_at: DateTime<Utc>,
 ) -> KanjiCard {
     let similar_kanji = similar_kanji
         .into_iter()
@@ -272,11 +1028,122 @@ fn kanji_card_from_query(
         },
         similar_kanji,
         kanji_words: word_count,
+        grade: kanji.kanji_grade.map(|g| g as u32),
+        jlpt: kanji.kanji_jlpt.map(|j| j as u32),
+        stroke_count: kanji.kanji_stroke_count.map(|sc| sc as u32),
+        frequency: kanji.kanji_frequency.map(|f| f as u32),
+        radical: kanji.kanji_radical.map(|r| r as u32),
+        generated_at: SystemTime::from(chosen_at),
     }
 }
 
 // queries
 
+crate::query! {
+    /// The rules for choosing which cards are included in a deck, in which order and with which examples.
+    #[derive(Debug, Clone, Copy)]
+    pub struct DeckRules {
+        pub order: CardOrder = decks::card_order,
+        pub max_word_cards: Option<i32> = decks::max_word_cards,
+        pub max_kanji_cards: Option<i32> = decks::max_kanji_cards,
+        pub min_word_frequency: Option<i32> = decks::min_word_frequency,
+        pub max_word_frequency: Option<i32> = decks::max_word_frequency,
+        pub max_kanji_grade: Option<i32> = decks::max_kanji_grade,
+        pub min_kanji_jlpt: Option<i32> = decks::min_kanji_jlpt,
+        pub extra_examples: i32 = decks::extra_examples,
+        pub example_preference: ExamplePreference = decks::example_preference,
+        pub min_word_frequency_score: Option<i32> = decks::min_word_frequency_score,
+        pub frequency_list_id: Option<i32> = decks::frequency_list_id,
+        pub max_word_frequency_rank: Option<i32> = decks::max_word_frequency_rank,
+    }
+}
+
+impl From<DeckRules> for res::DeckRules {
+    fn from(value: DeckRules) -> Self {
+        let DeckRules {
+            order,
+            max_word_cards,
+            max_kanji_cards,
+            min_word_frequency,
+            max_word_frequency,
+            max_kanji_grade,
+            min_kanji_jlpt,
+            extra_examples,
+            example_preference,
+            min_word_frequency_score,
+            frequency_list_id,
+            max_word_frequency_rank,
+        } = value;
+        Self {
+            order: match order {
+                CardOrder::Frequency => res::CardOrder::Frequency,
+                CardOrder::Appearance => res::CardOrder::Appearance,
+                CardOrder::Random => res::CardOrder::Random,
+                CardOrder::Common => res::CardOrder::Common,
+                CardOrder::Grade => res::CardOrder::Grade,
+            },
+            max_word_cards,
+            max_kanji_cards,
+            min_word_frequency,
+            max_word_frequency,
+            max_kanji_grade,
+            min_kanji_jlpt,
+            extra_examples,
+            example_preference: match example_preference {
+                ExamplePreference::Shortest => res::ExamplePreference::Shortest,
+                ExamplePreference::KnownWords => res::ExamplePreference::KnownWords,
+                ExamplePreference::SourceDiversity => res::ExamplePreference::SourceDiversity,
+            },
+            min_word_frequency_score,
+            frequency_list_id,
+            max_word_frequency_rank,
+        }
+    }
+}
+
+impl From<req::DeckRules> for DeckRules {
+    fn from(value: req::DeckRules) -> Self {
+        let req::DeckRules {
+            order,
+            max_word_cards,
+            max_kanji_cards,
+            min_word_frequency,
+            max_word_frequency,
+            max_kanji_grade,
+            min_kanji_jlpt,
+            extra_examples,
+            example_preference,
+            min_word_frequency_score,
+            frequency_list_id,
+            max_word_frequency_rank,
+        } = value;
+        Self {
+            order: match order {
+                req::CardOrder::Frequency => CardOrder::Frequency,
+                req::CardOrder::Appearance => CardOrder::Appearance,
+                req::CardOrder::Random => CardOrder::Random,
+                req::CardOrder::Common => CardOrder::Common,
+                req::CardOrder::Grade => CardOrder::Grade,
+            },
+            max_word_cards,
+            max_kanji_cards,
+            min_word_frequency,
+            max_word_frequency,
+            max_kanji_grade,
+            min_kanji_jlpt,
+            extra_examples,
+            example_preference: match example_preference {
+                req::ExamplePreference::Shortest => ExamplePreference::Shortest,
+                req::ExamplePreference::KnownWords => ExamplePreference::KnownWords,
+                req::ExamplePreference::SourceDiversity => ExamplePreference::SourceDiversity,
+            },
+            min_word_frequency_score,
+            frequency_list_id,
+            max_word_frequency_rank,
+        }
+    }
+}
+
 crate::query! {
     #[derive(Debug, Clone)]
     struct KanjiQuery {
@@ -298,16 +1165,26 @@ crate::newquery! {
         // postgres doesn't support non-null constraints on array elements,
         // so these are Options even though they're never None
         translations: Option<Vec<Option<String>>> = words::translations.nullable(),
+        // postgres doesn't support non-null constraints on array elements,
+        // so these are Options even though they're never None
+        pitch_accents: Option<Vec<Option<i32>>> = words::pitch_accents.nullable(),
+        frequency_score: Option<i32> = words::frequency_score.nullable(),
 
         // sentence info
         sentence_id: i32 = sentences::id,
         sentence: String = sentences::sentence,
+        sentence_start_ms: Option<i32> = sentences::start_ms,
+        sentence_end_ms: Option<i32> = sentences::end_ms,
         sentence_word_reading: Option<String> = sentence_words::reading,
         // postgres doesn't support non-null constraints on array elements,
         // so these are Options even though they're never None
         sentence_word_furigana: Vec<Option<database::Furigana>> = sentence_words::furigana,
         idx_start: i32 = sentence_words::idx_start,
         idx_end: i32 = sentence_words::idx_end,
+
+        // source info
+        source_id: i32 = sentences::source_id,
+        threshold: i32 = deck_sources::threshold,
     }
 }
 
@@ -317,10 +1194,28 @@ crate::query! {
         kanji_id: i32 = kanji::id,
         kanji: String = kanji::chara,
         kanji_name: Option<String> = kanji::name,
+        kanji_grade: Option<i32> = kanji::grade,
+        kanji_jlpt: Option<i32> = kanji::jlpt,
+        kanji_stroke_count: Option<i32> = kanji::stroke_count,
+        kanji_frequency: Option<i32> = kanji::frequency,
+        kanji_radical: Option<i32> = kanji::radical,
+        word_id: i32 = words::id,
         written_form: String = words::word,
         // postgres doesn't support non-null constraints on array elements,
         // so these are Options even though they're never None
         translations: Vec<Option<String>> = words::translations,
+        sentence_id: i32 = sentences::id,
+        source_id: i32 = sentences::source_id,
+        threshold: i32 = deck_sources::threshold,
+    }
+}
+
+crate::query! {
+    struct DeckCardQuery {
+        card_id: i32 = deck_cards::card_id,
+        sentence_id: Option<i32> = deck_cards::sentence_id,
+        example_word_id: Option<i32> = deck_cards::example_word_id,
+        chosen_at: DateTime<Utc> = deck_cards::chosen_at,
     }
 }
 
@@ -360,7 +1255,13 @@ mod test {
                 reading_end_idx: 6,
             })]),
             translations: Some(vec![Some("Cat".to_string())]),
+            pitch_accents: Some(vec![]),
+            frequency_score: Some(0),
             sentence_id,
+            sentence_start_ms: None,
+            sentence_end_ms: None,
+            source_id: 1,
+            threshold: 1,
         };
         let qs = vec![
             SentenceWordQuery {
@@ -387,7 +1288,13 @@ mod test {
                     }),
                 ]),
                 translations: Some(vec![Some("I".to_string())]),
+                pitch_accents: Some(vec![]),
+                frequency_score: Some(0),
                 sentence_id,
+                sentence_start_ms: None,
+                sentence_end_ms: None,
+                source_id: 1,
+                threshold: 1,
             },
             SentenceWordQuery {
                 word_id,
@@ -400,7 +1307,13 @@ mod test {
                 reading: Some("は".to_string()),
                 furigana: Some(vec![]),
                 translations: Some(vec![Some("tldr".to_string())]),
+                pitch_accents: Some(vec![]),
+                frequency_score: Some(0),
                 sentence_id,
+                sentence_start_ms: None,
+                sentence_end_ms: None,
+                source_id: 1,
+                threshold: 1,
             },
             SentenceWordQuery {
                 word_id,
@@ -418,7 +1331,13 @@ mod test {
                     reading_end_idx: 6,
                 })]),
                 translations: Some(vec![Some("Cat".to_string())]),
+                pitch_accents: Some(vec![]),
+                frequency_score: Some(0),
                 sentence_id,
+                sentence_start_ms: None,
+                sentence_end_ms: None,
+                source_id: 1,
+                threshold: 1,
             },
             SentenceWordQuery {
                 word_id,
@@ -431,7 +1350,13 @@ mod test {
                 reading: Some("で".to_string()),
                 furigana: Some(vec![]),
                 translations: Some(vec![Some("something".to_string())]),
+                pitch_accents: Some(vec![]),
+                frequency_score: Some(0),
                 sentence_id,
+                sentence_start_ms: None,
+                sentence_end_ms: None,
+                source_id: 1,
+                threshold: 1,
             },
             SentenceWordQuery {
                 word_id,
@@ -444,7 +1369,13 @@ mod test {
                 reading: Some("ある".to_string()),
                 furigana: Some(vec![]),
                 translations: Some(vec![]),
+                pitch_accents: Some(vec![]),
+                frequency_score: Some(0),
                 sentence_id,
+                sentence_start_ms: None,
+                sentence_end_ms: None,
+                source_id: 1,
+                threshold: 1,
             },
         ];
 
@@ -452,10 +1383,135 @@ mod test {
         kanji_names_by_kanji.insert("猫".to_string(), Some("cat".to_string()));
 
         let qs = qs.iter().collect::<Vec<_>>();
-        let card = word_card_from_query(&query, &qs, &kanji_names_by_kanji, 1);
+        let card = word_card_from_query(
+            &query,
+            &qs,
+            &kanji_names_by_kanji,
+            1,
+            vec![],
+            vec![],
+            vec![],
+            Utc::now(),
+        );
         assert_eq!(card.sentence.words[0].furigana[0].furigana, "わが");
     }
 
+    #[test]
+    fn orders_and_limits_cards() {
+        // (id, count, first sentence id, frequency score, grade)
+        let cards = vec![
+            (1, 2, 30, 0, Some(2)),
+            (2, 5, 20, 50, None),
+            (3, 2, 10, 90, Some(2)),
+            (4, 1, 40, 50, Some(1)),
+        ];
+        let stats = |&(id, count, first_sentence_id, frequency_score, grade): &(
+            i32,
+            usize,
+            i32,
+            i32,
+            Option<i32>,
+        )| CardStats {
+            id,
+            count,
+            first_sentence_id: Some(first_sentence_id),
+            frequency_score,
+            frequency_rank: None,
+            grade,
+        };
+
+        let ordered = order_cards(cards.clone(), CardOrder::Frequency, Some(3), stats);
+        let ids = ordered.iter().map(|c| c.0).collect::<Vec<_>>();
+        assert_eq!(ids, [2, 3, 1]);
+
+        let ordered = order_cards(cards.clone(), CardOrder::Appearance, None, stats);
+        let ids = ordered.iter().map(|c| c.0).collect::<Vec<_>>();
+        assert_eq!(ids, [3, 2, 1, 4]);
+
+        let ordered = order_cards(cards.clone(), CardOrder::Common, None, stats);
+        let ids = ordered.iter().map(|c| c.0).collect::<Vec<_>>();
+        assert_eq!(ids, [3, 2, 4, 1]);
+
+        let ordered = order_cards(cards.clone(), CardOrder::Grade, None, stats);
+        let ids = ordered.iter().map(|c| c.0).collect::<Vec<_>>();
+        assert_eq!(ids, [4, 3, 1, 2]);
+
+        let ordered = order_cards(cards, CardOrder::Random, Some(2), stats);
+        assert_eq!(ordered.len(), 2);
+    }
+
+    #[test]
+    fn chooses_extra_examples() {
+        let sentence_word = |sentence_id: i32, source_id: i32, sentence: &str| SentenceWordQuery {
+            word_id: Some(1),
+            word: Some("猫".to_string()),
+            reading: Some("ねこ".to_string()),
+            furigana: Some(vec![]),
+            translations: Some(vec![]),
+            pitch_accents: Some(vec![]),
+            frequency_score: Some(0),
+            sentence_id,
+            sentence: sentence.to_string(),
+            sentence_start_ms: None,
+            sentence_end_ms: None,
+            sentence_word_reading: None,
+            sentence_word_furigana: vec![],
+            idx_start: 0,
+            idx_end: 3,
+            source_id,
+            threshold: 1,
+        };
+        let sentences = [
+            sentence_word(1, 1, "猫です"),
+            sentence_word(2, 1, "猫がいる"),
+            sentence_word(2, 1, "猫がいる"),
+            sentence_word(3, 1, "猫が好き"),
+            sentence_word(4, 2, "猫が好きですか"),
+            sentence_word(5, 3, "猫が寝ているね"),
+        ];
+        let word_sentences = sentences.iter().collect::<Vec<_>>();
+        let ids = |examples: Vec<&SentenceWordQuery>| {
+            examples.iter().map(|sw| sw.sentence_id).collect::<Vec<_>>()
+        };
+
+        let examples = choose_extra_examples(
+            &word_sentences,
+            &sentences[0],
+            3,
+            ExamplePreference::Shortest,
+            |_| 0,
+        );
+        assert_eq!(ids(examples), [2, 3, 4]);
+
+        let unknown_words = |sentence_id| if sentence_id == 5 { 0 } else { 1 };
+        let examples = choose_extra_examples(
+            &word_sentences,
+            &sentences[0],
+            2,
+            ExamplePreference::KnownWords,
+            unknown_words,
+        );
+        assert_eq!(ids(examples), [5, 2]);
+
+        let examples = choose_extra_examples(
+            &word_sentences,
+            &sentences[0],
+            3,
+            ExamplePreference::SourceDiversity,
+            |_| 0,
+        );
+        assert_eq!(ids(examples), [4, 5, 2]);
+
+        let examples = choose_extra_examples(
+            &word_sentences,
+            &sentences[0],
+            0,
+            ExamplePreference::Shortest,
+            |_| 0,
+        );
+        assert!(examples.is_empty());
+    }
+
     #[test]
     fn converts_db_furigana_to_anki_furigana() {
         let furigana = &[
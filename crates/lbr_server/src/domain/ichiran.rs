@@ -1,7 +1,13 @@
 //! Functions and types related to ichiran.
 
+use crate::{LbrPool, eq};
 use diesel::prelude::*;
-use std::collections::HashMap;
+use eyre::WrapErr;
+use ichiran::Segment;
+use lbr::segmenter::IchiranCache;
+use moka::sync::Cache;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, path::Path, sync::Arc, time::UNIX_EPOCH};
 
 /// Returns a mapping from ichiran's words to LBR word ids.
 // maps (ichiran_seq, word_written_form, standardised_reading) => word_id
@@ -122,3 +128,119 @@ fn get_roots(
         }
     }
 }
+
+/// Returns a version string for the ichiran CLI at the given path.
+/// Changes whenever the CLI is rebuilt, which happens whenever ichiran or its dictionary is updated.
+// the CLI is a large SBCL image, so it's identified by its size and modification time instead of hashing it
+pub fn ichiran_version(ichiran_cli_path: &Path) -> eyre::Result<String> {
+    let metadata = std::fs::metadata(ichiran_cli_path).wrap_err_with(|| {
+        format!(
+            "Failed to read ichiran CLI metadata at {}",
+            ichiran_cli_path.display()
+        )
+    })?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let version = format!(
+        "{}-{}.{:09}",
+        metadata.len(),
+        modified.as_secs(),
+        modified.subsec_nanos()
+    );
+    Ok(version)
+}
+
+/// Caches ichiran's output in memory and in the database.
+/// The cached segmentations are only valid for the ichiran version they were created with.
+pub struct SegmentationCache {
+    lbr_pool: LbrPool,
+    ichiran_version: String,
+    memory: Cache<Vec<u8>, Arc<Vec<Segment>>>,
+}
+
+impl SegmentationCache {
+    pub fn new(lbr_pool: LbrPool, ichiran_version: String) -> Self {
+        Self {
+            lbr_pool,
+            ichiran_version,
+            memory: Cache::new(10_000),
+        }
+    }
+
+    fn get_from_database(&self, sentence_hash: &[u8]) -> eyre::Result<Option<Vec<Segment>>> {
+        use crate::schema::segmentation_cache as sc;
+
+        let mut conn = self.lbr_pool.get()?;
+        let segments = sc::table
+            .filter(
+                sc::sentence_hash
+                    .eq(sentence_hash)
+                    .and(sc::ichiran_version.eq(&self.ichiran_version)),
+            )
+            .select(sc::segments)
+            .get_result::<serde_json::Value>(&mut conn)
+            .optional()?
+            .map(serde_json::from_value)
+            .transpose()?;
+        Ok(segments)
+    }
+
+    fn insert_to_database(&self, sentence_hash: Vec<u8>, segments: &[Segment]) -> eyre::Result<()> {
+        use crate::schema::segmentation_cache as sc;
+
+        let mut conn = self.lbr_pool.get()?;
+        let ichiran_version = &self.ichiran_version;
+        let segments = serde_json::to_value(segments)?;
+        diesel::insert_into(sc::table)
+            .values(eq!(sc, sentence_hash, ichiran_version, segments))
+            .on_conflict((sc::sentence_hash, sc::ichiran_version))
+            .do_nothing()
+            .execute(&mut conn)?;
+        Ok(())
+    }
+}
+
+impl IchiranCache for SegmentationCache {
+    fn get(&self, text: &str) -> Option<Vec<Segment>> {
+        let sentence_hash = sentence_hash(text);
+        if let Some(segments) = self.memory.get(&sentence_hash) {
+            return Some(segments.as_ref().clone());
+        }
+        match self.get_from_database(&sentence_hash) {
+            Ok(Some(segments)) => {
+                self.memory
+                    .insert(sentence_hash, Arc::new(segments.clone()));
+                Some(segments)
+            }
+            Ok(None) => None,
+            Err(err) => {
+                tracing::warn!("Failed to get cached segmentation for '{text}': {err:#}");
+                None
+            }
+        }
+    }
+
+    fn insert(&self, text: &str, segments: &[Segment]) {
+        let sentence_hash = sentence_hash(text);
+        self.memory
+            .insert(sentence_hash.clone(), Arc::new(segments.to_vec()));
+        if let Err(err) = self.insert_to_database(sentence_hash, segments) {
+            tracing::warn!("Failed to cache segmentation for '{text}': {err:#}");
+        }
+    }
+}
+
+fn sentence_hash(text: &str) -> Vec<u8> {
+    Sha256::digest(text.as_bytes()).to_vec()
+}
+
+/// Clears all cached segmentations, used when the dictionary is updated.
+pub fn clear_segmentation_cache(conn: &mut PgConnection) -> eyre::Result<()> {
+    use crate::schema::segmentation_cache as sc;
+
+    let cleared = diesel::delete(sc::table).execute(conn)?;
+    tracing::info!("Cleared {cleared} cached segmentations");
+    Ok(())
+}
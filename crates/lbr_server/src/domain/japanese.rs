@@ -6,6 +6,7 @@ use crate::{
     utils::database::Furigana,
 };
 use diesel::prelude::*;
+use lbr_core::ruby::Ruby;
 use std::collections::HashMap;
 
 /// Returns a mapping from kanji to its potential readings.
@@ -34,11 +35,17 @@ query! {
 }
 
 /// Maps a reading onto a word with the database `Furigana` type.
+/// Ruby given by the text for the word is used directly if it agrees with the reading.
 pub fn map_to_db_furigana(
     word: &str,
     reading: &str,
+    ruby: &[Ruby],
     kanji_to_readings: &HashMap<String, Vec<String>>,
 ) -> eyre::Result<Vec<Furigana>> {
+    if let Some(furigana) = ruby_to_db_furigana(word, reading, ruby) {
+        return Ok(furigana);
+    }
+
     let furigana = furigana::map(word, reading, kanji_to_readings);
     let furigana = if furigana.is_empty() {
         tracing::warn!(
@@ -56,6 +63,36 @@ pub fn map_to_db_furigana(
     Ok(furigana)
 }
 
+// converts ruby with ranges relative to the word to the database form,
+// if the word with the ruby readings spells out the reading
+fn ruby_to_db_furigana(word: &str, reading: &str, ruby: &[Ruby]) -> Option<Vec<Furigana>> {
+    if ruby.is_empty() {
+        return None;
+    }
+
+    let mut f = Vec::new();
+    let mut spelled_reading = String::new();
+    let mut word_idx = 0;
+    for r in ruby {
+        spelled_reading.push_str(word.get(word_idx..r.range.start)?);
+        let reading_start_idx = i32::try_from(spelled_reading.len()).ok()?;
+        spelled_reading.push_str(&lbr::standardise_reading(&r.reading).hiragana);
+        f.push(Furigana {
+            word_start_idx: i32::try_from(r.range.start).ok()?,
+            word_end_idx: i32::try_from(r.range.end).ok()?,
+            reading_start_idx,
+            reading_end_idx: i32::try_from(spelled_reading.len()).ok()?,
+        });
+        word_idx = r.range.end;
+    }
+    spelled_reading.push_str(word.get(word_idx..)?);
+
+    let matches = lbr::standardise_reading(&spelled_reading).standardised
+        == lbr::standardise_reading(reading).standardised
+        && spelled_reading.len() == reading.len();
+    matches.then_some(f)
+}
+
 // converts furigana to the database form
 fn furigana_to_db_furigana(furigana: furigana::Furigana) -> eyre::Result<Vec<Furigana>> {
     let mut f = Vec::new();
@@ -86,6 +123,26 @@ fn furigana_to_db_furigana(furigana: furigana::Furigana) -> eyre::Result<Vec<Fur
 mod test {
     use super::*;
 
+    #[test]
+    fn uses_ruby_as_furigana() {
+        let ruby = vec![Ruby {
+            range: 0..3,
+            reading: "うま".to_string(),
+        }];
+        let furigana = ruby_to_db_furigana("生れた", "うまれた", &ruby).unwrap();
+        assert_eq!(
+            furigana,
+            vec![Furigana {
+                word_start_idx: 0,
+                word_end_idx: 3,
+                reading_start_idx: 0,
+                reading_end_idx: 6
+            }]
+        );
+        assert!(ruby_to_db_furigana("生れた", "いきれた", &ruby).is_none());
+        assert!(ruby_to_db_furigana("生れた", "うまれた", &[]).is_none());
+    }
+
     #[test]
     fn converts_furigana_to_db_furigana() {
         use crate::utils::database::Furigana as DbFurigana;
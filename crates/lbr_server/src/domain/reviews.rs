@@ -0,0 +1,434 @@
+//! Functions and types related to reviewing cards without Anki.
+
+use super::{
+    decks::{self, CardSelection},
+    user_words,
+};
+use crate::{
+    eq,
+    error::EyreResult,
+    queries, query,
+    utils::{
+        database::{CardKind, DeckSourceKind, ReviewRating, WordStatus},
+        diesel::PostgresChunks,
+    },
+};
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, upsert::excluded};
+use itertools::Itertools;
+use lbr::{
+    anki::{
+        KanjiCard, NoteType, RenderedCard, WordCard,
+        history::{CardHistory, LbrCard},
+    },
+    srs::{self, CardState, Rating},
+};
+use lbr_api::{request as req, response as res};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+/// How many cards that have never been reviewed are included in the due cards.
+const MAX_NEW_CARDS: usize = 20;
+
+/// Returns the deck's cards that are due for review, ordered by when they were due,
+/// followed by some new cards in the order set by the deck's rules.
+/// Scheduled cards are due whether or not they still pass the deck's rules, as long as they're in the deck's sources.
+pub fn get_due_cards(
+    conn: &mut PgConnection,
+    user_id: i32,
+    deck_id: i32,
+    now: DateTime<Utc>,
+) -> eyre::Result<Vec<res::ReviewCard>> {
+    use crate::schema::{card_schedules as cs, decks as d};
+
+    let decks = d::table
+        .filter(d::id.eq(deck_id).and(d::user_id.eq(user_id)))
+        .select(d::id)
+        .execute(conn)?;
+    if decks != 1 {
+        return Err(eyre::eyre!("No such deck"));
+    }
+
+    let due_by_card = cs::table
+        .filter(cs::user_id.eq(user_id))
+        .select((cs::kind, cs::card_id, cs::due))
+        .get_results::<(CardKind, i32, DateTime<Utc>)>(conn)?
+        .into_iter()
+        .map(|(kind, card_id, due)| ((kind, card_id), due))
+        .collect::<HashMap<_, _>>();
+    let due_ids = |kind: CardKind| {
+        due_by_card
+            .iter()
+            .filter(|((card_kind, _), card_due)| *card_kind == kind && **card_due <= now)
+            .map(|((_, card_id), _)| *card_id)
+            .collect::<HashSet<_>>()
+    };
+    let due_word_ids = due_ids(CardKind::Word);
+    let due_kanji_ids = due_ids(CardKind::Kanji);
+
+    // the examples chosen for cards are only stored when the deck is generated
+    let due_word_cards =
+        decks::get_word_cards(conn, user_id, deck_id, CardSelection::Only(&due_word_ids))?.0;
+    let due_kanji_cards =
+        decks::get_kanji_cards(conn, deck_id, CardSelection::Only(&due_kanji_ids))?.0;
+    let mut due = due_word_cards
+        .into_iter()
+        .map(Card::Word)
+        .chain(due_kanji_cards.into_iter().map(Card::Kanji))
+        .map(|card| (due_by_card[&card.key()], card))
+        .collect::<Vec<_>>();
+    due.sort_by_key(|(card_due, _)| *card_due);
+
+    // new cards in the deck's order, alternating between words and kanji
+    let new_word_cards = decks::get_word_cards(conn, user_id, deck_id, CardSelection::Rules)?.0;
+    let new_kanji_cards = decks::get_kanji_cards(conn, deck_id, CardSelection::Rules)?.0;
+    let new = new_word_cards
+        .into_iter()
+        .map(Card::Word)
+        .interleave(new_kanji_cards.into_iter().map(Card::Kanji))
+        .filter(|card| !due_by_card.contains_key(&card.key()))
+        .take(MAX_NEW_CARDS)
+        .collect::<Vec<_>>();
+
+    let note_types = decks::get_note_types(conn, deck_id)?;
+    let word_note_type = note_types.word();
+    let kanji_note_type = note_types.kanji();
+    let due_cards = due
+        .into_iter()
+        .map(|(_, card)| (card, false))
+        .chain(new.into_iter().map(|card| (card, true)))
+        .map(|(card, new)| card.into_review_card(new, &word_note_type, &kanji_note_type))
+        .collect();
+    Ok(due_cards)
+}
+
+/// Schedules the card's next review based on the rating and logs the review.
+pub fn review(
+    conn: &mut PgConnection,
+    user_id: i32,
+    review: req::Review,
+    now: DateTime<Utc>,
+) -> eyre::Result<()> {
+    use crate::schema::{card_schedules as cs, review_logs as rl};
+
+    let req::Review {
+        kind,
+        card_id,
+        rating,
+    } = review;
+    let kind = match kind {
+        req::CardKind::Word => CardKind::Word,
+        req::CardKind::Kanji => CardKind::Kanji,
+    };
+    let srs_rating = match rating {
+        req::ReviewRating::Again => Rating::Again,
+        req::ReviewRating::Hard => Rating::Hard,
+        req::ReviewRating::Good => Rating::Good,
+        req::ReviewRating::Easy => Rating::Easy,
+    };
+    let rating = to_db_rating(srs_rating);
+    if !in_user_decks(conn, user_id, kind, card_id)? {
+        return Err(eyre::eyre!("No such card"));
+    }
+
+    conn.transaction(|conn| {
+        let schedule = cs::table
+            .filter(
+                cs::user_id
+                    .eq(user_id)
+                    .and(cs::kind.eq(kind))
+                    .and(cs::card_id.eq(card_id)),
+            )
+            .select(CardSchedule::as_select())
+            .get_result(conn)
+            .optional()?;
+        let state = schedule.map(CardState::from).unwrap_or_default();
+        let state = srs::review(&state, srs_rating);
+
+        let interval_secs = i64::try_from(state.interval.as_secs())?;
+        let due = now + chrono::Duration::from_std(state.interval)?;
+        let ease = state.ease;
+        let repetitions = i32::try_from(state.repetitions)?;
+        let lapses = i32::try_from(state.lapses)?;
+        diesel::insert_into(cs::table)
+            .values(eq!(
+                cs,
+                user_id,
+                kind,
+                card_id,
+                due,
+                interval_secs,
+                ease,
+                repetitions,
+                lapses
+            ))
+            .on_conflict((cs::user_id, cs::kind, cs::card_id))
+            .do_update()
+            .set(eq!(cs, due, interval_secs, ease, repetitions, lapses))
+            .execute(conn)?;
+        let reviewed_at = now;
+        diesel::insert_into(rl::table)
+            .values(eq!(
+                rl,
+                user_id,
+                kind,
+                card_id,
+                rating,
+                reviewed_at,
+                interval_secs,
+                ease
+            ))
+            .execute(conn)?;
+        EyreResult::Ok(())
+    })?;
+    Ok(())
+}
+
+/// Stores the schedules and reviews of the cards from Anki, replacing the existing schedules.
+/// Words with mature cards are marked as known unless they are ignored, other reviewed words as learning.
+/// Reviews that were imported before are skipped so the same history can be imported again.
+pub fn import_history(
+    conn: &mut PgConnection,
+    user_id: i32,
+    histories: Vec<CardHistory>,
+    now: DateTime<Utc>,
+) -> eyre::Result<res::ReviewHistoryImport> {
+    use crate::schema::{card_schedules as cs, kanji as k, review_logs as rl, words as w};
+
+    conn.transaction(|conn| {
+        // the cards may be from a different LBR instance or refer to deleted words
+        let word_ids = histories.iter().filter_map(|h| match h.card {
+            LbrCard::Word(id) => Some(id),
+            LbrCard::Kanji(_) => None,
+        });
+        let word_ids = w::table
+            .filter(w::id.eq_any(word_ids.collect::<Vec<_>>()))
+            .select(w::id)
+            .get_results::<i32>(conn)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let kanji_ids = histories.iter().filter_map(|h| match h.card {
+            LbrCard::Kanji(id) => Some(id),
+            LbrCard::Word(_) => None,
+        });
+        let kanji_ids = k::table
+            .filter(k::id.eq_any(kanji_ids.collect::<Vec<_>>()))
+            .select(k::id)
+            .get_results::<i32>(conn)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let imported_reviews = rl::table
+            .filter(rl::user_id.eq(user_id))
+            .select((rl::kind, rl::card_id, rl::reviewed_at))
+            .get_results::<(CardKind, i32, DateTime<Utc>)>(conn)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let word_statuses = queries::word_statuses(conn, user_id)?;
+
+        let mut cards = 0;
+        let mut schedules = Vec::new();
+        let mut review_logs = Vec::new();
+        let mut known_words = Vec::new();
+        let mut learning_words = Vec::new();
+        for history in histories {
+            let (kind, card_id) = match history.card {
+                LbrCard::Word(id) if word_ids.contains(&id) => (CardKind::Word, id),
+                LbrCard::Kanji(id) if kanji_ids.contains(&id) => (CardKind::Kanji, id),
+                LbrCard::Word(_) | LbrCard::Kanji(_) => continue,
+            };
+            cards += 1;
+
+            let last_review = history
+                .reviews
+                .last()
+                .map(|r| DateTime::<Utc>::from(r.reviewed_at))
+                .unwrap_or(now);
+            let due = last_review + chrono::Duration::from_std(history.interval)?;
+            let interval_secs = i64::try_from(history.interval.as_secs())?;
+            let ease = history.ease;
+            let repetitions = i32::try_from(history.repetitions)?;
+            let lapses = i32::try_from(history.lapses)?;
+            schedules.push(eq!(
+                cs,
+                user_id,
+                kind,
+                card_id,
+                due,
+                interval_secs,
+                ease,
+                repetitions,
+                lapses
+            ));
+
+            if kind == CardKind::Word {
+                let ignored = word_statuses.get(&card_id) == Some(&WordStatus::Ignored);
+                if history.is_mature() && !ignored {
+                    known_words.push(card_id);
+                } else {
+                    learning_words.push(card_id);
+                }
+            }
+
+            for review in &history.reviews {
+                let reviewed_at = DateTime::<Utc>::from(review.reviewed_at);
+                if imported_reviews.contains(&(kind, card_id, reviewed_at)) {
+                    continue;
+                }
+                let rating = to_db_rating(review.rating);
+                let interval_secs = i64::try_from(review.interval.as_secs())?;
+                let ease = review.ease;
+                review_logs.push(eq!(
+                    rl,
+                    user_id,
+                    kind,
+                    card_id,
+                    rating,
+                    reviewed_at,
+                    interval_secs,
+                    ease
+                ));
+            }
+        }
+
+        for chunk in schedules.pg_chunks() {
+            diesel::insert_into(cs::table)
+                .values(chunk)
+                .on_conflict((cs::user_id, cs::kind, cs::card_id))
+                .do_update()
+                .set((
+                    cs::due.eq(excluded(cs::due)),
+                    cs::interval_secs.eq(excluded(cs::interval_secs)),
+                    cs::ease.eq(excluded(cs::ease)),
+                    cs::repetitions.eq(excluded(cs::repetitions)),
+                    cs::lapses.eq(excluded(cs::lapses)),
+                ))
+                .execute(conn)?;
+        }
+        for chunk in review_logs.pg_chunks() {
+            diesel::insert_into(rl::table).values(chunk).execute(conn)?;
+        }
+        let known_word_updates = known_words
+            .iter()
+            .map(|&word_id| req::WordStatusUpdate {
+                word_id,
+                status: req::WordStatus::Known,
+                level: None,
+            })
+            .collect::<Vec<_>>();
+        user_words::set_statuses(conn, user_id, &known_word_updates)?;
+        user_words::insert_new_words(conn, user_id, learning_words, WordStatus::Learning)?;
+
+        EyreResult::Ok(res::ReviewHistoryImport {
+            cards,
+            reviews: review_logs.len() as i32,
+            known_words: known_words.len() as i32,
+        })
+    })
+}
+
+// checks whether the word or kanji is in one of the sources of the user's decks for its kind
+fn in_user_decks(
+    conn: &mut PgConnection,
+    user_id: i32,
+    kind: CardKind,
+    card_id: i32,
+) -> eyre::Result<bool> {
+    use crate::schema::{
+        deck_sources as ds, decks as d, sentence_words as sw, sentences as s, word_kanji as wk,
+    };
+
+    let sentence_words = d::table
+        .filter(d::user_id.eq(user_id))
+        .inner_join(ds::table.on(ds::deck_id.eq(d::id)))
+        .inner_join(s::table.on(s::source_id.eq(ds::source_id)))
+        .inner_join(sw::table.on(sw::sentence_id.eq(s::id)));
+    let deck_id = match kind {
+        CardKind::Word => sentence_words
+            .filter(ds::kind.eq(DeckSourceKind::Word))
+            .filter(sw::word_id.eq(card_id))
+            .select(d::id)
+            .first::<i32>(conn)
+            .optional()?,
+        CardKind::Kanji => sentence_words
+            .inner_join(wk::table.on(wk::word_id.nullable().eq(sw::word_id)))
+            .filter(ds::kind.eq(DeckSourceKind::Kanji))
+            .filter(wk::kanji_id.eq(card_id))
+            .select(d::id)
+            .first::<i32>(conn)
+            .optional()?,
+    };
+    Ok(deck_id.is_some())
+}
+
+fn to_db_rating(rating: Rating) -> ReviewRating {
+    match rating {
+        Rating::Again => ReviewRating::Again,
+        Rating::Hard => ReviewRating::Hard,
+        Rating::Good => ReviewRating::Good,
+        Rating::Easy => ReviewRating::Easy,
+    }
+}
+
+enum Card {
+    Word(WordCard),
+    Kanji(KanjiCard),
+}
+
+impl Card {
+    fn key(&self) -> (CardKind, i32) {
+        match self {
+            Self::Word(card) => (CardKind::Word, card.id),
+            Self::Kanji(card) => (CardKind::Kanji, card.id),
+        }
+    }
+
+    fn into_review_card(
+        self,
+        new: bool,
+        word_note_type: &NoteType,
+        kanji_note_type: &NoteType,
+    ) -> res::ReviewCard {
+        let (kind, card_id) = match self.key() {
+            (CardKind::Word, card_id) => (res::CardKind::Word, card_id),
+            (CardKind::Kanji, card_id) => (res::CardKind::Kanji, card_id),
+        };
+        let RenderedCard {
+            question,
+            answer,
+            css,
+        } = match self {
+            Self::Word(card) => card.into_fields().render(word_note_type),
+            Self::Kanji(card) => card.into_fields().render(kanji_note_type),
+        };
+        res::ReviewCard {
+            kind,
+            card_id,
+            question,
+            answer,
+            css,
+            new,
+        }
+    }
+}
+
+query! {
+    struct CardSchedule {
+        interval_secs: i64 = card_schedules::interval_secs,
+        ease: f64 = card_schedules::ease,
+        repetitions: i32 = card_schedules::repetitions,
+        lapses: i32 = card_schedules::lapses,
+    }
+}
+
+impl From<CardSchedule> for CardState {
+    fn from(value: CardSchedule) -> Self {
+        Self {
+            interval: Duration::from_secs(value.interval_secs.max(0) as u64),
+            ease: value.ease,
+            repetitions: value.repetitions.max(0) as u32,
+            lapses: value.lapses.max(0) as u32,
+        }
+    }
+}
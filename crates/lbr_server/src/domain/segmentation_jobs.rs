@@ -0,0 +1,418 @@
+//! Functions and types related to background segmentation jobs.
+
+use super::{sentences, user_words, words};
+use crate::{
+    LbrState, eq, error::EyreResult, segmenter_pool::PendingSegmentation,
+    utils::database::SegmentationJobStatus,
+};
+use diesel::prelude::*;
+use eyre::WrapErr;
+use lbr::{
+    import::subtitles::Subtitle, ruby::Ruby, segmenter::SegmenterError,
+    sentence_splitter::SentenceSplitter,
+};
+use lbr_api::response as res;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// How many of a job's sentences are queued in the segmenter pool at once.
+const QUEUED_SENTENCES: usize = 64;
+
+/// Creates a job for segmenting the new sentences in the paragraph.
+/// Sentences that already exist in the source are skipped.
+/// Ruby in the paragraph is removed from the sentences and stored separately.
+pub fn create_job(
+    conn: &mut PgConnection,
+    user_id: i32,
+    source_id: i32,
+    title: Option<&str>,
+    paragraph: &str,
+) -> eyre::Result<i32> {
+    let sentences = SentenceSplitter::new(paragraph).map(|sentence| (sentence, None));
+    insert_job(conn, user_id, source_id, title, sentences)
+}
+
+/// Creates a job for segmenting the new sentences from subtitles.
+/// Sentences that already exist in the source at the same time are skipped.
+pub fn create_subtitle_job(
+    conn: &mut PgConnection,
+    user_id: i32,
+    source_id: i32,
+    title: Option<&str>,
+    subtitles: &[Subtitle],
+) -> eyre::Result<i32> {
+    let mut sentences = Vec::new();
+    for subtitle in subtitles {
+        let timestamps = res::Timestamps {
+            start_ms: i32::try_from(subtitle.range.start.as_millis())?,
+            end_ms: i32::try_from(subtitle.range.end.as_millis())?,
+        };
+        sentences.push((subtitle.text.as_str(), Some(timestamps)));
+    }
+    insert_job(conn, user_id, source_id, title, sentences)
+}
+
+fn insert_job<'a>(
+    conn: &mut PgConnection,
+    user_id: i32,
+    source_id: i32,
+    title: Option<&str>,
+    sentences: impl IntoIterator<Item = (&'a str, Option<res::Timestamps>)>,
+) -> eyre::Result<i32> {
+    use crate::schema::{
+        segmentation_job_sentences as sjs, segmentation_jobs as sj, sentences as se, sources as so,
+    };
+
+    conn.transaction(|conn| {
+        let source_id = so::table
+            .filter(so::id.eq(source_id).and(so::user_id.eq(user_id)))
+            .select(so::id)
+            .get_result::<i32>(conn)?;
+
+        let status = SegmentationJobStatus::Queued;
+        let job_id = diesel::insert_into(sj::table)
+            .values(eq!(sj, user_id, source_id, status, title))
+            .returning(sj::id)
+            .get_result::<i32>(conn)?;
+
+        let mut job_sentences = Vec::new();
+        for (sentence, timestamps) in sentences {
+            let (sentence, ruby) = lbr::ruby::parse(sentence);
+            let (start_ms, end_ms) = timestamps.map(|t| (t.start_ms, t.end_ms)).unzip();
+            let existing_sentences = se::table
+                .select(se::id)
+                .filter(
+                    se::sentence
+                        .eq(&sentence)
+                        .and(eq!(se, source_id))
+                        .and(se::start_ms.is_not_distinct_from(start_ms)),
+                )
+                .execute(conn)?;
+            if existing_sentences != 0 {
+                tracing::info!("Skipping existing sentence {sentence}");
+                continue;
+            }
+            let idx = job_sentences.len() as i32;
+            let ruby = serde_json::to_value(ruby)?;
+            job_sentences.push(eq!(sjs, job_id, idx, sentence, ruby, start_ms, end_ms));
+        }
+        diesel::insert_into(sjs::table)
+            .values(job_sentences)
+            .execute(conn)?;
+
+        EyreResult::Ok(job_id)
+    })
+}
+
+/// Runs the job in the background.
+pub fn spawn_job(state: LbrState, job_id: i32) {
+    spawn_jobs(state, vec![job_id]);
+}
+
+/// Runs the jobs in the background one after another.
+pub fn spawn_jobs(state: LbrState, job_ids: Vec<i32>) {
+    tokio::task::spawn_blocking(move || {
+        for job_id in job_ids {
+            if let Err(err) = run_job(&state, job_id) {
+                tracing::error!("Segmentation job {job_id} failed: {err:#}");
+                if let Err(err) = set_job_status(&state, job_id, SegmentationJobStatus::Failed) {
+                    tracing::error!("Failed to mark segmentation job {job_id} as failed: {err:#}");
+                }
+            }
+        }
+    });
+}
+
+/// Restarts all jobs that were left unfinished, e.g. due to the server being restarted.
+pub fn resume_jobs(state: &LbrState) -> eyre::Result<()> {
+    use crate::schema::segmentation_jobs as sj;
+
+    let mut conn = state.lbr_pool.get()?;
+    let job_ids = sj::table
+        .filter(sj::status.eq_any([
+            SegmentationJobStatus::Queued,
+            SegmentationJobStatus::Running,
+        ]))
+        .select(sj::id)
+        .order_by(sj::id)
+        .get_results::<i32>(&mut conn)?;
+    if !job_ids.is_empty() {
+        tracing::info!("Resuming segmentation jobs {job_ids:?}");
+        spawn_jobs(state.clone(), job_ids);
+    }
+    Ok(())
+}
+
+fn run_job(state: &LbrState, job_id: i32) -> eyre::Result<()> {
+    use crate::schema::segmentation_job_sentences as sjs;
+
+    set_job_status(state, job_id, SegmentationJobStatus::Running)?;
+
+    let unfinished_sentences = sjs::table
+        .filter(
+            sjs::job_id
+                .eq(job_id)
+                .and(sjs::segments.is_null())
+                .and(sjs::error.is_null()),
+        )
+        .select((sjs::idx, sjs::sentence, sjs::ruby))
+        .order_by(sjs::idx)
+        .get_results::<(i32, String, serde_json::Value)>(&mut state.lbr_pool.get()?)?;
+
+    // a few sentences are kept queued so that the pool's workers can segment them concurrently
+    // without the rest of a long job waiting in the pool's queue,
+    // the results are stored in order so that the finished sentences always form a prefix of the job
+    let mut unfinished_sentences = unfinished_sentences.into_iter();
+    let mut pending_sentences = VecDeque::new();
+    loop {
+        while pending_sentences.len() < QUEUED_SENTENCES {
+            let Some((idx, sentence, ruby)) = unfinished_sentences.next() else {
+                break;
+            };
+            let pending = state.segmenter.submit(sentence.clone());
+            pending_sentences.push_back((idx, sentence, ruby, pending));
+        }
+        let Some((idx, sentence, ruby, pending)) = pending_sentences.pop_front() else {
+            break;
+        };
+
+        // a sentence that fails is recorded as such and the job moves on to the next one
+        let (segments, error) = match segment_job_sentence(state, &sentence, ruby, pending) {
+            Ok(segments) => (Some(segments), None),
+            Err(err) => {
+                tracing::warn!("{err:#}");
+                (None, Some(format!("{err:#}")))
+            }
+        };
+        if let Err(err) = store_job_sentence(state, job_id, idx, segments, error) {
+            tracing::error!("Failed to store sentence {idx} of segmentation job {job_id}: {err:#}");
+            let error = Some(format!("Failed to store the segmented sentence: {err:#}"));
+            if let Err(err) = store_job_sentence(state, job_id, idx, None, error) {
+                tracing::error!(
+                    "Failed to store the error for sentence {idx} of segmentation job {job_id}: {err:#}"
+                );
+            }
+        }
+    }
+
+    set_job_status(state, job_id, SegmentationJobStatus::Finished)?;
+    Ok(())
+}
+
+// waits for the sentence's segmentation and converts it to the API type
+fn segment_job_sentence(
+    state: &LbrState,
+    sentence: &str,
+    ruby: serde_json::Value,
+    pending: Result<PendingSegmentation, SegmenterError>,
+) -> eyre::Result<serde_json::Value> {
+    let ruby = serde_json::from_value::<Vec<Ruby>>(ruby)?;
+    let mut segments = pending
+        .and_then(PendingSegmentation::wait)
+        .wrap_err_with(|| format!("Failed to segment sentence '{sentence}'"))?;
+    lbr::ruby::prefer_ruby_readings(sentence, &mut segments, &ruby);
+    let mut conn = state.lbr_pool.get()?;
+    let segments = sentences::to_api_segments(&mut conn, sentence, segments)?;
+    Ok(serde_json::to_value(segments)?)
+}
+
+fn store_job_sentence(
+    state: &LbrState,
+    job_id: i32,
+    idx: i32,
+    segments: Option<serde_json::Value>,
+    error: Option<String>,
+) -> eyre::Result<()> {
+    use crate::schema::segmentation_job_sentences as sjs;
+
+    let mut conn = state.lbr_pool.get()?;
+    diesel::update(sjs::table.filter(sjs::job_id.eq(job_id).and(sjs::idx.eq(idx))))
+        .set(eq!(sjs, segments, error))
+        .execute(&mut conn)?;
+    Ok(())
+}
+
+fn set_job_status(
+    state: &LbrState,
+    job_id: i32,
+    status: SegmentationJobStatus,
+) -> eyre::Result<()> {
+    use crate::schema::segmentation_jobs as sj;
+
+    let mut conn = state.lbr_pool.get()?;
+    diesel::update(sj::table.filter(sj::id.eq(job_id)))
+        .set(eq!(sj, status))
+        .execute(&mut conn)?;
+    Ok(())
+}
+
+/// Returns the job's status and the progress of each of its sentences.
+pub fn get_job(
+    conn: &mut PgConnection,
+    user_id: i32,
+    job_id: i32,
+) -> eyre::Result<res::SegmentationJob> {
+    use crate::schema::{segmentation_job_sentences as sjs, segmentation_jobs as sj};
+
+    let (source_id, title, status) = sj::table
+        .filter(sj::id.eq(job_id).and(sj::user_id.eq(user_id)))
+        .select((sj::source_id, sj::title, sj::status))
+        .get_result::<(i32, Option<String>, SegmentationJobStatus)>(conn)?;
+    let sentences = sjs::table
+        .filter(sjs::job_id.eq(job_id))
+        .select((sjs::sentence, sjs::segments.is_not_null(), sjs::error))
+        .order_by(sjs::idx)
+        .get_results::<(String, bool, Option<String>)>(conn)?
+        .into_iter()
+        .map(
+            |(sentence, segmented, error)| res::SegmentationJobSentence {
+                sentence,
+                segmented,
+                error,
+            },
+        )
+        .collect::<Vec<_>>();
+    let failed = sentences.iter().filter(|s| s.error.is_some()).count() as i32;
+
+    Ok(res::SegmentationJob {
+        id: job_id,
+        source_id,
+        title,
+        status: to_api_status(status),
+        sentences,
+        failed,
+    })
+}
+
+fn to_api_status(status: SegmentationJobStatus) -> res::SegmentationJobStatus {
+    match status {
+        SegmentationJobStatus::Queued => res::SegmentationJobStatus::Queued,
+        SegmentationJobStatus::Running => res::SegmentationJobStatus::Running,
+        SegmentationJobStatus::Finished => res::SegmentationJobStatus::Finished,
+        SegmentationJobStatus::Failed => res::SegmentationJobStatus::Failed,
+    }
+}
+
+/// Returns a summary of each of the source's jobs.
+pub fn get_source_jobs(
+    conn: &mut PgConnection,
+    user_id: i32,
+    source_id: i32,
+) -> eyre::Result<Vec<res::SegmentationJobSummary>> {
+    use crate::schema::{segmentation_job_sentences as sjs, segmentation_jobs as sj};
+
+    let jobs = sj::table
+        .filter(sj::source_id.eq(source_id).and(sj::user_id.eq(user_id)))
+        .select((sj::id, sj::title, sj::status))
+        .order_by(sj::id)
+        .get_results::<(i32, Option<String>, SegmentationJobStatus)>(conn)?;
+    let job_ids = jobs.iter().map(|(id, _, _)| *id).collect::<Vec<_>>();
+    let mut sentence_counts = HashMap::<i32, (i32, i32, i32)>::new();
+    for (job_id, segmented, failed) in sjs::table
+        .filter(sjs::job_id.eq_any(job_ids))
+        .select((
+            sjs::job_id,
+            sjs::segments.is_not_null(),
+            sjs::error.is_not_null(),
+        ))
+        .get_results::<(i32, bool, bool)>(conn)?
+    {
+        let (sentences, segmented_sentences, failed_sentences) =
+            sentence_counts.entry(job_id).or_default();
+        *sentences += 1;
+        if segmented {
+            *segmented_sentences += 1;
+        } else if failed {
+            *failed_sentences += 1;
+        }
+    }
+
+    let jobs = jobs
+        .into_iter()
+        .map(|(id, title, status)| {
+            let (sentences, segmented, failed) =
+                sentence_counts.get(&id).copied().unwrap_or_default();
+            res::SegmentationJobSummary {
+                id,
+                title,
+                status: to_api_status(status),
+                sentences,
+                segmented,
+                failed,
+            }
+        })
+        .collect();
+    Ok(jobs)
+}
+
+/// Returns the sentences the job has successfully segmented so far.
+pub fn get_job_results(
+    conn: &mut PgConnection,
+    user_id: i32,
+    job_id: i32,
+) -> eyre::Result<res::SegmentedParagraph> {
+    use crate::schema::{segmentation_job_sentences as sjs, segmentation_jobs as sj};
+
+    let job_id = sj::table
+        .filter(sj::id.eq(job_id).and(sj::user_id.eq(user_id)))
+        .select(sj::id)
+        .get_result::<i32>(conn)?;
+    let segmented_sentences = sjs::table
+        .filter(sjs::job_id.eq(job_id).and(sjs::segments.is_not_null()))
+        .select((
+            sjs::sentence,
+            sjs::segments.assume_not_null(),
+            sjs::ruby,
+            sjs::start_ms,
+            sjs::end_ms,
+        ))
+        .order_by(sjs::idx)
+        .get_results::<(
+            String,
+            serde_json::Value,
+            serde_json::Value,
+            Option<i32>,
+            Option<i32>,
+        )>(conn)?;
+
+    let mut sentences = Vec::new();
+    let mut word_ids = HashSet::new();
+    for (sentence, segments, ruby, start_ms, end_ms) in segmented_sentences {
+        let segments = serde_json::from_value::<Vec<res::ApiSegment>>(segments)?;
+        let ruby = serde_json::from_value::<Vec<Ruby>>(ruby)?;
+        for segment in &segments {
+            for interpretation in &segment.interpretations {
+                if let Some(word_id) = interpretation.word_id {
+                    word_ids.insert(word_id);
+                }
+            }
+        }
+        sentences.push(res::SegmentedParagraphSentence {
+            sentence,
+            segments,
+            ruby,
+            timestamps: sentences::to_api_timestamps(start_ms, end_ms),
+        });
+    }
+
+    let word_statuses = user_words::get_statuses(conn, user_id, &word_ids)?;
+    let word_ids = word_ids.into_iter().collect::<Vec<_>>();
+    let word_definitions = words::get_word_definitions(conn, user_id, &word_ids)?;
+    Ok(res::SegmentedParagraph {
+        sentences,
+        word_statuses,
+        word_definitions,
+    })
+}
+
+/// Deletes all of the source's jobs.
+pub fn delete_source_jobs(conn: &mut PgConnection, source_id: i32) -> eyre::Result<()> {
+    use crate::schema::{segmentation_job_sentences as sjs, segmentation_jobs as sj};
+
+    let job_ids = sj::table
+        .filter(sj::source_id.eq(source_id))
+        .select(sj::id)
+        .get_results::<i32>(conn)?;
+    diesel::delete(sjs::table.filter(sjs::job_id.eq_any(&job_ids))).execute(conn)?;
+    diesel::delete(sj::table.filter(sj::id.eq_any(&job_ids))).execute(conn)?;
+    Ok(())
+}
@@ -1,46 +1,55 @@
 //! Functions and types related to sentences.
 
-use super::japanese;
-use crate::{eq, error::EyreResult, utils::diesel::PostgresChunks};
+use super::{japanese, user_words, words};
+use crate::{
+    eq,
+    error::EyreResult,
+    queries, query,
+    utils::{
+        database::{self, WordStatus},
+        diesel::PostgresChunks,
+    },
+};
 use diesel::prelude::*;
 use eyre::WrapErr;
-use ichiran::{IchiranCli, IchiranError};
+use lbr::segmenter::Segmenter;
 use lbr_api::{
     request as req,
     response::{self as res, ApiInterpretation, ApiSegment},
 };
+use lbr_core::{ichiran_types::Segment, ruby::Ruby};
 use std::collections::{HashMap, HashSet};
 
-/// Segments a sentence using ichiran.
+/// Segments a sentence using the given segmenter.
 pub fn segment_sentence(
     conn: &mut PgConnection,
-    ichiran: &IchiranCli,
+    segmenter: &dyn Segmenter,
     sentence: &str,
-    ichiran_word_to_id: &HashMap<(i32, String, String), i32>,
-    kanji_to_readings: &HashMap<String, Vec<String>>,
-    word_to_meanings: &HashMap<i32, Vec<String>>,
 ) -> eyre::Result<Vec<ApiSegment>> {
-    use crate::schema::words as w;
-
     tracing::info!("Segmenting sentence '{sentence}'");
 
-    // get individual words from sentence with ichiran
-    let segments = match ichiran.segment(sentence, Some(16)) {
-        Ok(segments) => segments,
-        Err(err) => {
-            if let IchiranError::IchiranError { stdout, stderr } = &err {
-                tracing::error!("Ichiran error:\n    stdout:\n{stdout}\n    stderr:\n{stderr}");
-            }
-            return Err(err).wrap_err_with(|| format!("Failed to segment sentence '{sentence}'"));
-        }
-    };
-    let segmented_sentence = lbr::core::to_lbr_segments(
-        sentence,
-        segments,
-        ichiran_word_to_id,
-        kanji_to_readings,
-        word_to_meanings,
-    );
+    // get individual words from sentence with the segmenter
+    let segmented_sentence = segmenter
+        .segment(sentence)
+        .wrap_err_with(|| format!("Failed to segment sentence '{sentence}'"))?;
+
+    to_api_segments(conn, sentence, segmented_sentence)
+}
+
+/// Converts the segmenter's output for a sentence into the API type.
+pub fn to_api_segments(
+    conn: &mut PgConnection,
+    sentence: &str,
+    segmented_sentence: Vec<Segment>,
+) -> eyre::Result<Vec<ApiSegment>> {
+    use crate::schema::words as w;
+
+    let word_ids = segmented_sentence
+        .iter()
+        .flat_map(|segment| &segment.interpretations)
+        .filter_map(|interpretation| interpretation.word_id)
+        .collect::<Vec<_>>();
+    let word_senses = words::get_word_senses(conn, &word_ids)?;
 
     let mut api_segmented_sentence = Vec::new();
     // convert to database words where applicable
@@ -48,10 +57,15 @@ pub fn segment_sentence(
         let mut api_interpretations = Vec::new();
         for interpretation in segment.interpretations.into_iter() {
             if let Some(word_id) = interpretation.word_id {
-                let (word, reading) = w::table
+                let (word, reading, pitch_accents, frequency_score) = w::table
                     .filter(w::id.eq(word_id))
-                    .select((w::word, w::reading_standard))
-                    .get_result::<(String, String)>(conn)?;
+                    .select((
+                        w::word,
+                        w::reading_standard,
+                        w::pitch_accents,
+                        w::frequency_score,
+                    ))
+                    .get_result::<(String, String, Vec<Option<i32>>, i32)>(conn)?;
                 api_interpretations.push(ApiInterpretation {
                     word_id: interpretation.word_id,
                     score: interpretation.score,
@@ -59,7 +73,15 @@ pub fn segment_sentence(
                     text_reading_hiragana: interpretation.reading_hiragana,
                     db_word: word,
                     db_reading_hiragana: reading,
+                    pitch_accents: pitch_accents
+                        .into_iter()
+                        .flatten()
+                        .map(|accent| accent as u32)
+                        .collect(),
+                    frequency_score,
                     meanings: interpretation.meanings,
+                    senses: word_senses.get(&word_id).cloned().unwrap_or_default(),
+                    inflections: interpretation.inflections,
                 });
             }
         }
@@ -75,24 +97,91 @@ pub fn segment_sentence(
     Ok(api_segmented_sentence)
 }
 
+/// The sentences to fetch words for.
+#[derive(Debug, Clone, Copy)]
+pub enum SentenceScope {
+    Sentence(i32),
+    Source(i32),
+}
+
+/// Returns the words in each of the sentences in the scope along with the user's status for them, by sentence id.
+/// Words that the user has saved in a sentence are being learned unless they have been ignored.
+pub fn get_sentence_words(
+    conn: &mut PgConnection,
+    user_id: i32,
+    scope: SentenceScope,
+) -> eyre::Result<HashMap<i32, Vec<res::SentenceWord>>> {
+    use crate::schema::{sentence_words as sw, sentences as s, words as w};
+
+    let query = sw::table
+        .inner_join(w::table.on(sw::word_id.eq(w::id.nullable())))
+        .inner_join(s::table.on(s::id.eq(sw::sentence_id)))
+        .select(SentenceWordQuery::as_select())
+        .order_by((sw::sentence_id, sw::idx_start))
+        .into_boxed();
+    let query = match scope {
+        SentenceScope::Sentence(sentence_id) => query.filter(s::id.eq(sentence_id)),
+        SentenceScope::Source(source_id) => query.filter(s::source_id.eq(source_id)),
+    };
+    let sentence_words = query.load(conn)?;
+
+    let word_statuses = queries::word_statuses(conn, user_id)?;
+    let mut words_by_sentence = HashMap::<i32, Vec<res::SentenceWord>>::new();
+    for sw in sentence_words {
+        let status = word_statuses
+            .get(&sw.word_id)
+            .copied()
+            .map(user_words::to_api_status)
+            .unwrap_or(res::WordStatus::Unknown);
+        words_by_sentence
+            .entry(sw.sentence_id)
+            .or_default()
+            .push(res::SentenceWord {
+                word_id: sw.word_id,
+                status,
+                word: sw.word,
+                reading: sw.reading,
+                sentence_word_reading: sw.sentence_word_reading,
+                idx_start: sw.idx_start,
+                idx_end: sw.idx_end,
+                furigana: sw
+                    .furigana
+                    .into_iter()
+                    .flatten()
+                    .map(|f| res::Furigana {
+                        word_start_idx: f.word_start_idx,
+                        word_end_idx: f.word_end_idx,
+                        reading_start_idx: f.reading_start_idx,
+                        reading_end_idx: f.reading_end_idx,
+                    })
+                    .collect(),
+                translations: sw.translations.into_iter().flatten().collect(),
+            });
+    }
+    Ok(words_by_sentence)
+}
+
+/// Converts the timestamps stored for a sentence into the API type.
+pub fn to_api_timestamps(start_ms: Option<i32>, end_ms: Option<i32>) -> Option<res::Timestamps> {
+    Some(res::Timestamps {
+        start_ms: start_ms?,
+        end_ms: end_ms?,
+    })
+}
+
 /// Processes a sentence into the appropriate response type.
 pub fn process_sentence(
     conn: &mut PgConnection,
-    ichiran_cli: &IchiranCli,
+    segmenter: &dyn Segmenter,
     sentence: String,
-    ichiran_word_to_id: &HashMap<(i32, String, String), i32>,
-    kanji_to_readings: &HashMap<String, Vec<String>>,
-    word_to_meanings: &HashMap<i32, Vec<String>>,
 ) -> eyre::Result<res::SegmentedParagraphSentence> {
-    let segments = segment_sentence(
-        conn,
-        ichiran_cli,
-        &sentence,
-        ichiran_word_to_id,
-        kanji_to_readings,
-        word_to_meanings,
-    )?;
-    Ok(res::SegmentedParagraphSentence { sentence, segments })
+    let segments = segment_sentence(conn, segmenter, &sentence)?;
+    Ok(res::SegmentedParagraphSentence {
+        sentence,
+        segments,
+        ruby: Vec::new(),
+        timestamps: None,
+    })
 }
 
 pub struct NewSentenceWords<'a> {
@@ -101,6 +190,8 @@ pub struct NewSentenceWords<'a> {
     pub sentence: &'a str,
     pub words: Vec<req::Word>,
     pub ignore_words: HashSet<i32>,
+    /// Readings given by the source text, used for the furigana of the words they cover.
+    pub ruby: Vec<Ruby>,
 }
 
 pub fn insert_sentence_words(
@@ -108,7 +199,7 @@ pub fn insert_sentence_words(
     kanji_to_readings: &HashMap<String, Vec<String>>,
     new_sentence_words: NewSentenceWords<'_>,
 ) -> eyre::Result<()> {
-    use crate::schema::{ignored_words as iw, sentence_words as sw};
+    use crate::schema::sentence_words as sw;
 
     let NewSentenceWords {
         user_id,
@@ -116,10 +207,19 @@ pub fn insert_sentence_words(
         sentence,
         words,
         ignore_words,
+        ruby,
     } = new_sentence_words;
 
+    if ruby
+        .iter()
+        .any(|r| r.range.start > r.range.end || r.range.end > sentence.len())
+    {
+        eyre::bail!("Request had invalid indexes for ruby");
+    }
+
     conn.transaction(move |conn| {
         let mut sentence_words = Vec::new();
+        let mut word_ids = Vec::new();
         for req::Word {
             id: word_id,
             reading,
@@ -130,15 +230,28 @@ pub fn insert_sentence_words(
             let word = sentence
                 .get(idx_start as usize..idx_end as usize)
                 .ok_or_else(|| eyre::eyre!("Request had invalid indexes for word"))?;
+            // the ruby within the word, relative to the word,
+            // the ruby's ranges have been validated so the subtraction can't underflow
+            let word_range = idx_start as usize..idx_end as usize;
+            let word_ruby = ruby
+                .iter()
+                .filter(|r| r.range.start >= word_range.start && r.range.end <= word_range.end)
+                .map(|r| Ruby {
+                    range: r.range.start - word_range.start..r.range.end - word_range.start,
+                    reading: r.reading.clone(),
+                })
+                .collect::<Vec<_>>();
             let furigana = reading
                 .as_ref()
                 .map(|reading| {
-                    japanese::map_to_db_furigana(word, reading, kanji_to_readings).wrap_err_with(
-                        || format!("Failed to map furigana to reading for {reading}"),
-                    )
+                    japanese::map_to_db_furigana(word, reading, &word_ruby, kanji_to_readings)
+                        .wrap_err_with(|| {
+                            format!("Failed to map furigana to reading for {reading}")
+                        })
                 })
                 .transpose()?
                 .unwrap_or_default();
+            word_ids.extend(word_id);
             sentence_words.push(eq!(
                 sw,
                 sentence_id,
@@ -155,23 +268,38 @@ pub fn insert_sentence_words(
                 .execute(conn)
                 .wrap_err("Failed to insert sentence word")?;
         }
+        // the sentence's words are being learned unless the user already has a status for them
+        user_words::insert_new_words(conn, user_id, word_ids, WordStatus::Learning)
+            .wrap_err("Failed to insert user words")?;
         let ignored_words = ignore_words
             .into_iter()
-            .map(|word_id| eq!(iw, word_id, user_id))
+            .map(|word_id| req::WordStatusUpdate {
+                word_id,
+                status: req::WordStatus::Ignored,
+                level: None,
+            })
             .collect::<Vec<_>>();
-        for chunk in ignored_words.pg_chunks() {
-            diesel::insert_into(iw::table)
-                .values(chunk)
-                .on_conflict((iw::word_id, iw::user_id))
-                .do_nothing()
-                .execute(conn)
-                .wrap_err("Failed to insert ignored words")?;
-        }
+        user_words::set_statuses(conn, user_id, &ignored_words)
+            .wrap_err("Failed to insert ignored words")?;
         EyreResult::Ok(())
     })?;
     Ok(())
 }
 
+query! {
+    struct SentenceWordQuery {
+        sentence_id: i32 = sentence_words::sentence_id,
+        word_id: i32 = words::id,
+        word: String = words::word,
+        reading: String = words::reading,
+        sentence_word_reading: Option<String> = sentence_words::reading,
+        idx_start: i32 = sentence_words::idx_start,
+        idx_end: i32 = sentence_words::idx_end,
+        furigana: Vec<Option<database::Furigana>> = sentence_words::furigana,
+        translations: Vec<Option<String>> = words::translations,
+    }
+}
+
 /*
 pub struct BetterSegmentationNode {
     pub contents: BetterSegment,
@@ -0,0 +1,188 @@
+//! Functions and types related to the user's knowledge of words.
+
+use crate::{
+    eq,
+    error::EyreResult,
+    queries, query,
+    utils::{database::WordStatus, diesel::PostgresChunks},
+};
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, upsert::excluded};
+use lbr_api::{request as req, response as res};
+use std::collections::{HashMap, HashSet};
+
+/// The highest learning level, after which a word is usually considered known.
+pub const MAX_LEVEL: i32 = 5;
+
+/// Returns the user's statuses for the given words.
+/// Words without a status are not included.
+pub fn get_statuses(
+    conn: &mut PgConnection,
+    user_id: i32,
+    word_ids: &HashSet<i32>,
+) -> eyre::Result<HashMap<i32, res::WordStatus>> {
+    let statuses = queries::word_statuses(conn, user_id)?
+        .into_iter()
+        .filter(|(word_id, _)| word_ids.contains(word_id))
+        .map(|(word_id, status)| (word_id, to_api_status(status)))
+        .collect();
+    Ok(statuses)
+}
+
+/// Returns all of the user's words that have a status.
+pub fn get_user_words(conn: &mut PgConnection, user_id: i32) -> eyre::Result<Vec<res::UserWord>> {
+    use crate::schema::user_words as uw;
+
+    let user_words = uw::table
+        .filter(uw::user_id.eq(user_id))
+        .select(UserWord::as_select())
+        .order_by(uw::word_id)
+        .load(conn)?
+        .into_iter()
+        .map(|uw| res::UserWord {
+            word_id: uw.word_id,
+            status: to_api_status(uw.status),
+            level: uw.level,
+            first_seen: uw.first_seen,
+            last_changed: uw.last_changed,
+        })
+        .collect();
+    Ok(user_words)
+}
+
+/// Sets the user's statuses for the words.
+/// Learning words without a level start at level 1, other words keep their current level if none is given.
+pub fn set_statuses(
+    conn: &mut PgConnection,
+    user_id: i32,
+    updates: &[req::WordStatusUpdate],
+) -> eyre::Result<()> {
+    use crate::schema::user_words as uw;
+
+    if let Some(update) = updates
+        .iter()
+        .find(|u| u.level.is_some_and(|l| !(1..=MAX_LEVEL).contains(&l)))
+    {
+        eyre::bail!(
+            "Invalid level {:?} for word {}, expected a level from 1 to {MAX_LEVEL}",
+            update.level,
+            update.word_id
+        );
+    }
+
+    conn.transaction(|conn| {
+        let (with_level, without_level): (Vec<&req::WordStatusUpdate>, Vec<_>) =
+            updates.iter().partition(|u| u.level.is_some());
+
+        let with_level = with_level
+            .into_iter()
+            .map(|u| {
+                let word_id = u.word_id;
+                let status = from_api_status(u.status);
+                let level = u.level.unwrap_or_default();
+                eq!(uw, user_id, word_id, status, level)
+            })
+            .collect::<Vec<_>>();
+        for chunk in with_level.pg_chunks() {
+            diesel::insert_into(uw::table)
+                .values(chunk)
+                .on_conflict((uw::user_id, uw::word_id))
+                .do_update()
+                .set((
+                    uw::status.eq(excluded(uw::status)),
+                    uw::level.eq(excluded(uw::level)),
+                    uw::last_changed.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+        }
+
+        let without_level = without_level
+            .into_iter()
+            .map(|u| {
+                let word_id = u.word_id;
+                let status = from_api_status(u.status);
+                eq!(uw, user_id, word_id, status)
+            })
+            .collect::<Vec<_>>();
+        for chunk in without_level.pg_chunks() {
+            diesel::insert_into(uw::table)
+                .values(chunk)
+                .on_conflict((uw::user_id, uw::word_id))
+                .do_update()
+                .set((
+                    uw::status.eq(excluded(uw::status)),
+                    uw::last_changed.eq(diesel::dsl::now),
+                ))
+                .execute(conn)?;
+        }
+
+        // updated learning words start at the first level
+        let word_ids = updates.iter().map(|u| u.word_id).collect::<Vec<_>>();
+        diesel::update(
+            uw::table.filter(
+                uw::user_id
+                    .eq(user_id)
+                    .and(uw::word_id.eq_any(word_ids))
+                    .and(uw::status.eq(WordStatus::Learning))
+                    .and(uw::level.eq(0)),
+            ),
+        )
+        .set(uw::level.eq(1))
+        .execute(conn)?;
+
+        EyreResult::Ok(())
+    })?;
+    Ok(())
+}
+
+/// Gives the words the status if the user has not seen them before.
+pub fn insert_new_words(
+    conn: &mut PgConnection,
+    user_id: i32,
+    word_ids: impl IntoIterator<Item = i32>,
+    status: WordStatus,
+) -> eyre::Result<()> {
+    use crate::schema::user_words as uw;
+
+    let level = if status == WordStatus::Learning { 1 } else { 0 };
+    let user_words = word_ids
+        .into_iter()
+        .map(|word_id| eq!(uw, user_id, word_id, status, level))
+        .collect::<Vec<_>>();
+    for chunk in user_words.pg_chunks() {
+        diesel::insert_into(uw::table)
+            .values(chunk)
+            .on_conflict((uw::user_id, uw::word_id))
+            .do_nothing()
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+pub fn to_api_status(status: WordStatus) -> res::WordStatus {
+    match status {
+        WordStatus::Unknown => res::WordStatus::Unknown,
+        WordStatus::Learning => res::WordStatus::Learning,
+        WordStatus::Known => res::WordStatus::Known,
+        WordStatus::Ignored => res::WordStatus::Ignored,
+    }
+}
+
+pub fn from_api_status(status: res::WordStatus) -> WordStatus {
+    match status {
+        res::WordStatus::Unknown => WordStatus::Unknown,
+        res::WordStatus::Learning => WordStatus::Learning,
+        res::WordStatus::Known => WordStatus::Known,
+        res::WordStatus::Ignored => WordStatus::Ignored,
+    }
+}
+
+query! {
+    struct UserWord {
+        word_id: i32 = user_words::word_id,
+        status: WordStatus = user_words::status,
+        level: i32 = user_words::level,
+        first_seen: DateTime<Utc> = user_words::first_seen,
+        last_changed: DateTime<Utc> = user_words::last_changed,
+    }
+}
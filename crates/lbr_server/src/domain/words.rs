@@ -1,6 +1,9 @@
 //! Functions and types related to ichiran.
 
+use crate::query;
 use diesel::prelude::*;
+use lbr::segmenter::DictionaryEntry;
+use lbr_core::{word_definitions::WordDefinitions, word_sense::WordSense};
 use std::collections::HashMap;
 
 /// Returns a mapping from word ids to their meanings.
@@ -22,3 +25,100 @@ pub fn get_word_to_meanings(conn: &mut PgConnection) -> eyre::Result<HashMap<i32
         });
     Ok(word_to_meanings)
 }
+
+/// Returns all words as entries for the dictionary segmenter.
+pub fn get_dictionary_entries(conn: &mut PgConnection) -> eyre::Result<Vec<DictionaryEntry>> {
+    use crate::schema::words as w;
+
+    tracing::info!("Building the dictionary for the dictionary segmenter");
+
+    let entries = w::table
+        .select((w::id, w::word, w::reading, w::translations))
+        .get_results::<(i32, String, String, Vec<Option<String>>)>(conn)?
+        .into_iter()
+        .map(|(word_id, word, reading, translations)| DictionaryEntry {
+            word_id,
+            word,
+            reading_hiragana: lbr::standardise_reading(&reading).hiragana,
+            meanings: translations.into_iter().flatten().collect(),
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// Returns the senses of the given words in JMdict order, by word id.
+pub fn get_word_senses(
+    conn: &mut PgConnection,
+    word_ids: &[i32],
+) -> eyre::Result<HashMap<i32, Vec<WordSense>>> {
+    use crate::schema::word_senses as ws;
+
+    let mut word_senses = HashMap::<i32, Vec<WordSense>>::new();
+    ws::table
+        .filter(ws::word_id.eq_any(word_ids))
+        .order_by((ws::word_id, ws::idx))
+        .select(WordSenseQuery::as_select())
+        .get_results::<WordSenseQuery>(conn)?
+        .into_iter()
+        .for_each(|sense| {
+            let flat = |values: Vec<Option<String>>| values.into_iter().flatten().collect();
+            word_senses
+                .entry(sense.word_id)
+                .or_default()
+                .push(WordSense {
+                    glosses: flat(sense.glosses),
+                    parts_of_speech: flat(sense.parts_of_speech),
+                    misc: flat(sense.misc),
+                    fields: flat(sense.fields),
+                    dialects: flat(sense.dialects),
+                    info: flat(sense.info),
+                    cross_references: flat(sense.cross_references),
+                    antonyms: flat(sense.antonyms),
+                });
+        });
+    Ok(word_senses)
+}
+
+/// Returns the definitions of the given words in the dictionaries the user has chosen to show, by word id.
+/// The dictionaries are ordered by name.
+pub fn get_word_definitions(
+    conn: &mut PgConnection,
+    user_id: i32,
+    word_ids: &[i32],
+) -> eyre::Result<HashMap<i32, Vec<WordDefinitions>>> {
+    use crate::schema::{dictionaries as di, user_dictionaries as ud, word_definitions as wd};
+
+    let mut word_definitions = HashMap::<i32, Vec<WordDefinitions>>::new();
+    wd::table
+        .inner_join(di::table)
+        .inner_join(ud::table.on(ud::dictionary_id.eq(wd::dictionary_id)))
+        .filter(ud::user_id.eq(user_id).and(wd::word_id.eq_any(word_ids)))
+        .order_by((wd::word_id, di::name))
+        .select((wd::word_id, di::name, wd::definitions))
+        .get_results::<(i32, String, Vec<Option<String>>)>(conn)?
+        .into_iter()
+        .for_each(|(word_id, dictionary, definitions)| {
+            word_definitions
+                .entry(word_id)
+                .or_default()
+                .push(WordDefinitions {
+                    dictionary,
+                    definitions: definitions.into_iter().flatten().collect(),
+                });
+        });
+    Ok(word_definitions)
+}
+
+query! {
+    struct WordSenseQuery {
+        word_id: i32 = word_senses::word_id,
+        glosses: Vec<Option<String>> = word_senses::glosses,
+        parts_of_speech: Vec<Option<String>> = word_senses::parts_of_speech,
+        misc: Vec<Option<String>> = word_senses::misc,
+        fields: Vec<Option<String>> = word_senses::fields,
+        dialects: Vec<Option<String>> = word_senses::dialects,
+        info: Vec<Option<String>> = word_senses::info,
+        cross_references: Vec<Option<String>> = word_senses::cross_references,
+        antonyms: Vec<Option<String>> = word_senses::antonyms,
+    }
+}
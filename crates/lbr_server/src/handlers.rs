@@ -2,6 +2,8 @@
 
 pub mod auth;
 pub mod decks;
+pub mod kanji;
+pub mod reviews;
 pub mod segment;
 pub mod sentences;
 pub mod sources;
@@ -1,7 +1,11 @@
 //! /decks
 
 use super::prelude::*;
-use crate::{domain::decks, utils::database::DeckSourceKind};
+use crate::{
+    domain::decks,
+    utils::database::{CardKind, DeckSourceKind},
+};
+use lbr::anki;
 use std::io::Cursor;
 
 // handlers
@@ -41,7 +45,7 @@ pub async fn get_one(
     use schema::{deck_sources as ds, decks as d};
 
     let user_id = user.user_id;
-    let (deck, sources) = tokio::task::spawn_blocking(move || {
+    let (deck, sources, rules, card_types) = tokio::task::spawn_blocking(move || {
         let mut conn = state.lbr_pool.get()?;
         let deck = d::table
             .select(Deck::as_select())
@@ -51,7 +55,9 @@ pub async fn get_one(
             .filter(ds::deck_id.eq(id))
             .select(DeckSource::as_select())
             .load(&mut conn)?;
-        EyreResult::Ok((deck, sources))
+        let rules = decks::get_rules(&mut conn, id)?;
+        let card_types = decks::get_word_card_types(&mut conn, id)?;
+        EyreResult::Ok((deck, sources, rules, card_types))
     })
     .await??;
 
@@ -70,6 +76,13 @@ pub async fn get_one(
         id: deck.id,
         name: deck.name,
         sources,
+        exclude_known: deck.exclude_known,
+        rules: rules.into(),
+        card_types: res::WordCardTypes {
+            reading: card_types.reading,
+            production: card_types.production,
+            cloze: card_types.cloze,
+        },
     };
     Ok(Json(deck))
 }
@@ -111,7 +124,29 @@ pub async fn update(
     let req::UpdateDeck {
         name,
         included_sources,
+        exclude_known,
+        rules,
+        card_types,
     } = update_deck;
+    let req::WordCardTypes {
+        reading: reading_cards,
+        production: production_cards,
+        cloze: cloze_cards,
+    } = card_types;
+    let decks::DeckRules {
+        order,
+        max_word_cards,
+        max_kanji_cards,
+        min_word_frequency,
+        max_word_frequency,
+        max_kanji_grade,
+        min_kanji_jlpt,
+        extra_examples,
+        example_preference,
+        min_word_frequency_score,
+        frequency_list_id,
+        max_word_frequency_rank,
+    } = rules.into();
     tokio::task::spawn_blocking(move || {
         let mut conn = state.lbr_pool.get()?;
         let decks = d::table
@@ -124,7 +159,25 @@ pub async fn update(
 
         conn.transaction(|conn| {
             diesel::update(d::table.filter(d::id.eq(id).and(d::user_id.eq(user_id))))
-                .set(eq!(d, name))
+                .set((
+                    eq!(d, name, exclude_known),
+                    d::card_order.eq(order),
+                    eq!(
+                        d,
+                        max_word_cards,
+                        max_kanji_cards,
+                        min_word_frequency,
+                        max_word_frequency,
+                        max_kanji_grade,
+                        min_kanji_jlpt,
+                        extra_examples,
+                        example_preference,
+                        min_word_frequency_score,
+                        frequency_list_id,
+                        max_word_frequency_rank
+                    ),
+                    eq!(d, reading_cards, production_cards, cloze_cards),
+                ))
                 .execute(conn)?;
             diesel::delete(ds::table.filter(ds::deck_id.eq(id))).execute(conn)?;
             let values = included_sources
@@ -161,13 +214,15 @@ pub async fn delete(
     Path(deck_id): Path<i32>,
     user: Authentication,
 ) -> LbrResult<()> {
-    use schema::{deck_sources as ds, decks as d};
+    use schema::{deck_cards as dc, deck_note_types as dnt, deck_sources as ds, decks as d};
 
     let user_id = user.user_id;
     tokio::task::spawn_blocking(move || {
         let mut conn = state.lbr_pool.get()?;
         conn.transaction(|conn| {
             diesel::delete(ds::table.filter(eq!(ds, deck_id))).execute(conn)?;
+            diesel::delete(dc::table.filter(eq!(dc, deck_id))).execute(conn)?;
+            diesel::delete(dnt::table.filter(eq!(dnt, deck_id))).execute(conn)?;
             let id = deck_id;
             let decks_deleted =
                 diesel::delete(d::table.filter(d::id.eq(id).and(d::user_id.eq(user_id))))
@@ -217,12 +272,174 @@ pub async fn generate(
     Ok(deck_data.into_inner())
 }
 
+#[instrument]
+pub async fn preview(
+    State(state): State<LbrState>,
+    Path(id): Path<i32>,
+    user: Authentication,
+) -> LbrResult<Json<res::DeckPreview>> {
+    use schema::decks as d;
+
+    let user_id = user.user_id;
+    let preview = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let decks = d::table
+            .filter(d::id.eq(id).and(d::user_id.eq(user_id)))
+            .select(d::id)
+            .execute(&mut conn)?;
+        if decks != 1 {
+            return Err(eyre::eyre!("No such deck"));
+        }
+
+        let preview = decks::preview_deck(&mut conn, user_id, id)?;
+        EyreResult::Ok(preview)
+    })
+    .await??;
+
+    Ok(Json(preview))
+}
+
+#[instrument]
+pub async fn get_note_types(
+    State(state): State<LbrState>,
+    Path(id): Path<i32>,
+    user: Authentication,
+) -> LbrResult<Json<res::DeckNoteTypes>> {
+    use schema::decks as d;
+
+    let user_id = user.user_id;
+    let note_types = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let decks = d::table
+            .filter(d::id.eq(id).and(d::user_id.eq(user_id)))
+            .select(d::id)
+            .execute(&mut conn)?;
+        if decks != 1 {
+            return Err(eyre::eyre!("No such deck"));
+        }
+
+        let note_types = decks::get_note_types(&mut conn, id)?;
+        EyreResult::Ok(note_types)
+    })
+    .await??;
+
+    let deck_note_type = |custom: bool, note_type: anki::NoteType, fields: &[&str]| {
+        res::DeckNoteType {
+            note_type: to_api_note_type(note_type),
+            custom,
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+        }
+    };
+    let note_types = res::DeckNoteTypes {
+        word: deck_note_type(
+            note_types.word.is_some(),
+            note_types.word(),
+            &anki::WordFields::NAMES,
+        ),
+        kanji: deck_note_type(
+            note_types.kanji.is_some(),
+            note_types.kanji(),
+            &anki::KanjiFields::NAMES,
+        ),
+    };
+    Ok(Json(note_types))
+}
+
+#[instrument]
+pub async fn update_note_type(
+    State(state): State<LbrState>,
+    Path(id): Path<i32>,
+    user: Authentication,
+    Json(update): Json<req::UpdateNoteType>,
+) -> LbrResult<()> {
+    use schema::decks as d;
+
+    let user_id = user.user_id;
+    let req::UpdateNoteType { kind, note_type } = update;
+    let kind = match kind {
+        req::CardKind::Word => CardKind::Word,
+        req::CardKind::Kanji => CardKind::Kanji,
+    };
+    let note_type = note_type.map(from_api_note_type);
+    tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let decks = d::table
+            .filter(d::id.eq(id).and(d::user_id.eq(user_id)))
+            .select(d::id)
+            .execute(&mut conn)?;
+        if decks != 1 {
+            return Err(eyre::eyre!("No such deck"));
+        }
+
+        decks::set_note_type(&mut conn, id, kind, note_type)?;
+        EyreResult::Ok(())
+    })
+    .await??;
+
+    Ok(())
+}
+
+#[instrument]
+pub async fn reroll(
+    State(state): State<LbrState>,
+    Path(deck_id): Path<i32>,
+    user: Authentication,
+    Json(reroll): Json<req::Reroll>,
+) -> LbrResult<()> {
+    let req::Reroll { kind, card_id } = reroll;
+    let kind = match kind {
+        req::CardKind::Word => CardKind::Word,
+        req::CardKind::Kanji => CardKind::Kanji,
+    };
+    tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        decks::reroll_example(&mut conn, user.user_id, deck_id, kind, card_id)?;
+        EyreResult::Ok(())
+    })
+    .await??;
+
+    Ok(())
+}
+
+// conversions
+
+fn to_api_note_type(note_type: anki::NoteType) -> res::NoteType {
+    res::NoteType {
+        css: note_type.css,
+        templates: note_type
+            .templates
+            .into_iter()
+            .map(|t| res::CardTemplate {
+                name: t.name,
+                question: t.question,
+                answer: t.answer,
+            })
+            .collect(),
+    }
+}
+
+fn from_api_note_type(note_type: req::NoteType) -> anki::NoteType {
+    anki::NoteType {
+        css: note_type.css,
+        templates: note_type
+            .templates
+            .into_iter()
+            .map(|t| anki::CardTemplate {
+                name: t.name,
+                question: t.question,
+                answer: t.answer,
+            })
+            .collect(),
+    }
+}
+
 // queries
 
 query! {
     struct Deck {
         pub id: i32 = decks::id,
         pub name: String = decks::name,
+        pub exclude_known: bool = decks::exclude_known,
     }
 }
 
@@ -0,0 +1,78 @@
+//! /kanji
+
+use super::prelude::*;
+
+// handlers
+
+#[instrument]
+pub async fn get_one(
+    State(state): State<LbrState>,
+    Path(kanji): Path<String>,
+    _user: Authentication,
+) -> LbrResult<Json<res::Kanji>> {
+    use schema::{kanji as k, kanji_readings as kr};
+
+    let kanji = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+
+        let kanji = k::table
+            .filter(k::chara.eq(&kanji))
+            .select(Kanji::as_select())
+            .get_result(&mut conn)
+            .wrap_err_with(|| format!("Failed to find kanji {kanji}"))?;
+        let readings = kr::table
+            .filter(kr::kanji_id.eq(kanji.id))
+            .select(KanjiReading::as_select())
+            .order_by(kr::id)
+            .get_results(&mut conn)?
+            .into_iter()
+            .map(|kr| res::KanjiReading {
+                reading: kr.reading,
+                okurigana: kr.okurigana,
+                nanori: kr.nanori,
+            })
+            .collect();
+        let kanji = res::Kanji {
+            id: kanji.id,
+            kanji: kanji.chara,
+            name: kanji.name,
+            meanings: kanji.meanings.into_iter().flatten().collect(),
+            components: kanji.components.into_iter().flatten().collect(),
+            readings,
+            grade: kanji.grade,
+            jlpt: kanji.jlpt,
+            stroke_count: kanji.stroke_count,
+            frequency: kanji.frequency,
+            radical: kanji.radical,
+        };
+        EyreResult::Ok(kanji)
+    })
+    .await??;
+
+    Ok(Json(kanji))
+}
+
+// queries
+
+query! {
+    struct Kanji {
+        id: i32 = kanji::id,
+        chara: String = kanji::chara,
+        name: Option<String> = kanji::name,
+        meanings: Vec<Option<String>> = kanji::meanings,
+        components: Vec<Option<String>> = kanji::components,
+        grade: Option<i32> = kanji::grade,
+        jlpt: Option<i32> = kanji::jlpt,
+        stroke_count: Option<i32> = kanji::stroke_count,
+        frequency: Option<i32> = kanji::frequency,
+        radical: Option<i32> = kanji::radical,
+    }
+}
+
+query! {
+    struct KanjiReading {
+        reading: String = kanji_readings::reading,
+        okurigana: Option<String> = kanji_readings::okurigana,
+        nanori: bool = kanji_readings::nanori,
+    }
+}
@@ -0,0 +1,60 @@
+//! /reviews
+
+use super::prelude::*;
+use crate::domain::reviews;
+use axum::body::Bytes;
+
+// handlers
+
+#[instrument]
+pub async fn get_due(
+    State(state): State<LbrState>,
+    Path(deck_id): Path<i32>,
+    user: Authentication,
+) -> LbrResult<Json<Vec<res::ReviewCard>>> {
+    let cards = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let cards = reviews::get_due_cards(&mut conn, user.user_id, deck_id, chrono::Utc::now())?;
+        EyreResult::Ok(cards)
+    })
+    .await??;
+
+    Ok(Json(cards))
+}
+
+#[instrument]
+pub async fn insert(
+    State(state): State<LbrState>,
+    user: Authentication,
+    Json(review): Json<req::Review>,
+) -> LbrResult<()> {
+    tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        reviews::review(&mut conn, user.user_id, review, chrono::Utc::now())?;
+        EyreResult::Ok(())
+    })
+    .await??;
+
+    Ok(())
+}
+
+#[instrument(skip(data))]
+pub async fn import_history(
+    State(state): State<LbrState>,
+    user: Authentication,
+    data: Bytes,
+) -> LbrResult<Json<res::ReviewHistoryImport>> {
+    let import = tokio::task::spawn_blocking(move || {
+        let histories = lbr::anki::history::read_history(&data)
+            .wrap_err("Failed to read review history from Anki")?;
+        tracing::info!("Importing the history of {} cards", histories.len());
+
+        let mut conn = state.lbr_pool.get()?;
+        let import =
+            reviews::import_history(&mut conn, user.user_id, histories, chrono::Utc::now())?;
+        EyreResult::Ok(import)
+    })
+    .await??;
+
+    Ok(Json(import))
+}
@@ -1,76 +1,65 @@
 //! /segment
 
 use super::prelude::*;
-use crate::{domain::sentences, queries};
-use lbr::sentence_splitter::SentenceSplitter;
-use std::collections::HashSet;
+use crate::domain::segmentation_jobs;
 
 #[instrument]
-pub async fn segment(
+pub async fn create_job(
     State(state): State<LbrState>,
     user: Authentication,
     paragraph: Json<req::Paragraph<'static>>,
-) -> LbrResult<Json<res::SegmentedParagraph>> {
-    use schema::{sentences as se, sources as so};
+) -> LbrResult<String> {
     let user_id = user.user_id;
     let req::Paragraph {
         source_id,
         paragraph,
     } = paragraph.0;
 
-    let segmented_paragraph = tokio::task::spawn_blocking(move || {
+    let job_id = tokio::task::spawn_blocking({
+        let state = state.clone();
+        move || {
+            let mut conn = state.lbr_pool.get()?;
+            let job_id =
+                segmentation_jobs::create_job(&mut conn, user_id, source_id, None, &paragraph)?;
+            EyreResult::Ok(job_id)
+        }
+    })
+    .await??;
+    segmentation_jobs::spawn_job(state, job_id);
+
+    Ok(job_id.to_string())
+}
+
+#[instrument]
+pub async fn get_job(
+    State(state): State<LbrState>,
+    Path(id): Path<i32>,
+    user: Authentication,
+) -> LbrResult<Json<res::SegmentationJob>> {
+    let user_id = user.user_id;
+    let job = tokio::task::spawn_blocking(move || {
         let mut conn = state.lbr_pool.get()?;
-        let source_id = so::table
-            .filter(so::id.eq(source_id).and(so::user_id.eq(user_id)))
-            .select(so::id)
-            .get_result::<i32>(&mut conn)?;
+        let job = segmentation_jobs::get_job(&mut conn, user_id, id)?;
+        EyreResult::Ok(job)
+    })
+    .await??;
 
-        let ignored_word_ids = queries::ignored_words(&mut conn, user_id)?;
+    Ok(Json(job))
+}
 
-        let segmented_paragraph = std::thread::scope(|scope| {
-            let mut segmented_sentences = Vec::new();
-            let mut handles = Vec::new();
-            for sentence in SentenceSplitter::new(&paragraph) {
-                let existing_sentences = se::table
-                    .select(se::id)
-                    .filter(eq!(se, sentence).and(eq!(se, source_id)))
-                    .execute(&mut conn)?;
-                if existing_sentences != 0 {
-                    tracing::info!("Skipping existing sentence {sentence}");
-                    continue;
-                }
-                let segmented_sentence = scope.spawn(|| {
-                    let mut conn = state.lbr_pool.get()?;
-                    sentences::process_sentence(
-                        &mut conn,
-                        &state.ichiran_cli,
-                        sentence.to_string(),
-                        &state.ichiran_word_to_id,
-                        &state.kanji_to_readings,
-                        &state.word_to_meanings,
-                    )
-                });
-                handles.push(segmented_sentence);
-            }
-            let mut word_ids = HashSet::new();
-            for handle in handles {
-                let segmented_sentence = handle.join().expect("Failed to join thread handle")?;
-                for segment in &segmented_sentence.segments {
-                    for interpretation in &segment.interpretations {
-                        if let Some(word_id) = interpretation.word_id {
-                            word_ids.insert(word_id);
-                        }
-                    }
-                }
-                segmented_sentences.push(segmented_sentence)
-            }
-            EyreResult::Ok(res::SegmentedParagraph {
-                sentences: segmented_sentences,
-                ignored_words: word_ids.intersection(&ignored_word_ids).copied().collect(),
-            })
-        })?;
-        EyreResult::Ok(segmented_paragraph)
+#[instrument]
+pub async fn get_job_results(
+    State(state): State<LbrState>,
+    Path(id): Path<i32>,
+    user: Authentication,
+) -> LbrResult<Json<res::SegmentedParagraph>> {
+    let user_id = user.user_id;
+    let results = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let results = segmentation_jobs::get_job_results(&mut conn, user_id, id)?;
+        EyreResult::Ok(results)
     })
     .await??;
-    Ok(Json(segmented_paragraph))
+
+    Ok(Json(results))
 }
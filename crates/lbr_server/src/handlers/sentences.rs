@@ -1,10 +1,9 @@
 //! /sentences
 
 use super::prelude::*;
-use crate::{
-    domain::sentences::{self, NewSentenceWords},
-    queries,
-    utils::database,
+use crate::domain::{
+    sentences::{self, NewSentenceWords, SentenceScope},
+    user_words, words,
 };
 use lbr_api::response::SegmentedSentence;
 use std::collections::HashSet;
@@ -17,7 +16,7 @@ pub async fn get_one(
     Path(id): Path<i32>,
     user: Authentication,
 ) -> LbrResult<Json<res::SentenceDetails>> {
-    use schema::{sentence_words as sw, sentences as s, sources as so, words as w};
+    use schema::{sentences as s, sources as so};
 
     let sentence = tokio::task::spawn_blocking(move || {
         let mut conn = state.lbr_pool.get()?;
@@ -27,38 +26,19 @@ pub async fn get_one(
             .filter(so::user_id.eq(user.user_id).and(s::id.eq(id)))
             .select(Sentence::as_select())
             .get_result(&mut conn)?;
-        let words = sw::table
-            .inner_join(w::table.on(sw::word_id.eq(w::id.nullable())))
-            .filter(sw::sentence_id.eq(id))
-            .select(SentenceWord::as_select())
-            .load(&mut conn)?;
-        let words = words
-            .into_iter()
-            .map(|sw| res::SentenceWord {
-                word: sw.word,
-                reading: sw.reading,
-                sentence_word_reading: sw.sentence_word_reading,
-                idx_start: sw.idx_start,
-                idx_end: sw.idx_end,
-                furigana: sw
-                    .furigana
-                    .into_iter()
-                    .flatten()
-                    .map(|f| res::Furigana {
-                        word_start_idx: f.word_start_idx,
-                        word_end_idx: f.word_end_idx,
-                        reading_start_idx: f.reading_start_idx,
-                        reading_end_idx: f.reading_end_idx,
-                    })
-                    .collect(),
-                translations: sw.translations.into_iter().flatten().collect(),
-            })
-            .collect();
+        let words = sentences::get_sentence_words(
+            &mut conn,
+            user.user_id,
+            SentenceScope::Sentence(sentence.id),
+        )?
+        .remove(&sentence.id)
+        .unwrap_or_default();
         let sentence = res::SentenceDetails {
             id: sentence.id,
             source_id: sentence.source_id,
             sentence: sentence.sentence,
             words,
+            timestamps: sentences::to_api_timestamps(sentence.start_ms, sentence.end_ms),
         };
         EyreResult::Ok(sentence)
     })
@@ -80,6 +60,9 @@ pub async fn update(
         sentence,
         words,
         ignore_words,
+        ruby,
+        // the timestamps of an existing sentence are kept as is
+        timestamps: _,
     } = update_sentence.0;
     tokio::task::spawn_blocking(move || {
         let mut conn = state.lbr_pool.get()?;
@@ -105,6 +88,7 @@ pub async fn update(
                     sentence,
                     words,
                     ignore_words,
+                    ruby,
                 },
             )?;
             EyreResult::Ok(())
@@ -157,14 +141,8 @@ pub async fn segment(
             .filter(s::id.eq(id))
             .select(s::sentence)
             .get_result::<String>(&mut conn)?;
-        let segmented_sentence = sentences::process_sentence(
-            &mut conn,
-            &state.ichiran_cli,
-            sentence,
-            &state.ichiran_word_to_id,
-            &state.kanji_to_readings,
-            &state.word_to_meanings,
-        )?;
+        let segmented_sentence =
+            sentences::process_sentence(&mut conn, &state.segmenter, sentence)?;
         let mut word_ids = HashSet::new();
         for segment in &segmented_sentence.segments {
             for interpretation in &segment.interpretations {
@@ -173,11 +151,14 @@ pub async fn segment(
                 }
             }
         }
-        let ignored_word_ids = queries::ignored_words(&mut conn, user.user_id)?;
+        let word_statuses = user_words::get_statuses(&mut conn, user.user_id, &word_ids)?;
+        let word_ids = word_ids.into_iter().collect::<Vec<_>>();
+        let word_definitions = words::get_word_definitions(&mut conn, user.user_id, &word_ids)?;
         EyreResult::Ok(SegmentedSentence {
             sentence: segmented_sentence.sentence,
             segments: segmented_sentence.segments,
-            ignored_words: word_ids.intersection(&ignored_word_ids).copied().collect(),
+            word_statuses,
+            word_definitions,
         })
     })
     .await??;
@@ -192,17 +173,7 @@ query! {
         id: i32 = sentences::id,
         source_id: i32 = sentences::source_id,
         sentence: String = sentences::sentence,
-    }
-}
-
-query! {
-    struct SentenceWord {
-        word: String = words::word,
-        reading: String = words::reading,
-        sentence_word_reading: Option<String> = sentence_words::reading,
-        idx_start: i32 = sentence_words::idx_start,
-        idx_end: i32 = sentence_words::idx_end,
-        furigana: Vec<Option<database::Furigana>> = sentence_words::furigana,
-        translations: Vec<Option<String>> = words::translations,
+        start_ms: Option<i32> = sentences::start_ms,
+        end_ms: Option<i32> = sentences::end_ms,
     }
 }
@@ -1,7 +1,12 @@
 //! /sources
 
 use super::prelude::*;
-use crate::domain::sentences::{self, NewSentenceWords};
+use crate::domain::{
+    segmentation_jobs,
+    sentences::{self, NewSentenceWords, SentenceScope},
+};
+use axum::{body::Bytes, extract::Query};
+use lbr::import::subtitles::SubtitleFormat;
 
 // handlers
 
@@ -87,11 +92,57 @@ pub async fn get_details(
             .map(|s| res::Sentence {
                 id: s.id,
                 sentence: s.sentence,
+                timestamps: sentences::to_api_timestamps(s.start_ms, s.end_ms),
             })
             .collect(),
     }))
 }
 
+#[instrument]
+pub async fn get_reader(
+    State(state): State<LbrState>,
+    Path(id): Path<i32>,
+    user: Authentication,
+) -> LbrResult<Json<res::SourceReader>> {
+    use schema::{sentences as se, sources as so};
+
+    let user_id = user.user_id;
+    let reader = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let source = so::table
+            .select(Source::as_select())
+            .filter(so::id.eq(id).and(so::user_id.eq(user_id)))
+            .get_result(&mut conn)?;
+        let source_id = source.id;
+        let source_sentences = se::table
+            .select(Sentence::as_select())
+            .filter(eq!(se, source_id))
+            .order_by(se::id)
+            .get_results(&mut conn)?;
+        let mut words_by_sentence =
+            sentences::get_sentence_words(&mut conn, user_id, SentenceScope::Source(source_id))?;
+
+        let sentences = source_sentences
+            .into_iter()
+            .map(|s| res::SentenceDetails {
+                id: s.id,
+                source_id,
+                words: words_by_sentence.remove(&s.id).unwrap_or_default(),
+                sentence: s.sentence,
+                timestamps: sentences::to_api_timestamps(s.start_ms, s.end_ms),
+            })
+            .collect();
+        EyreResult::Ok(res::SourceReader {
+            id: source.id,
+            name: source.name,
+            sentences,
+        })
+    })
+    .await??;
+
+    Ok(Json(reader))
+}
+
 #[instrument]
 pub async fn insert(
     State(state): State<LbrState>,
@@ -150,6 +201,7 @@ pub async fn delete(
     tokio::task::spawn_blocking(move || {
         let mut conn = state.lbr_pool.get()?;
         conn.transaction(move |conn| {
+            segmentation_jobs::delete_source_jobs(conn, id)?;
             diesel::delete(ds::table.filter(ds::source_id.eq(id))).execute(conn)?;
             let sentence_ids = se::table
                 .select(se::id)
@@ -168,6 +220,93 @@ pub async fn delete(
     Ok(())
 }
 
+#[instrument]
+pub async fn get_jobs(
+    State(state): State<LbrState>,
+    Path(id): Path<i32>,
+    user: Authentication,
+) -> LbrResult<Json<Vec<res::SegmentationJobSummary>>> {
+    let user_id = user.user_id;
+    let jobs = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let jobs = segmentation_jobs::get_source_jobs(&mut conn, user_id, id)?;
+        EyreResult::Ok(jobs)
+    })
+    .await??;
+
+    Ok(Json(jobs))
+}
+
+#[instrument(skip(data))]
+pub async fn import(
+    State(state): State<LbrState>,
+    Path(id): Path<i32>,
+    user: Authentication,
+    Query(import): Query<req::Import<'static>>,
+    data: Bytes,
+) -> LbrResult<Json<Vec<i32>>> {
+    let user_id = user.user_id;
+    let filename = import.filename;
+    let job_ids = tokio::task::spawn_blocking({
+        let state = state.clone();
+        move || {
+            if let Some(format) = SubtitleFormat::detect(&filename) {
+                let parts = lbr::import::subtitles::import(format, &data);
+                if parts.is_empty() {
+                    return Err(eyre::eyre!("No subtitles found in {filename}"));
+                }
+                tracing::info!("Importing {} parts from {filename}", parts.len());
+
+                let mut conn = state.lbr_pool.get()?;
+                let job_ids = conn.transaction(|conn| {
+                    let mut job_ids = Vec::new();
+                    for (idx, part) in parts.iter().enumerate() {
+                        let title = (parts.len() > 1).then(|| format!("({})", idx + 1));
+                        let job_id = segmentation_jobs::create_subtitle_job(
+                            conn,
+                            user_id,
+                            id,
+                            title.as_deref(),
+                            part,
+                        )?;
+                        job_ids.push(job_id);
+                    }
+                    EyreResult::Ok(job_ids)
+                })?;
+                return EyreResult::Ok(job_ids);
+            }
+
+            let format = lbr::import::Format::detect(&filename, &data)
+                .ok_or_else(|| eyre::eyre!("Unsupported file {filename}"))?;
+            let chapters = lbr::import::import(format, &data)
+                .wrap_err_with(|| format!("Failed to import {filename}"))?;
+            tracing::info!("Importing {} chapters from {filename}", chapters.len());
+
+            let mut conn = state.lbr_pool.get()?;
+            let job_ids = conn.transaction(|conn| {
+                let mut job_ids = Vec::new();
+                for chapter in chapters {
+                    let job_id = segmentation_jobs::create_job(
+                        conn,
+                        user_id,
+                        id,
+                        chapter.title.as_deref(),
+                        &chapter.text,
+                    )?;
+                    job_ids.push(job_id);
+                }
+                EyreResult::Ok(job_ids)
+            })?;
+            EyreResult::Ok(job_ids)
+        }
+    })
+    .await??;
+    // the chapters are segmented in order so that the first ones can be reviewed while the rest are processed
+    segmentation_jobs::spawn_jobs(state, job_ids.clone());
+
+    Ok(Json(job_ids))
+}
+
 #[instrument]
 pub async fn add_sentence(
     State(state): State<LbrState>,
@@ -182,7 +321,10 @@ pub async fn add_sentence(
         sentence,
         words,
         ignore_words,
+        ruby,
+        timestamps,
     } = sentence.0;
+    let (start_ms, end_ms) = timestamps.map(|t| (t.start_ms, t.end_ms)).unzip();
     tokio::task::spawn_blocking(move || {
         let sentence = &sentence;
         let mut conn = state.lbr_pool.get().wrap_err("Failed to get pool")?;
@@ -196,7 +338,7 @@ pub async fn add_sentence(
         }
         conn.transaction(move |conn| {
             let sentence_id = diesel::insert_into(se::table)
-                .values(eq!(se, sentence, source_id))
+                .values(eq!(se, sentence, source_id, start_ms, end_ms))
                 .returning(se::id)
                 .get_result::<i32>(conn)
                 .wrap_err("Failed to insert sentence")?;
@@ -209,6 +351,7 @@ pub async fn add_sentence(
                     sentence,
                     words,
                     ignore_words,
+                    ruby,
                 },
             )?;
             EyreResult::Ok(())
@@ -226,6 +369,8 @@ query! {
     struct Sentence {
         id: i32 = sentences::id,
         sentence: String = sentences::sentence,
+        start_ms: Option<i32> = sentences::start_ms,
+        end_ms: Option<i32> = sentences::end_ms,
     }
 }
 
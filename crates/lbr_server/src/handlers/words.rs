@@ -1,30 +1,84 @@
 //! /words
 
 use super::prelude::*;
+use crate::{domain::user_words, utils::database::WordStatus};
 use std::collections::HashMap;
 
 // handlers
 
+#[instrument]
+pub async fn statuses(
+    State(state): State<LbrState>,
+    user: Authentication,
+) -> LbrResult<Json<Vec<res::UserWord>>> {
+    let user_words = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let user_words = user_words::get_user_words(&mut conn, user.user_id)?;
+        EyreResult::Ok(user_words)
+    })
+    .await??;
+
+    Ok(Json(user_words))
+}
+
+#[instrument]
+pub async fn set_status(
+    State(state): State<LbrState>,
+    user: Authentication,
+    Json(update): Json<req::WordStatusUpdate>,
+) -> LbrResult<()> {
+    tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        user_words::set_statuses(&mut conn, user.user_id, &[update])?;
+        EyreResult::Ok(())
+    })
+    .await??;
+
+    Ok(())
+}
+
+#[instrument]
+pub async fn set_statuses(
+    State(state): State<LbrState>,
+    user: Authentication,
+    Json(updates): Json<Vec<req::WordStatusUpdate>>,
+) -> LbrResult<()> {
+    tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        user_words::set_statuses(&mut conn, user.user_id, &updates)?;
+        EyreResult::Ok(())
+    })
+    .await??;
+
+    Ok(())
+}
+
 #[instrument]
 pub async fn ignored_words(
     State(state): State<LbrState>,
     user: Authentication,
 ) -> LbrResult<Json<Vec<res::IgnoredWord>>> {
-    use schema::{ignored_words as iw, words as w};
+    use schema::{user_words as uw, words as w};
 
     let ignored_words = tokio::task::spawn_blocking(move || {
         let mut conn = state.lbr_pool.get()?;
-        let user_ignored_words = iw::table.filter(iw::user_id.eq(user.user_id));
-        let ignored_word_translations = user_ignored_words
-            .inner_join(w::table.on(w::id.eq(iw::word_id)))
+        let user_ignored_words = || {
+            uw::table.filter(
+                uw::user_id
+                    .eq(user.user_id)
+                    .and(uw::status.eq(WordStatus::Ignored)),
+            )
+        };
+        let ignored_word_translations = user_ignored_words()
+            .inner_join(w::table.on(w::id.eq(uw::word_id)))
             .select(IgnoredWordTranslations::as_select())
             .get_results(&mut conn)?;
-        let ignored_word_written_forms = user_ignored_words
-            .inner_join(w::table.on(w::id.eq(iw::word_id)))
+        let ignored_word_written_forms = user_ignored_words()
+            .inner_join(w::table.on(w::id.eq(uw::word_id)))
             .select(IgnoredWordWrittenForm::as_select())
             .get_results(&mut conn)?;
-        let ignored_word_readings = user_ignored_words
-            .inner_join(w::table.on(w::id.eq(iw::word_id)))
+        let ignored_word_readings = user_ignored_words()
+            .inner_join(w::table.on(w::id.eq(uw::word_id)))
             .select(IgnoredWordReading::as_select())
             .get_results(&mut conn)?;
 
@@ -73,13 +127,19 @@ pub async fn delete_ignored_word(
     Path(word_id): Path<i32>,
     user: Authentication,
 ) -> LbrResult<()> {
-    use schema::ignored_words as iw;
+    use schema::user_words as uw;
 
     tokio::task::spawn_blocking(move || {
         let mut conn = state.lbr_pool.get()?;
         conn.transaction(|conn| {
+            // the word becomes unknown again
             diesel::delete(
-                iw::table.filter(iw::user_id.eq(user.user_id).and(iw::word_id.eq(word_id))),
+                uw::table.filter(
+                    uw::user_id
+                        .eq(user.user_id)
+                        .and(uw::word_id.eq(word_id))
+                        .and(uw::status.eq(WordStatus::Ignored)),
+                ),
             )
             .execute(conn)?;
             LbrResult::Ok(())
@@ -91,12 +151,103 @@ pub async fn delete_ignored_word(
     Ok(())
 }
 
+#[instrument]
+pub async fn frequency_lists(
+    State(state): State<LbrState>,
+    _user: Authentication,
+) -> LbrResult<Json<Vec<res::FrequencyList>>> {
+    use schema::frequency_lists as fl;
+
+    let frequency_lists = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let frequency_lists = fl::table
+            .select((fl::id, fl::name))
+            .order_by(fl::name)
+            .get_results::<(i32, String)>(&mut conn)?
+            .into_iter()
+            .map(|(id, name)| res::FrequencyList { id, name })
+            .collect::<Vec<_>>();
+        EyreResult::Ok(frequency_lists)
+    })
+    .await??;
+
+    Ok(Json(frequency_lists))
+}
+
+#[instrument]
+pub async fn dictionaries(
+    State(state): State<LbrState>,
+    user: Authentication,
+) -> LbrResult<Json<Vec<res::Dictionary>>> {
+    use schema::{dictionaries as di, user_dictionaries as ud};
+
+    let dictionaries = tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        let enabled = ud::table
+            .filter(ud::user_id.eq(user.user_id))
+            .select(ud::dictionary_id)
+            .get_results::<i32>(&mut conn)?;
+        let dictionaries = di::table
+            .select((di::id, di::name))
+            .order_by(di::name)
+            .get_results::<(i32, String)>(&mut conn)?
+            .into_iter()
+            .map(|(id, name)| res::Dictionary {
+                id,
+                name,
+                enabled: enabled.contains(&id),
+            })
+            .collect::<Vec<_>>();
+        EyreResult::Ok(dictionaries)
+    })
+    .await??;
+
+    Ok(Json(dictionaries))
+}
+
+#[instrument]
+pub async fn set_dictionaries(
+    State(state): State<LbrState>,
+    user: Authentication,
+    Json(enabled): Json<req::EnabledDictionaries>,
+) -> LbrResult<()> {
+    use schema::user_dictionaries as ud;
+
+    tokio::task::spawn_blocking(move || {
+        let mut conn = state.lbr_pool.get()?;
+        conn.transaction(|conn| {
+            diesel::delete(ud::table.filter(ud::user_id.eq(user.user_id))).execute(conn)?;
+            let user_dictionaries = enabled
+                .dictionary_ids
+                .iter()
+                .map(|dictionary_id| {
+                    (
+                        ud::user_id.eq(user.user_id),
+                        ud::dictionary_id.eq(dictionary_id),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for chunk in user_dictionaries.pg_chunks() {
+                diesel::insert_into(ud::table)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+            }
+            EyreResult::Ok(())
+        })?;
+        EyreResult::Ok(())
+    })
+    .await??;
+
+    Ok(())
+}
+
 // queries
 
 query! {
     #[derive(Debug)]
     struct IgnoredWordTranslations {
-        word_id: i32 = ignored_words::word_id,
+        word_id: i32 = user_words::word_id,
         translations: Vec<Option<String>> = words::translations,
     }
 }
@@ -104,7 +255,7 @@ query! {
 query! {
     #[derive(Debug)]
     struct IgnoredWordWrittenForm {
-        word_id: i32 = ignored_words::word_id,
+        word_id: i32 = user_words::word_id,
         word: String = words::word,
     }
 }
@@ -112,7 +263,7 @@ query! {
 query! {
     #[derive(Debug)]
     struct IgnoredWordReading {
-        word_id: i32 = ignored_words::word_id,
+        word_id: i32 = user_words::word_id,
         reading: String = words::reading,
     }
 }
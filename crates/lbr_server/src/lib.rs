@@ -7,13 +7,14 @@ pub mod handlers;
 pub mod queries;
 pub mod schema;
 pub mod schema_ichiran;
+pub mod segmenter_pool;
 pub mod utils;
 
-use crate::handlers::{decks, sentences, sources, words};
+use crate::handlers::{decks, kanji, reviews, sentences, sources, words};
 use authentication::{Expiration, SessionCache};
 use axum::{
     Router,
-    extract::FromRef,
+    extract::{DefaultBodyLimit, FromRef},
     response::{Html, IntoResponse},
     routing::{delete, get, post},
 };
@@ -24,18 +25,22 @@ use diesel::{
 use error::EyreResult;
 use eyre::WrapErr;
 use handlers::{auth, segment};
-use ichiran::IchiranCli;
+use lbr::segmenter::{DictionarySegmenter, IchiranSegmenter, Segmenter};
 use lbr_web::App;
 use leptos::prelude::*;
 use leptos_axum::LeptosRoutes;
 use leptos_meta::*;
 use moka::future::Cache;
+use segmenter_pool::{SegmenterPool, SegmenterPoolConfig};
 use std::{collections::HashMap, fmt::Debug, ops::Deref, path::PathBuf, sync::Arc, time::Duration};
 use tokio::io::AsyncReadExt;
 use tower_cookies::{CookieManagerLayer, Key};
 
 pub type LbrPool = Pool<ConnectionManager<PgConnection>>;
 
+/// The maximum size of a file imported into a source, in bytes.
+const MAX_IMPORT_SIZE: usize = 50 * 1024 * 1024;
+
 #[derive(Clone)]
 pub struct LbrState(Arc<LbrStateCore>);
 
@@ -55,11 +60,8 @@ impl Debug for LbrState {
 
 pub struct LbrStateCore {
     pub lbr_pool: LbrPool,
-    pub ichiran_pool: LbrPool,
-    pub ichiran_cli: IchiranCli,
-    pub kanji_to_readings: HashMap<String, Vec<String>>,
-    pub word_to_meanings: HashMap<i32, Vec<String>>,
-    pub ichiran_word_to_id: HashMap<(i32, String, String), i32>,
+    pub segmenter: SegmenterPool,
+    pub kanji_to_readings: Arc<HashMap<String, Vec<String>>>,
     pub private_cookie_key: Key,
     pub sessions: SessionCache,
     pub leptos_options: LeptosOptions,
@@ -100,6 +102,13 @@ pub async fn router(state: LbrState) -> Router<()> {
                                         .delete(sources::delete),
                                 )
                                 .route("/details", get(sources::get_details))
+                                .route("/reader", get(sources::get_reader))
+                                .route("/jobs", get(sources::get_jobs))
+                                .route(
+                                    "/import",
+                                    post(sources::import)
+                                        .layer(DefaultBodyLimit::max(MAX_IMPORT_SIZE)),
+                                )
                                 .route("/sentence", post(sources::add_sentence)),
                         ),
                 )
@@ -116,9 +125,23 @@ pub async fn router(state: LbrState) -> Router<()> {
                                         .post(decks::update)
                                         .delete(decks::delete),
                                 )
-                                .route("/generate/{filename}", get(decks::generate)),
+                                .route("/generate/{filename}", get(decks::generate))
+                                .route("/preview", get(decks::preview))
+                                .route(
+                                    "/note-types",
+                                    get(decks::get_note_types).post(decks::update_note_type),
+                                )
+                                .route("/reroll", post(decks::reroll))
+                                .route("/reviews", get(reviews::get_due)),
                         ),
                 )
+                .nest(
+                    "/reviews",
+                    Router::new().route("/", post(reviews::insert)).route(
+                        "/import",
+                        post(reviews::import_history).layer(DefaultBodyLimit::max(MAX_IMPORT_SIZE)),
+                    ),
+                )
                 .nest(
                     "/sentences",
                     Router::new().nest(
@@ -133,16 +156,36 @@ pub async fn router(state: LbrState) -> Router<()> {
                             .route("/segment", post(sentences::segment)),
                     ),
                 )
+                .route("/kanji/{kanji}", get(kanji::get_one))
                 .nest(
                     "/words",
+                    Router::new()
+                        .route("/status", post(words::set_status))
+                        .route("/statuses", get(words::statuses).post(words::set_statuses))
+                        .route("/frequency-lists", get(words::frequency_lists))
+                        .route(
+                            "/dictionaries",
+                            get(words::dictionaries).post(words::set_dictionaries),
+                        )
+                        .nest(
+                            "/ignored",
+                            Router::new()
+                                .route("/", get(words::ignored_words))
+                                .route("/{id}", delete(words::delete_ignored_word)),
+                        ),
+                )
+                .nest(
+                    "/segment",
                     Router::new().nest(
-                        "/ignored",
-                        Router::new()
-                            .route("/", get(words::ignored_words))
-                            .route("/{id}", delete(words::delete_ignored_word)),
+                        "/jobs",
+                        Router::new().route("/", post(segment::create_job)).nest(
+                            "/{id}",
+                            Router::new()
+                                .route("/", get(segment::get_job))
+                                .route("/results", get(segment::get_job_results)),
+                        ),
                     ),
                 )
-                .route("/segment", post(segment::segment))
                 .layer(CookieManagerLayer::new()),
         )
         .leptos_routes(
@@ -180,10 +223,21 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
     }
 }
 
+/// The segmenter used to split sentences into words.
+pub enum SegmenterConfig {
+    /// Uses ichiran, requires a connection to the ichiran database and the ichiran CLI.
+    Ichiran {
+        database_url: String,
+        cli_path: PathBuf,
+    },
+    /// Uses LBR's own dictionary, requires no external tools.
+    Dictionary,
+}
+
 pub async fn router_from_vars(
     lbr_database_url: &str,
-    ichiran_database_url: &str,
-    ichiran_cli_path: PathBuf,
+    segmenter_config: SegmenterConfig,
+    segmenter_pool_config: SegmenterPoolConfig,
     private_cookie_password: &str,
 ) -> eyre::Result<Router<()>> {
     // conservative pool config aimed at not using the database too much
@@ -192,12 +246,7 @@ pub async fn router_from_vars(
         .idle_timeout(Some(Duration::from_secs(30)))
         .build(ConnectionManager::new(lbr_database_url))
         .wrap_err_with(|| format!("Failed to connect to the LBR database at {lbr_database_url}"))?;
-    let ichiran_pool =
-        Pool::new(ConnectionManager::new(ichiran_database_url)).wrap_err_with(|| {
-            format!("Failed to connect to the ichiran database at {ichiran_database_url}")
-        })?;
-    let ichiran_cli = IchiranCli::new(ichiran_cli_path);
-    let kanji_to_readings = if cfg!(debug_assertions) {
+    let kanji_to_readings = Arc::new(if cfg!(debug_assertions) {
         match tokio::fs::File::open("./data/kanji_to_readings.bitcode").await {
             Ok(mut file) => {
                 let mut buf = Vec::new();
@@ -232,7 +281,71 @@ pub async fn router_from_vars(
         })
         .await
         .wrap_err("Failed to generate kanji to readings mapping")??
+    });
+    let segmenter: Box<dyn Segmenter> = match segmenter_config {
+        SegmenterConfig::Ichiran {
+            database_url,
+            cli_path,
+        } => {
+            let segmenter = ichiran_segmenter(
+                lbr_pool.clone(),
+                &database_url,
+                cli_path,
+                kanji_to_readings.clone(),
+            )
+            .await?;
+            // each of the pool's workers gets an ichiran process ready to use
+            segmenter.start_processes(segmenter_pool_config.workers);
+            Box::new(segmenter)
+        }
+        SegmenterConfig::Dictionary => {
+            let lbr_pool = lbr_pool.clone();
+            let entries = tokio::task::spawn_blocking(move || {
+                let mut conn = lbr_pool.get()?;
+                let entries = domain::words::get_dictionary_entries(&mut conn)?;
+                EyreResult::Ok(entries)
+            })
+            .await
+            .wrap_err("Failed to build the dictionary")??;
+            Box::new(DictionarySegmenter::new(entries))
+        }
     };
+    let segmenter = SegmenterPool::new(segmenter, segmenter_pool_config);
+
+    let private_cookie_key = Key::from(private_cookie_password.as_bytes());
+    let sessions = Cache::builder()
+        .max_capacity(100_000_000)
+        .expire_after(Expiration::new(4))
+        .build();
+    let leptos_options = leptos::prelude::get_configuration(None)
+        .unwrap()
+        .leptos_options;
+
+    let state = LbrState(Arc::new(LbrStateCore {
+        lbr_pool,
+        segmenter,
+        kanji_to_readings,
+        private_cookie_key,
+        sessions,
+        leptos_options,
+    }));
+    domain::segmentation_jobs::resume_jobs(&state)
+        .wrap_err("Failed to resume segmentation jobs")?;
+    let router = self::router(state).await;
+    Ok(router)
+}
+
+async fn ichiran_segmenter(
+    lbr_pool: LbrPool,
+    ichiran_database_url: &str,
+    ichiran_cli_path: PathBuf,
+    kanji_to_readings: Arc<HashMap<String, Vec<String>>>,
+) -> eyre::Result<IchiranSegmenter> {
+    let ichiran_pool =
+        Pool::new(ConnectionManager::new(ichiran_database_url)).wrap_err_with(|| {
+            format!("Failed to connect to the ichiran database at {ichiran_database_url}")
+        })?;
+    let ichiran_version = domain::ichiran::ichiran_version(&ichiran_cli_path)?;
     let ichiran_word_to_id = if cfg!(debug_assertions) {
         match tokio::fs::File::open("./data/ichiran_word_to_id.bitcode").await {
             Ok(mut file) => {
@@ -307,28 +420,14 @@ pub async fn router_from_vars(
         .await??
     };
 
-    let private_cookie_key = Key::from(private_cookie_password.as_bytes());
-    let sessions = Cache::builder()
-        .max_capacity(100_000_000)
-        .expire_after(Expiration::new(4))
-        .build();
-    let leptos_options = leptos::prelude::get_configuration(None)
-        .unwrap()
-        .leptos_options;
-
-    let state = LbrState(Arc::new(LbrStateCore {
-        lbr_pool,
-        ichiran_pool,
-        ichiran_cli,
+    let segmentation_cache = domain::ichiran::SegmentationCache::new(lbr_pool, ichiran_version);
+    Ok(IchiranSegmenter::new(
+        ichiran_cli_path,
+        ichiran_word_to_id,
         kanji_to_readings,
         word_to_meanings,
-        ichiran_word_to_id,
-        private_cookie_key,
-        sessions,
-        leptos_options,
-    }));
-    let router = self::router(state).await;
-    Ok(router)
+    )
+    .with_cache(segmentation_cache))
 }
 
 pub async fn favicon() -> impl IntoResponse {
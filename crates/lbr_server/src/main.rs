@@ -1,7 +1,8 @@
 //! Web backend for LBR.
 
 use eyre::WrapErr;
-use std::{env, net::SocketAddr};
+use lbr_server::{SegmenterConfig, segmenter_pool::SegmenterPoolConfig};
+use std::{env, net::SocketAddr, time::Duration};
 use tokio::net::TcpListener;
 
 #[tokio::main]
@@ -15,16 +16,45 @@ async fn main() -> eyre::Result<()> {
         .wrap_err("Invalid SERVER_URL")?;
 
     let lbr_database_url = env::var("DATABASE_URL").wrap_err("Missing DATABASE_URL")?;
-    let ichiran_database_url =
-        env::var("ICHIRAN_DATABASE_URL").wrap_err("Missing ICHIRAN_DATABASE_URL")?;
-    let ichiran_cli_path = env::var("ICHIRAN_CLI_PATH").wrap_err("Missing ICHIRAN_CLI_PATH")?;
+    let segmenter_config = match env::var("SEGMENTER").as_deref() {
+        Ok("ichiran") | Err(_) => {
+            let database_url =
+                env::var("ICHIRAN_DATABASE_URL").wrap_err("Missing ICHIRAN_DATABASE_URL")?;
+            let cli_path = env::var("ICHIRAN_CLI_PATH").wrap_err("Missing ICHIRAN_CLI_PATH")?;
+            SegmenterConfig::Ichiran {
+                database_url,
+                cli_path: cli_path.into(),
+            }
+        }
+        Ok("dictionary") => SegmenterConfig::Dictionary,
+        Ok(other) => eyre::bail!("Invalid SEGMENTER '{other}', expected 'ichiran' or 'dictionary'"),
+    };
+    let mut segmenter_pool_config = SegmenterPoolConfig::default();
+    if let Ok(workers) = env::var("SEGMENTER_WORKERS") {
+        segmenter_pool_config.workers = workers.parse().wrap_err("Invalid SEGMENTER_WORKERS")?;
+    }
+    if let Ok(queue_size) = env::var("SEGMENTER_QUEUE_SIZE") {
+        segmenter_pool_config.queue_size = queue_size
+            .parse()
+            .wrap_err("Invalid SEGMENTER_QUEUE_SIZE")?;
+    }
+    if let Ok(timeout) = env::var("SEGMENTER_TIMEOUT_SECS") {
+        let timeout = timeout.parse().wrap_err("Invalid SEGMENTER_TIMEOUT_SECS")?;
+        segmenter_pool_config.timeout = Duration::from_secs(timeout);
+    }
+    if let Ok(queue_timeout) = env::var("SEGMENTER_QUEUE_TIMEOUT_SECS") {
+        let queue_timeout = queue_timeout
+            .parse()
+            .wrap_err("Invalid SEGMENTER_QUEUE_TIMEOUT_SECS")?;
+        segmenter_pool_config.queue_timeout = Duration::from_secs(queue_timeout);
+    }
     let private_cookie_password =
         env::var("PRIVATE_COOKIE_PASSWORD").wrap_err("Missing PRIVATE_COOKIE_PASSWORD")?;
 
     let router = lbr_server::router_from_vars(
         &lbr_database_url,
-        &ichiran_database_url,
-        ichiran_cli_path.into(),
+        segmenter_config,
+        segmenter_pool_config,
         &private_cookie_password,
     )
     .await
@@ -1,17 +1,22 @@
 //! Reusable database query functions.
 
+use crate::utils::database::WordStatus;
 use diesel::prelude::*;
-use std::collections::HashSet;
+use std::collections::HashMap;
 
-pub fn ignored_words(conn: &mut PgConnection, user_id: i32) -> eyre::Result<HashSet<i32>> {
-    use crate::schema::ignored_words as iw;
+/// Returns the user's statuses for the words that have one, words without a status are unknown.
+pub fn word_statuses(
+    conn: &mut PgConnection,
+    user_id: i32,
+) -> eyre::Result<HashMap<i32, WordStatus>> {
+    use crate::schema::user_words as uw;
 
-    let ignored_words = iw::table
-        .select(iw::word_id)
-        .filter(iw::user_id.eq(user_id))
-        .get_results::<i32>(conn)?
+    let word_statuses = uw::table
+        .select((uw::word_id, uw::status))
+        .filter(uw::user_id.eq(user_id))
+        .get_results::<(i32, WordStatus)>(conn)?
         .into_iter()
-        .collect::<HashSet<i32>>();
+        .collect::<HashMap<_, _>>();
 
-    Ok(ignored_words)
+    Ok(word_statuses)
 }
@@ -1,13 +1,80 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "card_kind"))]
+    pub struct CardKind;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "card_order"))]
+    pub struct CardOrder;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "deck_source_kind"))]
     pub struct DeckSourceKind;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "example_preference"))]
+    pub struct ExamplePreference;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "furigana"))]
     pub struct Furigana;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "review_rating"))]
+    pub struct ReviewRating;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "segmentation_job_status"))]
+    pub struct SegmentationJobStatus;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "word_status"))]
+    pub struct WordStatus;
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CardKind;
+
+    card_schedules (user_id, kind, card_id) {
+        user_id -> Int4,
+        kind -> CardKind,
+        card_id -> Int4,
+        due -> Timestamptz,
+        interval_secs -> Int8,
+        ease -> Float8,
+        repetitions -> Int4,
+        lapses -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CardKind;
+
+    deck_cards (deck_id, kind, card_id) {
+        deck_id -> Int4,
+        kind -> CardKind,
+        card_id -> Int4,
+        sentence_id -> Nullable<Int4>,
+        example_word_id -> Nullable<Int4>,
+        chosen_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CardKind;
+
+    deck_note_types (deck_id, kind) {
+        deck_id -> Int4,
+        kind -> CardKind,
+        anki_model_id -> Int8,
+        css -> Text,
+        templates -> Jsonb,
+    }
 }
 
 diesel::table! {
@@ -23,18 +90,45 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CardOrder;
+    use super::sql_types::ExamplePreference;
+
     decks (id) {
         id -> Int4,
         anki_deck_id -> Int8,
         name -> Text,
         user_id -> Int4,
+        exclude_known -> Bool,
+        card_order -> CardOrder,
+        max_word_cards -> Nullable<Int4>,
+        max_kanji_cards -> Nullable<Int4>,
+        min_word_frequency -> Nullable<Int4>,
+        max_word_frequency -> Nullable<Int4>,
+        max_kanji_grade -> Nullable<Int4>,
+        min_kanji_jlpt -> Nullable<Int4>,
+        reading_cards -> Bool,
+        production_cards -> Bool,
+        cloze_cards -> Bool,
+        extra_examples -> Int4,
+        example_preference -> ExamplePreference,
+        min_word_frequency_score -> Nullable<Int4>,
+        frequency_list_id -> Nullable<Int4>,
+        max_word_frequency_rank -> Nullable<Int4>,
     }
 }
 
 diesel::table! {
-    ignored_words (word_id, user_id) {
-        word_id -> Int4,
-        user_id -> Int4,
+    dictionaries (id) {
+        id -> Int4,
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    frequency_lists (id) {
+        id -> Int4,
+        name -> Text,
     }
 }
 
@@ -45,6 +139,11 @@ diesel::table! {
         name -> Nullable<Text>,
         meanings -> Array<Nullable<Text>>,
         components -> Array<Nullable<Text>>,
+        grade -> Nullable<Int4>,
+        jlpt -> Nullable<Int4>,
+        stroke_count -> Nullable<Int4>,
+        frequency -> Nullable<Int4>,
+        radical -> Nullable<Int4>,
     }
 }
 
@@ -65,6 +164,57 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CardKind;
+    use super::sql_types::ReviewRating;
+
+    review_logs (id) {
+        id -> Int4,
+        user_id -> Int4,
+        kind -> CardKind,
+        card_id -> Int4,
+        rating -> ReviewRating,
+        reviewed_at -> Timestamptz,
+        interval_secs -> Int8,
+        ease -> Float8,
+    }
+}
+
+diesel::table! {
+    segmentation_cache (sentence_hash, ichiran_version) {
+        sentence_hash -> Bytea,
+        ichiran_version -> Text,
+        segments -> Jsonb,
+    }
+}
+
+diesel::table! {
+    segmentation_job_sentences (job_id, idx) {
+        job_id -> Int4,
+        idx -> Int4,
+        sentence -> Text,
+        segments -> Nullable<Jsonb>,
+        error -> Nullable<Text>,
+        ruby -> Jsonb,
+        start_ms -> Nullable<Int4>,
+        end_ms -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::SegmentationJobStatus;
+
+    segmentation_jobs (id) {
+        id -> Int4,
+        user_id -> Int4,
+        source_id -> Int4,
+        status -> SegmentationJobStatus,
+        title -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::Furigana;
@@ -85,6 +235,8 @@ diesel::table! {
         id -> Int4,
         sentence -> Text,
         source_id -> Int4,
+        start_ms -> Nullable<Int4>,
+        end_ms -> Nullable<Int4>,
     }
 }
 
@@ -96,6 +248,27 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    user_dictionaries (user_id, dictionary_id) {
+        user_id -> Int4,
+        dictionary_id -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::WordStatus;
+
+    user_words (user_id, word_id) {
+        user_id -> Int4,
+        word_id -> Int4,
+        status -> WordStatus,
+        level -> Int4,
+        first_seen -> Timestamptz,
+        last_changed -> Timestamptz,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> Int4,
@@ -105,6 +278,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    word_definitions (dictionary_id, word_id) {
+        dictionary_id -> Int4,
+        word_id -> Int4,
+        definitions -> Array<Nullable<Text>>,
+    }
+}
+
+diesel::table! {
+    word_frequencies (list_id, word_id) {
+        list_id -> Int4,
+        word_id -> Int4,
+        rank -> Int4,
+    }
+}
+
 diesel::table! {
     word_kanji (word_id, kanji_id) {
         word_id -> Int4,
@@ -112,6 +301,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    word_senses (word_id, idx) {
+        word_id -> Int4,
+        idx -> Int4,
+        glosses -> Array<Nullable<Text>>,
+        parts_of_speech -> Array<Nullable<Text>>,
+        misc -> Array<Nullable<Text>>,
+        fields -> Array<Nullable<Text>>,
+        dialects -> Array<Nullable<Text>>,
+        info -> Array<Nullable<Text>>,
+        cross_references -> Array<Nullable<Text>>,
+        antonyms -> Array<Nullable<Text>>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::Furigana;
@@ -124,33 +328,64 @@ diesel::table! {
         reading_standard -> Text,
         furigana -> Array<Nullable<Furigana>>,
         translations -> Array<Nullable<Text>>,
+        pitch_accents -> Array<Nullable<Int4>>,
+        priority_tags -> Array<Nullable<Text>>,
+        frequency_score -> Int4,
     }
 }
 
+diesel::joinable!(card_schedules -> users (user_id));
+diesel::joinable!(deck_cards -> decks (deck_id));
+diesel::joinable!(deck_note_types -> decks (deck_id));
 diesel::joinable!(deck_sources -> decks (deck_id));
 diesel::joinable!(deck_sources -> sources (source_id));
+diesel::joinable!(decks -> frequency_lists (frequency_list_id));
 diesel::joinable!(decks -> users (user_id));
-diesel::joinable!(ignored_words -> users (user_id));
-diesel::joinable!(ignored_words -> words (word_id));
 diesel::joinable!(kanji_readings -> kanji (kanji_id));
+diesel::joinable!(review_logs -> users (user_id));
+diesel::joinable!(segmentation_job_sentences -> segmentation_jobs (job_id));
+diesel::joinable!(segmentation_jobs -> sources (source_id));
+diesel::joinable!(segmentation_jobs -> users (user_id));
 diesel::joinable!(sentence_words -> sentences (sentence_id));
 diesel::joinable!(sentence_words -> words (word_id));
 diesel::joinable!(sentences -> sources (source_id));
 diesel::joinable!(sources -> users (user_id));
+diesel::joinable!(user_dictionaries -> dictionaries (dictionary_id));
+diesel::joinable!(user_dictionaries -> users (user_id));
+diesel::joinable!(user_words -> users (user_id));
+diesel::joinable!(user_words -> words (word_id));
+diesel::joinable!(word_definitions -> dictionaries (dictionary_id));
+diesel::joinable!(word_definitions -> words (word_id));
+diesel::joinable!(word_frequencies -> frequency_lists (list_id));
+diesel::joinable!(word_frequencies -> words (word_id));
 diesel::joinable!(word_kanji -> kanji (kanji_id));
 diesel::joinable!(word_kanji -> words (word_id));
+diesel::joinable!(word_senses -> words (word_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    card_schedules,
+    deck_cards,
+    deck_note_types,
     deck_sources,
     decks,
-    ignored_words,
+    dictionaries,
+    frequency_lists,
     kanji,
     kanji_readings,
     kanji_similar,
+    review_logs,
+    segmentation_cache,
+    segmentation_job_sentences,
+    segmentation_jobs,
     sentence_words,
     sentences,
     sources,
+    user_dictionaries,
+    user_words,
     users,
+    word_definitions,
+    word_frequencies,
     word_kanji,
+    word_senses,
     words,
 );
@@ -0,0 +1,345 @@
+//! A bounded pool of long-lived workers that segment text in the background.
+
+use lbr::segmenter::{Segmenter, SegmenterError};
+use lbr_core::ichiran_types::Segment;
+use std::{
+    sync::{
+        Arc, Mutex, PoisonError,
+        mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender},
+    },
+    time::{Duration, Instant},
+};
+
+/// Configuration for the segmenter pool.
+#[derive(Debug, Clone, Copy)]
+pub struct SegmenterPoolConfig {
+    /// The number of workers, i.e. the maximum number of texts that are segmented at once.
+    pub workers: usize,
+    /// The maximum number of jobs waiting for a worker before new submissions block.
+    pub queue_size: usize,
+    /// How long a single job is allowed to run after a worker picks it up.
+    pub timeout: Duration,
+    /// How long a job is allowed to wait for a worker after being submitted.
+    pub queue_timeout: Duration,
+}
+
+impl Default for SegmenterPoolConfig {
+    fn default() -> Self {
+        let workers = std::thread::available_parallelism()
+            .map(|p| p.get())
+            .unwrap_or(4);
+        Self {
+            workers,
+            queue_size: 1024,
+            timeout: Duration::from_secs(60),
+            queue_timeout: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+/// Runs segmentation jobs on a fixed number of workers.
+/// Each worker segments one text at a time, so with ichiran there are at most `workers` ichiran processes in use,
+/// which the segmenter keeps running between jobs. The segmenter is given the job's timeout so that it can kill ichiran when it hangs.
+/// Each worker runs the segmenter on a separate runner thread, which is replaced if it crashes
+/// or hasn't stopped by the time the job has taken twice its timeout, so a stuck job never blocks a worker for good.
+pub struct SegmenterPool {
+    jobs: SyncSender<Job>,
+    queue_timeout: Duration,
+}
+
+struct Job {
+    text: String,
+    submitted_at: Instant,
+    updates: Sender<JobUpdate>,
+}
+
+enum JobUpdate {
+    Started,
+    Finished(Result<Vec<Segment>, SegmenterError>),
+}
+
+impl SegmenterPool {
+    pub fn new(segmenter: Box<dyn Segmenter>, config: SegmenterPoolConfig) -> Self {
+        let segmenter = Arc::<dyn Segmenter>::from(segmenter);
+        let (jobs, receiver) = mpsc::sync_channel(config.queue_size);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for worker in 0..config.workers.max(1) {
+            spawn_worker(worker, segmenter.clone(), receiver.clone(), config);
+        }
+        tracing::info!("Started segmenter pool with {} workers", config.workers);
+        Self {
+            jobs,
+            queue_timeout: config.queue_timeout,
+        }
+    }
+
+    /// Queues the text for segmentation, blocking if the queue is full.
+    pub fn submit(&self, text: String) -> Result<PendingSegmentation, SegmenterError> {
+        let (updates, receiver) = mpsc::channel();
+        let submitted_at = Instant::now();
+        self.jobs
+            .send(Job {
+                text,
+                submitted_at,
+                updates,
+            })
+            .map_err(|_| SegmenterError::Unavailable)?;
+        Ok(PendingSegmentation {
+            receiver,
+            submitted_at,
+            queue_timeout: self.queue_timeout,
+        })
+    }
+}
+
+impl Segmenter for SegmenterPool {
+    fn segment(&self, text: &str) -> Result<Vec<Segment>, SegmenterError> {
+        self.submit(text.to_string())?.wait()
+    }
+}
+
+/// A segmentation job that has been submitted to the pool.
+pub struct PendingSegmentation {
+    receiver: Receiver<JobUpdate>,
+    submitted_at: Instant,
+    queue_timeout: Duration,
+}
+
+impl PendingSegmentation {
+    /// Waits for the job to finish.
+    /// Fails if no worker picks up the job within the queue timeout,
+    /// after that the worker reports back within twice the job's timeout.
+    pub fn wait(self) -> Result<Vec<Segment>, SegmenterError> {
+        let queue_deadline = self.submitted_at + self.queue_timeout;
+        let mut started = false;
+        loop {
+            let update = if started {
+                self.receiver
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                self.receiver
+                    .recv_timeout(queue_deadline.saturating_duration_since(Instant::now()))
+            };
+            match update {
+                Ok(JobUpdate::Started) => started = true,
+                Ok(JobUpdate::Finished(result)) => return result,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(SegmenterError::Timeout(self.queue_timeout));
+                }
+                // the pool was dropped
+                Err(RecvTimeoutError::Disconnected) => return Err(SegmenterError::Unavailable),
+            }
+        }
+    }
+}
+
+fn spawn_worker(
+    worker: usize,
+    segmenter: Arc<dyn Segmenter>,
+    jobs: Arc<Mutex<Receiver<Job>>>,
+    config: SegmenterPoolConfig,
+) {
+    let spawned = std::thread::Builder::new()
+        .name(format!("segmenter-{worker}"))
+        .spawn(move || run_worker(worker, segmenter, &jobs, config));
+    if let Err(err) = spawned {
+        tracing::error!("Failed to spawn segmenter worker {worker}: {err}");
+    }
+}
+
+fn run_worker(
+    worker: usize,
+    segmenter: Arc<dyn Segmenter>,
+    jobs: &Mutex<Receiver<Job>>,
+    config: SegmenterPoolConfig,
+) {
+    let mut runner = None;
+    loop {
+        // the lock is only held while waiting for a job, the poison is ignored just in case
+        let job = jobs.lock().unwrap_or_else(PoisonError::into_inner).recv();
+        let Ok(Job {
+            text,
+            submitted_at,
+            updates,
+        }) = job
+        else {
+            // the pool was dropped
+            return;
+        };
+        // whoever submitted the job has already given up on it
+        if submitted_at.elapsed() > config.queue_timeout {
+            let result = Err(SegmenterError::Timeout(config.queue_timeout));
+            let _ = updates.send(JobUpdate::Finished(result));
+            continue;
+        }
+
+        // the receiver may have been dropped, in which case the job is still processed
+        // so that its result can be cached
+        let _ = updates.send(JobUpdate::Started);
+        let current_runner = match runner.take() {
+            Some(runner) => runner,
+            None => match Runner::spawn(worker, segmenter.clone(), config.timeout) {
+                Ok(runner) => runner,
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to spawn a runner for segmenter worker {worker}: {err}"
+                    );
+                    let _ = updates.send(JobUpdate::Finished(Err(SegmenterError::Unavailable)));
+                    continue;
+                }
+            },
+        };
+        let result = match current_runner.run(text, config.timeout * 2) {
+            Ok(result) => {
+                runner = Some(current_runner);
+                result
+            }
+            // the runner is dropped and a new one is spawned for the next job,
+            // a stuck runner exits once the segmenter returns
+            Err(RecvTimeoutError::Timeout) => {
+                tracing::error!(
+                    "Segmenter worker {worker} did not stop after its timeout, replacing it"
+                );
+                Err(SegmenterError::Timeout(config.timeout))
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                tracing::error!("Segmenter worker {worker} crashed, restarting");
+                Err(SegmenterError::Unavailable)
+            }
+        };
+        let _ = updates.send(JobUpdate::Finished(result));
+    }
+}
+
+// a thread that runs the segmenter for one text at a time
+struct Runner {
+    texts: Sender<String>,
+    results: Receiver<Result<Vec<Segment>, SegmenterError>>,
+}
+
+impl Runner {
+    fn spawn(
+        worker: usize,
+        segmenter: Arc<dyn Segmenter>,
+        timeout: Duration,
+    ) -> std::io::Result<Self> {
+        let (texts, text_receiver) = mpsc::channel::<String>();
+        let (result_sender, results) = mpsc::channel();
+        std::thread::Builder::new()
+            .name(format!("segmenter-{worker}-runner"))
+            .spawn(move || {
+                // stops once the worker drops the runner
+                for text in text_receiver {
+                    let result = segmenter.segment_with_timeout(&text, timeout);
+                    if result_sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        Ok(Self { texts, results })
+    }
+
+    fn run(
+        &self,
+        text: String,
+        timeout: Duration,
+    ) -> Result<Result<Vec<Segment>, SegmenterError>, RecvTimeoutError> {
+        self.texts
+            .send(text)
+            .map_err(|_| RecvTimeoutError::Disconnected)?;
+        self.results.recv_timeout(timeout)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Echo;
+
+    impl Segmenter for Echo {
+        fn segment(&self, text: &str) -> Result<Vec<Segment>, SegmenterError> {
+            if text == "panic" {
+                panic!("crashed");
+            }
+            if text == "slow" {
+                std::thread::sleep(Duration::from_millis(200));
+            }
+            // ignores the timeout entirely
+            if text == "hang" {
+                std::thread::sleep(Duration::from_secs(2));
+            }
+            Ok(vec![Segment {
+                text: text.to_string(),
+                interpretations: Vec::new(),
+                range: 0..text.len(),
+            }])
+        }
+    }
+
+    fn pool(workers: usize, timeout: Duration, queue_timeout: Duration) -> SegmenterPool {
+        SegmenterPool::new(
+            Box::new(Echo),
+            SegmenterPoolConfig {
+                workers,
+                queue_size: 16,
+                timeout,
+                queue_timeout,
+            },
+        )
+    }
+
+    #[test]
+    fn segments_queued_jobs() {
+        let pool = pool(2, Duration::from_secs(5), Duration::from_secs(5));
+        let pending = (0..10)
+            .map(|i| pool.submit(i.to_string()).unwrap())
+            .collect::<Vec<_>>();
+        for (i, pending) in pending.into_iter().enumerate() {
+            let segments = pending.wait().unwrap();
+            assert_eq!(segments[0].text, i.to_string());
+        }
+    }
+
+    #[test]
+    fn times_out_slow_jobs() {
+        let pool = pool(1, Duration::from_millis(10), Duration::from_secs(5));
+        let err = pool.segment("slow").unwrap_err();
+        assert!(matches!(err, SegmenterError::Timeout(_)));
+    }
+
+    #[test]
+    fn runs_next_job_after_timeout() {
+        let pool = pool(1, Duration::from_millis(50), Duration::from_secs(5));
+        let hung = pool.submit("hang".to_string()).unwrap();
+        let next = pool.submit("ok".to_string()).unwrap();
+        let err = hung.wait().unwrap_err();
+        assert!(matches!(err, SegmenterError::Timeout(_)));
+
+        // the stuck runner is replaced instead of blocking the worker until it finishes
+        let started = Instant::now();
+        let segments = next.wait().unwrap();
+        assert_eq!(segments[0].text, "ok");
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn times_out_queued_jobs() {
+        let pool = pool(1, Duration::from_secs(5), Duration::from_millis(50));
+        let slow = pool.submit("slow".to_string()).unwrap();
+        let queued = pool.submit("queued".to_string()).unwrap();
+        let err = queued.wait().unwrap_err();
+        assert!(matches!(err, SegmenterError::Timeout(_)));
+        assert_eq!(slow.wait().unwrap()[0].text, "slow");
+    }
+
+    #[test]
+    fn restarts_crashed_workers() {
+        let pool = pool(1, Duration::from_secs(5), Duration::from_secs(5));
+        let err = pool.segment("panic").unwrap_err();
+        assert!(matches!(err, SegmenterError::Unavailable));
+        let segments = pool.segment("ok").unwrap();
+        assert_eq!(segments[0].text, "ok");
+    }
+}
@@ -19,3 +19,61 @@ diesel_enum!(
         Kanji: "kanji"
     }
 );
+
+diesel_enum!(
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    SegmentationJobStatus {
+        Queued: "queued",
+        Running: "running",
+        Finished: "finished",
+        Failed: "failed"
+    }
+);
+
+diesel_enum!(
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    WordStatus {
+        Unknown: "unknown",
+        Learning: "learning",
+        Known: "known",
+        Ignored: "ignored"
+    }
+);
+
+diesel_enum!(
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    CardKind {
+        Word: "word",
+        Kanji: "kanji"
+    }
+);
+
+diesel_enum!(
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    CardOrder {
+        Frequency: "frequency",
+        Appearance: "appearance",
+        Random: "random",
+        Common: "common",
+        Grade: "grade"
+    }
+);
+
+diesel_enum!(
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    ExamplePreference {
+        Shortest: "shortest",
+        KnownWords: "known_words",
+        SourceDiversity: "source_diversity"
+    }
+);
+
+diesel_enum!(
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    ReviewRating {
+        Again: "again",
+        Hard: "hard",
+        Good: "good",
+        Easy: "easy"
+    }
+);
@@ -1,6 +1,9 @@
 //! Custom components.
 
 pub mod analysis;
+pub mod deck;
+pub mod reader;
+pub mod review;
 
 use crate::{
     context::{get_client, get_session},
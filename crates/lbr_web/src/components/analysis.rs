@@ -1,6 +1,6 @@
 //! Components related to sentence analysis.
 
-use crate::{context::get_client, error::WebResult};
+use crate::{context::get_client, error::WebResult, utils};
 use itertools::Itertools;
 use lbr_api::{request as req, response as res};
 use leptos::{html, prelude::*};
@@ -12,42 +12,118 @@ use std::{
     ops::Range,
     rc::Rc,
     sync::Arc,
+    time::Duration,
 };
 
+/// Polls the segmentation job until it is done and shows its results as they come in.
 #[component]
-pub fn SegmentedParagraphView(source_id: i32, paragraph: res::SegmentedParagraph) -> impl IntoView {
-    let active_sentence = RwSignal::new(0usize);
-    let completed_sentences = RwSignal::new(HashSet::<usize>::new());
-    let sentence_is_active = move |idx: usize| active_sentence.read() == idx;
-    let sentences_view = paragraph
-        .sentences
-        .iter()
-        .enumerate()
-        .map(|(idx, s)| {
-            let sentence_button_class = move || {
-                if sentence_is_active(idx) {
-                    "button mt-2 is-light"
-                } else {
-                    "button mt-2 is-primary"
-                }
+pub fn SegmentationJobView(source_id: i32, job_id: i32) -> impl IntoView {
+    let job = RwSignal::new(None::<res::SegmentationJob>);
+    let paragraph = RwSignal::new(res::SegmentedParagraph {
+        sentences: Vec::new(),
+        word_statuses: HashMap::new(),
+        word_definitions: HashMap::new(),
+    });
+    let poll_act = Action::new(move |&()| {
+        let client = get_client();
+        async move {
+            let polled_job = SendWrapper::new(client.get_segmentation_job(job_id)).await?;
+            let results = SendWrapper::new(client.get_segmentation_job_results(job_id)).await?;
+            // only update the paragraph when there are new results
+            // so that the sentences that are already shown are not rerendered
+            if results.sentences.len() != paragraph.with_untracked(|p| p.sentences.len()) {
+                paragraph.set(results);
+            }
+            job.set(Some(polled_job));
+            WebResult::Ok(())
+        }
+    });
+    poll_act.dispatch(());
+    Effect::new(move |_| {
+        if job.with(|job| job.as_ref().is_some_and(|job| !job.status.is_done())) {
+            set_timeout(
+                move || {
+                    poll_act.dispatch(());
+                },
+                Duration::from_secs(1),
+            );
+        }
+    });
+
+    let progress = move || {
+        poll_act.value().get().transpose()?;
+        let view = job.get().map(|job| {
+            let total = job.sentences.len();
+            let segmented = job.sentences.iter().filter(|s| s.segmented).count();
+            // sentences that failed will not be retried so they count as processed
+            let processed = segmented + job.failed as usize;
+            let failed = job
+                .sentences
+                .iter()
+                .filter_map(|s| {
+                    s.error
+                        .clone()
+                        .map(|error| view! { <li>{format!("{}: {error}", s.sentence)}</li> })
+                })
+                .collect_view();
+            let status = match job.status {
+                res::SegmentationJobStatus::Queued => "Queued",
+                res::SegmentationJobStatus::Running => "Analysing...",
+                res::SegmentationJobStatus::Finished => "Finished",
+                res::SegmentationJobStatus::Failed => "Failed",
             };
-            let snippet_end = s.sentence.chars().take(4).map(|c| c.len_utf8()).sum();
             view! {
                 <div>
-                    <button class=sentence_button_class on:click=move |_ev| active_sentence.set(idx)>
-                        {idx}: {s.sentence[..snippet_end].to_string()}
-                    </button>
+                    {format!(
+                        "{status} ({segmented}/{total} sentences analysed, {} failed)",
+                        job.failed,
+                    )}
                 </div>
+                <progress class="progress is-primary" value=processed max=total/>
+                <ul>{failed}</ul>
             }
-        })
-        .collect_view();
+        });
+        WebResult::Ok(view)
+    };
 
-    let ignored_words = Arc::new(paragraph.ignored_words);
-    let segmented_sentence_views = paragraph
-        .sentences
-        .into_iter()
-        .enumerate()
-        .map(move |(idx, segmented_sentence)| {
+    view! {
+        <ErrorBoundary fallback={utils::errors_fallback}>
+            {progress}
+        </ErrorBoundary>
+        <SegmentedParagraphView source_id paragraph />
+    }
+}
+
+#[component]
+pub fn SegmentedParagraphView(
+    source_id: i32,
+    #[prop(into)] paragraph: Signal<res::SegmentedParagraph>,
+) -> impl IntoView {
+    let active_sentence = RwSignal::new(0usize);
+    let completed_sentences = RwSignal::new(HashSet::<usize>::new());
+    let sentence_is_active = move |idx: usize| active_sentence.read() == idx;
+    let sentences =
+        move || paragraph.with(|p| p.sentences.iter().cloned().enumerate().collect::<Vec<_>>());
+    let sentence_view = move |(idx, s): (usize, res::SegmentedParagraphSentence)| {
+        let sentence_button_class = move || {
+            if sentence_is_active(idx) {
+                "button mt-2 is-light"
+            } else {
+                "button mt-2 is-primary"
+            }
+        };
+        let snippet_end = s.sentence.chars().take(4).map(|c| c.len_utf8()).sum();
+        view! {
+            <div>
+                <button class=sentence_button_class on:click=move |_ev| active_sentence.set(idx)>
+                    {idx}: {s.sentence[..snippet_end].to_string()}
+                    {s.timestamps.map(|t| format!(" ({})", utils::format_timestamps(t)))}
+                </button>
+            </div>
+        }
+    };
+    let segmented_sentence_view =
+        move |(idx, segmented_sentence): (usize, res::SegmentedParagraphSentence)| {
             let class = move || {
                 if sentence_is_active(idx) {
                     ""
@@ -66,6 +142,9 @@ pub fn SegmentedParagraphView(source_id: i32, paragraph: res::SegmentedParagraph
                     .location()
                     .set_hash("paragraph-segmentation");
             });
+            let word_statuses = Arc::new(paragraph.with_untracked(|p| p.word_statuses.clone()));
+            let word_definitions =
+                Arc::new(paragraph.with_untracked(|p| p.word_definitions.clone()));
             view! {
                 <div class=class>
                     <SegmentedSentenceView
@@ -73,17 +152,87 @@ pub fn SegmentedParagraphView(source_id: i32, paragraph: res::SegmentedParagraph
                         sentence_id={None}
                         sentence={segmented_sentence.sentence}
                         segments={segmented_sentence.segments}
-                        ignored_words={ignored_words.clone()}
+                        ruby={segmented_sentence.ruby}
+                        timestamps={segmented_sentence.timestamps}
+                        word_statuses={word_statuses}
+                        word_definitions={word_definitions}
                         on_successful_accept={on_successful_accept}
                     />
                 </div>
             }
+        };
+
+    // keyed by index so that the existing sentences are kept as is when new ones are added
+    view! {
+        <For each=sentences key=|(idx, _)| *idx children=sentence_view />
+        <For each=sentences key=|(idx, _)| *idx children=segmented_sentence_view />
+    }
+}
+
+/// Shows the reading with a line over the high morae and a tick where the pitch drops.
+#[component]
+fn PitchAccent(reading: String, accent: u32) -> impl IntoView {
+    let morae = lbr_core::pitch_accent::pitch_accent(&reading, accent)
+        .into_iter()
+        .map(|mora| {
+            let style = match (mora.high, mora.downstep) {
+                (true, true) => "border-top: 1px solid; border-right: 1px solid;",
+                (true, false) => "border-top: 1px solid;",
+                (false, _) => "",
+            };
+            view! { <span style=style>{mora.text.to_string()}</span> }
         })
         .collect_view();
+    view! { <span class="mr-2" lang="ja">{morae}</span> }
+}
 
+/// Shows a dictionary sense with its parts of speech, usage tags and related words.
+#[component]
+fn WordSenseView(sense: res::WordSense) -> impl IntoView {
+    let parts_of_speech = sense
+        .parts_of_speech
+        .iter()
+        .map(|pos| view! { <span class="tag is-light mr-1">{pos.clone()}</span> })
+        .collect_view();
+    let tags = sense.tags().collect::<Vec<_>>();
+    let tags = (!tags.is_empty()).then(|| {
+        view! { <span class="is-size-7 has-text-grey">" (" {tags.join(", ")} ")"</span> }
+    });
+    let info = (!sense.info.is_empty()).then(|| {
+        view! { <span class="is-size-7">" [" {sense.info.join(", ")} "]"</span> }
+    });
+    let related = (!sense.cross_references.is_empty() || !sense.antonyms.is_empty()).then(|| {
+        let see_also = (!sense.cross_references.is_empty())
+            .then(|| format!("See also: {}", sense.cross_references.join(", ")));
+        let antonyms = (!sense.antonyms.is_empty())
+            .then(|| format!("Antonyms: {}", sense.antonyms.join(", ")));
+        let related = see_also.into_iter().chain(antonyms).join(" ");
+        view! { <div class="is-size-7" lang="ja">{related}</div> }
+    });
     view! {
-        {sentences_view}
-        {segmented_sentence_views}
+        <div>
+            {parts_of_speech}
+            {sense.glosses.join("; ")}
+            {tags}
+            {info}
+            {related}
+        </div>
+    }
+}
+
+/// Shows a word's definitions in one of the additional dictionaries.
+#[component]
+fn WordDefinitionsView(definitions: res::WordDefinitions) -> impl IntoView {
+    let definition_list = definitions
+        .definitions
+        .into_iter()
+        .map(|definition| view! { <li style="white-space: pre-line;">{definition}</li> })
+        .collect_view();
+    view! {
+        <div class="mt-2">
+            <div class="is-size-7 has-text-grey">{definitions.dictionary}</div>
+            <ul lang="ja">{definition_list}</ul>
+        </div>
     }
 }
 
@@ -96,17 +245,27 @@ struct FormWord {
     text_reading: Option<String>,
     db_word: String,
     db_reading: Option<String>,
+    // the reading the pitch accents apply to
+    accent_reading: String,
+    pitch_accents: Vec<u32>,
     score: i32,
+    frequency_score: i32,
+    status: res::WordStatus,
     meanings: Vec<String>,
+    senses: Vec<res::WordSense>,
+    definitions: Vec<res::WordDefinitions>,
+    inflections: Vec<String>,
     tail: Option<String>,
 }
 
 #[derive(Debug)]
 enum FormWordOr {
     FormWord(FormWord),
-    Ignored {
+    /// A word the user knows or has ignored.
+    Hidden {
         text_word: String,
         range: Range<usize>,
+        status: res::WordStatus,
     },
     Unknown {
         text_word: String,
@@ -118,7 +277,7 @@ impl FormWordOr {
     fn range(&self) -> Range<usize> {
         match self {
             FormWordOr::FormWord(fw) => fw.range.clone(),
-            FormWordOr::Ignored { range, .. } => range.clone(),
+            FormWordOr::Hidden { range, .. } => range.clone(),
             FormWordOr::Unknown { range, .. } => range.clone(),
         }
     }
@@ -130,7 +289,10 @@ pub fn SegmentedSentenceView(
     sentence_id: Option<i32>,
     sentence: String,
     segments: Vec<res::ApiSegment>,
-    ignored_words: Arc<HashSet<i32>>,
+    ruby: Vec<res::Ruby>,
+    timestamps: Option<res::Timestamps>,
+    word_statuses: Arc<HashMap<i32, res::WordStatus>>,
+    word_definitions: Arc<HashMap<i32, Vec<res::WordDefinitions>>>,
     on_successful_accept: Arc<dyn Fn() + Send + Sync>,
 ) -> impl IntoView {
     // convert the words into a more convenient form
@@ -140,7 +302,8 @@ pub fn SegmentedSentenceView(
         .flat_map(|s| {
             let s_range = s.range.clone();
             let sentence = &sentence;
-            let ignored_words = &ignored_words;
+            let word_statuses = &word_statuses;
+            let word_definitions = &word_definitions;
             let field_id = field_id.clone();
             s.interpretations.clone().into_iter().map(move |i| {
                 let text_word = sentence[s_range.clone()].to_string();
@@ -151,11 +314,16 @@ pub fn SegmentedSentenceView(
                         range: s_range.clone(),
                     };
                 };
-                // check ignored
-                if ignored_words.contains(&word_id) {
-                    return FormWordOr::Ignored {
+                // hide words the user doesn't need to review
+                let status = word_statuses
+                    .get(&word_id)
+                    .copied()
+                    .unwrap_or(res::WordStatus::Unknown);
+                if matches!(status, res::WordStatus::Known | res::WordStatus::Ignored) {
+                    return FormWordOr::Hidden {
                         text_word,
                         range: s_range.clone(),
+                        status,
                     };
                 }
 
@@ -177,6 +345,7 @@ pub fn SegmentedSentenceView(
                 } else {
                     Some(i.text_reading_hiragana)
                 };
+                let accent_reading = i.db_reading_hiragana.clone();
                 let db_reading = if i.db_word == i.db_reading_hiragana {
                     None
                 } else {
@@ -192,8 +361,15 @@ pub fn SegmentedSentenceView(
                     text_reading,
                     db_word: i.db_word,
                     db_reading,
+                    accent_reading,
+                    pitch_accents: i.pitch_accents,
                     score: i.score,
+                    frequency_score: i.frequency_score,
+                    status,
                     meanings,
+                    senses: i.senses,
+                    definitions: word_definitions.get(&word_id).cloned().unwrap_or_default(),
+                    inflections: i.inflections,
                     tail: None,
                 })
             })
@@ -261,14 +437,28 @@ pub fn SegmentedSentenceView(
                         let fw_ignore = fw.clone();
                         let fw_class = fw.clone();
 
-                        let meanings_view = fw
-                            .meanings
+                        // the dictionary's senses are more detailed than the segmenter's meanings when available
+                        let meanings_view = if fw.senses.is_empty() {
+                            fw.meanings
+                                .into_iter()
+                                .map(|m| {
+                                    view! {
+                                        <div>{m}</div>
+                                    }
+                                })
+                                .collect_view()
+                                .into_any()
+                        } else {
+                            fw.senses
+                                .into_iter()
+                                .map(|sense| view! { <WordSenseView sense /> })
+                                .collect_view()
+                                .into_any()
+                        };
+                        let definitions_view = fw
+                            .definitions
                             .into_iter()
-                            .map(|m| {
-                                view! {
-                                    <div>{m}</div>
-                                }
-                            })
+                            .map(|definitions| view! { <WordDefinitionsView definitions /> })
                             .collect_view();
                         let accept_button = move || {
                             let accepted = form.read().is_accepted(fw_accept.field_id);
@@ -324,13 +514,30 @@ pub fn SegmentedSentenceView(
                                 </button>
                             }
                         };
+                        let pitch_accents = fw
+                            .pitch_accents
+                            .iter()
+                            .map(|&accent| view! { <PitchAccent reading=fw.accent_reading.clone() accent /> })
+                            .collect_view();
+                        let inflections = (!fw.inflections.is_empty()).then(|| {
+                            view! {
+                                <div class="is-size-7">{fw.db_word.clone()} " → " {fw.inflections.join(" → ")}</div>
+                            }
+                        });
+                        let common_badge = lbr_core::word_priority::is_common(fw.frequency_score).then(|| {
+                            view! {
+                                <span class="tag is-success is-light ml-1" title=format!("Frequency score {}", fw.frequency_score)>
+                                    "common"
+                                </span>
+                            }
+                        });
                         let word = if fw.db_reading.is_some() {
                             view! {
-                                <div>{fw.db_word} " (" {fw.db_reading} ") [" {fw.score} "]"</div>
+                                <div>{fw.db_word} " (" {fw.db_reading} ") [" {fw.score} "]" {common_badge}</div>
                             }.into_any()
                         } else {
                             view! {
-                                <div>{fw.db_word} " [" {fw.score} "]"</div>
+                                <div>{fw.db_word} " [" {fw.score} "]" {common_badge}</div>
                             }.into_any()
                         };
                         let override_reading: NodeRef<html::Input> = NodeRef::new();
@@ -353,6 +560,8 @@ pub fn SegmentedSentenceView(
                             >
                                 <div>{before}<b>{fw.text_word}</b>{after}</div>
                                 {word}
+                                <div>{pitch_accents}</div>
+                                {inflections}
                                 <input
                                     class="input"
                                     type="text"
@@ -360,6 +569,7 @@ pub fn SegmentedSentenceView(
                                     node_ref=override_reading
                                 />
                                 <div>{meanings_view}</div>
+                                {definitions_view}
                                 <div>
                                     {accept_button}
                                     {accept_reading_button}
@@ -369,10 +579,15 @@ pub fn SegmentedSentenceView(
                             </div>
                         }.into_any()
                     },
-                    FormWordOr::Ignored { text_word, .. } => {
+                    FormWordOr::Hidden { text_word, status, .. } => {
                         if group_size == 1 {
+                            let status = if status == res::WordStatus::Known {
+                                "(known)"
+                            } else {
+                                "(ignored)"
+                            };
                             view! {
-                                <div>{text_word} (ignored)</div>
+                                <div>{text_word} {status}</div>
                             }
                             .into_any()
                         } else {
@@ -403,7 +618,9 @@ pub fn SegmentedSentenceView(
     let accept_sentence = sentence.clone();
     let accept_sentence = Action::new(move |_| {
         let client = get_client();
-        let req = form.get().finish(accept_sentence.clone());
+        let req = form
+            .get()
+            .finish(accept_sentence.clone(), ruby.clone(), timestamps);
         tracing::info!("finished");
         let on_successful_accept = on_successful_accept.clone();
         async move {
@@ -454,14 +671,23 @@ impl Form {
     fn init(form_words: &[Vec<FormWordOr>]) -> Self {
         let mut accepted = Vec::new();
         let mut covered_idx = 0;
-        for form_word in form_words.iter().flat_map(|fw| fw.iter()) {
-            if let FormWordOr::FormWord(form_word) = form_word {
-                if form_word.range.start >= covered_idx {
-                    // accept most likely interpretation
-                    accepted.push(form_word.clone());
-                    covered_idx = form_word.range.end;
+        for group in form_words {
+            let mut group_words = group.iter().filter_map(|fw| match fw {
+                FormWordOr::FormWord(form_word) if form_word.range.start >= covered_idx => {
+                    Some(form_word)
                 }
-            }
+                _ => None,
+            });
+            let Some(most_likely) = group_words.next() else {
+                continue;
+            };
+            // accept an interpretation the user is learning, or the most likely interpretation
+            let form_word = std::iter::once(most_likely)
+                .chain(group_words)
+                .find(|fw| fw.status == res::WordStatus::Learning)
+                .unwrap_or(most_likely);
+            accepted.push(form_word.clone());
+            covered_idx = form_word.range.end;
         }
         Self {
             accepted,
@@ -555,7 +781,12 @@ impl Form {
         self.ignore_words.remove(&form_word.word_id);
     }
 
-    fn finish(&self, sentence: String) -> req::SegmentedSentence {
+    fn finish(
+        &self,
+        sentence: String,
+        ruby: Vec<res::Ruby>,
+        timestamps: Option<res::Timestamps>,
+    ) -> req::SegmentedSentence {
         let words = self
             .accepted
             .iter()
@@ -576,6 +807,8 @@ impl Form {
             sentence,
             words,
             ignore_words: self.ignore_words.clone(),
+            ruby,
+            timestamps,
         }
     }
 }
@@ -594,18 +827,23 @@ fn sort_by_length(a: &FormWordOr, b: &FormWordOr) -> Ordering {
 fn sort_by_score(a: &FormWordOr, b: &FormWordOr) -> Ordering {
     match (a, b) {
         (FormWordOr::FormWord(a), FormWordOr::FormWord(b)) => {
-            a.score.cmp(&b.score).reverse().then_with(|| {
-                // 中 readings are usually scored the same but なか is by far the most common one
-                if a.db_word == "中" && b.db_word == "中" {
-                    if a.text_reading.as_deref() == Some("なか") {
-                        Ordering::Less
+            // the segmenter's score first, then how common the words are in general
+            a.score
+                .cmp(&b.score)
+                .reverse()
+                .then(a.frequency_score.cmp(&b.frequency_score).reverse())
+                .then_with(|| {
+                    // 中 readings are usually scored the same but なか is by far the most common one
+                    if a.db_word == "中" && b.db_word == "中" {
+                        if a.text_reading.as_deref() == Some("なか") {
+                            Ordering::Less
+                        } else {
+                            Ordering::Greater
+                        }
                     } else {
-                        Ordering::Greater
+                        Ordering::Equal
                     }
-                } else {
-                    Ordering::Equal
-                }
-            })
+                })
         }
         (FormWordOr::FormWord(_), _) => Ordering::Less,
         (_, FormWordOr::FormWord(_)) => Ordering::Greater,
@@ -0,0 +1,231 @@
+//! Components for decks.
+
+use crate::{context::get_client, error::WebResult};
+use lbr_api::{request as req, response as res};
+use leptos::prelude::*;
+use send_wrapper::SendWrapper;
+
+/// Lists the cards a deck would be generated with, each card can be opened to see how it looks in Anki.
+#[component]
+pub fn PreviewCards(preview: res::DeckPreview) -> impl IntoView {
+    let res::DeckPreview {
+        word_cards,
+        kanji_cards,
+    } = preview;
+    let opened = RwSignal::new(None::<(res::CardKind, i32)>);
+
+    let card_rows = move |cards: Vec<res::PreviewCard>| {
+        cards
+            .into_iter()
+            .map(|card| {
+                let res::PreviewCard {
+                    kind,
+                    card_id,
+                    text,
+                    count,
+                    example,
+                    question,
+                    answer,
+                    css,
+                } = card;
+                let key = (kind, card_id);
+                let is_opened = move || opened.get() == Some(key);
+                let rendered = move || {
+                    is_opened().then(|| {
+                        view! {
+                            <tr>
+                                <td colspan=3>
+                                    <style>{css.clone()}</style>
+                                    <div class="block card p-4" lang="ja" inner_html=question.clone()></div>
+                                    <div class="block card p-4" lang="ja" inner_html=answer.clone()></div>
+                                </td>
+                            </tr>
+                        }
+                    })
+                };
+                view! {
+                    <tr
+                        class:is-selected=is_opened
+                        style="cursor: pointer;"
+                        on:click=move |_ev| {
+                            opened.update(|o| *o = if *o == Some(key) { None } else { Some(key) })
+                        }
+                    >
+                        <td lang="ja">{text}</td>
+                        <td>{count}</td>
+                        <td lang="ja">{example}</td>
+                    </tr>
+                    {rendered}
+                }
+            })
+            .collect_view()
+    };
+
+    view! {
+        <div class="block">
+            {format!("{} word cards and {} kanji cards", word_cards.len(), kanji_cards.len())}
+        </div>
+        <h4 class="subtitle">"Word cards"</h4>
+        <table class="table is-fullwidth is-hoverable">
+            <thead>
+                <tr>
+                    <th>"Word"</th>
+                    <th>"Count"</th>
+                    <th>"Sentence"</th>
+                </tr>
+            </thead>
+            <tbody>{card_rows(word_cards)}</tbody>
+        </table>
+        <h4 class="subtitle">"Kanji cards"</h4>
+        <table class="table is-fullwidth is-hoverable">
+            <thead>
+                <tr>
+                    <th>"Kanji"</th>
+                    <th>"Count"</th>
+                    <th>"Example word"</th>
+                </tr>
+            </thead>
+            <tbody>{card_rows(kanji_cards)}</tbody>
+        </table>
+    }
+}
+
+/// Edits the styling and card templates used for a deck's word or kanji notes.
+#[component]
+pub fn NoteTypeEditor(deck_id: i32, kind: res::CardKind, note_type: res::DeckNoteType) -> impl IntoView {
+    let res::DeckNoteType {
+        note_type,
+        custom,
+        fields,
+    } = note_type;
+    let css = RwSignal::new(note_type.css);
+    let templates = RwSignal::new(note_type.templates);
+    let custom = RwSignal::new(custom);
+
+    // saves the edited note type, or goes back to the default one if none is given
+    let save_act = Action::new(move |note_type: &Option<req::NoteType>| {
+        let client = get_client();
+        let update = req::UpdateNoteType {
+            kind,
+            note_type: note_type.clone(),
+        };
+        SendWrapper::new(async move {
+            client.update_note_type(deck_id, &update).await?;
+            let note_types = client.get_note_types(deck_id).await?;
+            let res::DeckNoteType {
+                note_type,
+                custom: is_custom,
+                ..
+            } = match kind {
+                res::CardKind::Word => note_types.word,
+                res::CardKind::Kanji => note_types.kanji,
+            };
+            css.set(note_type.css);
+            templates.set(note_type.templates);
+            custom.set(is_custom);
+            WebResult::Ok("Saved note type!")
+        })
+    });
+
+    let template_list = move || {
+        templates
+            .get()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, template)| {
+                view! {
+                    <div class="box">
+                        <label class="label">
+                            "Name"
+                            <input
+                                class="input"
+                                prop:value=template.name
+                                on:change=move |ev| {
+                                    templates.update(|t| t[idx].name = event_target_value(&ev))
+                                }
+                            />
+                        </label>
+                        <label class="label">
+                            "Front"
+                            <textarea
+                                class="textarea is-family-monospace"
+                                prop:value=template.question
+                                on:change=move |ev| {
+                                    templates.update(|t| t[idx].question = event_target_value(&ev))
+                                }
+                            ></textarea>
+                        </label>
+                        <label class="label">
+                            "Back"
+                            <textarea
+                                class="textarea is-family-monospace"
+                                prop:value=template.answer
+                                on:change=move |ev| {
+                                    templates.update(|t| t[idx].answer = event_target_value(&ev))
+                                }
+                            ></textarea>
+                        </label>
+                        <button
+                            class="button is-small is-danger"
+                            on:click=move |_ev| templates.update(|t| { t.remove(idx); })
+                        >
+                            "Remove template"
+                        </button>
+                    </div>
+                }
+            })
+            .collect_view()
+    };
+
+    view! {
+        <div class="block">
+            {move || if custom.get() { "Using a custom note type." } else { "Using the default note type." }}
+            " Available fields: "
+            <span class="is-family-monospace">{fields.join(", ")}</span>
+        </div>
+        <label class="label">
+            "Styling"
+            <textarea
+                class="textarea is-family-monospace"
+                prop:value=move || css.get()
+                on:change=move |ev| css.set(event_target_value(&ev))
+            ></textarea>
+        </label>
+        {template_list}
+        <div class="buttons">
+            <button
+                class="button"
+                on:click=move |_ev| {
+                    templates.update(|t| {
+                        t.push(res::CardTemplate {
+                            name: format!("Card {}", t.len() + 1),
+                            question: String::new(),
+                            answer: "{{FrontSide}}<hr id=answer>".to_string(),
+                        })
+                    })
+                }
+            >
+                "Add template"
+            </button>
+            <button
+                class="button is-primary"
+                on:click=move |_ev| {
+                    save_act.dispatch(Some(req::NoteType {
+                        css: css.get(),
+                        templates: templates.get(),
+                    }));
+                }
+            >
+                "Save"
+            </button>
+            <button class="button" on:click=move |_ev| { save_act.dispatch(None); }>
+                "Reset to default"
+            </button>
+        </div>
+        {move || match save_act.value().get() {
+            Some(Ok(message)) => view! { <div>{message}</div> }.into_any(),
+            Some(Err(err)) => view! { <div class="has-text-danger">{err.to_string()}</div> }.into_any(),
+            None => ().into_any(),
+        }}
+    }
+}
@@ -0,0 +1,198 @@
+//! Components for reading a source.
+
+use crate::{context::get_client, error::WebResult, utils};
+use lbr_api::{request as req, response as res};
+use leptos::prelude::*;
+use send_wrapper::SendWrapper;
+use std::collections::HashMap;
+
+/// Renders a sentence as part of continuous text with furigana, with its words coloured by status.
+/// Clicking a word selects it.
+#[component]
+pub fn ReaderSentence(
+    sentence: res::SentenceDetails,
+    selected_word: RwSignal<Option<res::SentenceWord>>,
+    word_statuses: RwSignal<HashMap<i32, res::WordStatus>>,
+) -> impl IntoView {
+    let text = sentence.sentence;
+    let mut words = sentence.words;
+    words.sort_unstable_by_key(|w| w.idx_start);
+
+    let mut pieces = Vec::new();
+    let mut idx = 0;
+    for word in words {
+        let (start, end) = (word.idx_start as usize, word.idx_end as usize);
+        if start < idx || text.get(start..end).is_none() {
+            // overlaps with the previous word
+            continue;
+        }
+        if idx < start {
+            pieces.push(text[idx..start].to_string().into_any());
+        }
+        let word_text = furigana_view(&text[start..end], &word);
+        let word_id = word.word_id;
+        let class = move || {
+            let status = word_statuses
+                .read()
+                .get(&word_id)
+                .copied()
+                .unwrap_or(res::WordStatus::Unknown);
+            format!("is-clickable {}", status_class(status))
+        };
+        pieces.push(
+            view! {
+                <span class=class on:click=move |_ev| selected_word.set(Some(word.clone()))>
+                    {word_text}
+                </span>
+            }
+            .into_any(),
+        );
+        idx = end;
+    }
+    if idx < text.len() {
+        pieces.push(text[idx..].to_string().into_any());
+    }
+
+    view! {
+        <span id=format!("sentence-{}", sentence.id)>
+            {pieces}
+            {sentence.timestamps.map(|t| view! { <sup class="has-text-grey">{utils::format_timestamps(t)}</sup> })}
+        </span>
+    }
+}
+
+// splits the word in the sentence into plain text and ruby
+fn furigana_view(word_text: &str, word: &res::SentenceWord) -> Vec<AnyView> {
+    let reading = word.sentence_word_reading.as_deref().unwrap_or_default();
+    let mut furigana = word.furigana.clone();
+    furigana.sort_unstable_by_key(|f| f.word_start_idx);
+
+    let mut pieces = Vec::new();
+    let mut idx = 0;
+    for f in furigana {
+        let (word_start, word_end) = (f.word_start_idx as usize, f.word_end_idx as usize);
+        let (Some(base), Some(rt)) = (
+            word_text.get(word_start..word_end),
+            reading.get(f.reading_start_idx as usize..f.reading_end_idx as usize),
+        ) else {
+            continue;
+        };
+        if word_start < idx {
+            continue;
+        }
+        if idx < word_start {
+            pieces.push(word_text[idx..word_start].to_string().into_any());
+        }
+        pieces.push(
+            view! {
+                <ruby>{base.to_string()}<rt>{rt.to_string()}</rt></ruby>
+            }
+            .into_any(),
+        );
+        idx = word_end;
+    }
+    if idx < word_text.len() {
+        pieces.push(word_text[idx..].to_string().into_any());
+    }
+    pieces
+}
+
+fn status_class(status: res::WordStatus) -> &'static str {
+    match status {
+        res::WordStatus::Unknown => "has-background-danger-light",
+        res::WordStatus::Learning => "has-background-warning-light",
+        res::WordStatus::Known => "has-background-success-light",
+        res::WordStatus::Ignored => "has-text-grey",
+    }
+}
+
+/// Shows the meanings of the selected word and lets the user change its status.
+#[component]
+pub fn ReaderWordDetails(
+    selected_word: RwSignal<Option<res::SentenceWord>>,
+    word_statuses: RwSignal<HashMap<i32, res::WordStatus>>,
+) -> impl IntoView {
+    let set_status_act = Action::new(move |update: &req::WordStatusUpdate| {
+        let client = get_client();
+        let update = *update;
+        async move {
+            SendWrapper::new(client.set_word_status(&update)).await?;
+            word_statuses.update(|ws| {
+                ws.insert(update.word_id, update.status);
+            });
+            WebResult::Ok(())
+        }
+    });
+
+    move || {
+        selected_word.get().map(|word| {
+            let word_id = word.word_id;
+            let current_status = move || {
+                word_statuses
+                    .read()
+                    .get(&word_id)
+                    .copied()
+                    .unwrap_or(res::WordStatus::Unknown)
+            };
+            let status_buttons = [
+                res::WordStatus::Unknown,
+                res::WordStatus::Learning,
+                res::WordStatus::Known,
+                res::WordStatus::Ignored,
+            ]
+            .into_iter()
+            .map(|status| {
+                let update = req::WordStatusUpdate {
+                    word_id,
+                    status,
+                    level: None,
+                };
+                view! {
+                    <button
+                        class="button is-small"
+                        class:is-link=move || current_status() == status
+                        on:click=move |_ev| {
+                            set_status_act.dispatch(update);
+                        }
+                    >
+                        {status_name(status)}
+                    </button>
+                }
+            })
+            .collect_view();
+            let translations = word
+                .translations
+                .into_iter()
+                .map(|t| view! { <li>{t}</li> })
+                .collect_view();
+            view! {
+                <div class="box">
+                    <div class="is-size-4">{format!("{} ({})", word.word, word.reading)}</div>
+                    <div class="content">
+                        <ul>{translations}</ul>
+                    </div>
+                    <div class="buttons has-addons">{status_buttons}</div>
+                    {move || {
+                        set_status_act
+                            .value()
+                            .get()
+                            .and_then(Result::err)
+                            .map(|err| view! { <div class="has-text-danger">{err.to_string()}</div> })
+                    }}
+                    <button class="button is-small" on:click=move |_ev| selected_word.set(None)>
+                        "Close"
+                    </button>
+                </div>
+            }
+        })
+    }
+}
+
+fn status_name(status: res::WordStatus) -> &'static str {
+    match status {
+        res::WordStatus::Unknown => "Unknown",
+        res::WordStatus::Learning => "Learning",
+        res::WordStatus::Known => "Known",
+        res::WordStatus::Ignored => "Ignored",
+    }
+}
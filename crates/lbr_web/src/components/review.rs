@@ -0,0 +1,104 @@
+//! Components for reviewing cards.
+
+use crate::{context::get_client, error::WebResult};
+use lbr_api::{request as req, response as res};
+use leptos::prelude::*;
+use send_wrapper::SendWrapper;
+
+/// Shows the cards one at a time and sends the user's rating for each.
+#[component]
+pub fn ReviewSession(cards: Vec<res::ReviewCard>) -> impl IntoView {
+    let total = cards.len();
+    let cards = StoredValue::new(cards);
+    let current = RwSignal::new(0_usize);
+    let show_answer = RwSignal::new(false);
+
+    let review_act = Action::new(move |rating: &req::ReviewRating| {
+        let client = get_client();
+        let rating = *rating;
+        let card = cards.with_value(|cs| cs.get(current.get_untracked()).cloned());
+        async move {
+            let Some(card) = card else {
+                return WebResult::Ok(());
+            };
+            let review = req::Review {
+                kind: card.kind,
+                card_id: card.card_id,
+                rating,
+            };
+            SendWrapper::new(client.review(&review)).await?;
+            show_answer.set(false);
+            current.update(|c| *c += 1);
+            WebResult::Ok(())
+        }
+    });
+
+    let card_view = move || {
+        let idx = current.get();
+        let Some(card) = cards.with_value(|cs| cs.get(idx).cloned()) else {
+            return view! { <div class="block">"No more cards are due."</div> }.into_any();
+        };
+        let side = move || {
+            if show_answer.get() {
+                card.answer.clone()
+            } else {
+                card.question.clone()
+            }
+        };
+        let rating_buttons = move || {
+            [
+                (req::ReviewRating::Again, "Again", "is-danger"),
+                (req::ReviewRating::Hard, "Hard", "is-warning"),
+                (req::ReviewRating::Good, "Good", "is-success"),
+                (req::ReviewRating::Easy, "Easy", "is-info"),
+            ]
+            .into_iter()
+            .map(|(rating, label, class)| {
+                view! {
+                    <button
+                        class=format!("button {class}")
+                        disabled=move || review_act.pending().get()
+                        on:click=move |_ev| {
+                            review_act.dispatch(rating);
+                        }
+                    >
+                        {label}
+                    </button>
+                }
+            })
+            .collect_view()
+        };
+        view! {
+            <div class="block has-text-grey">
+                {format!("{}/{total}", idx + 1)}
+                {card.new.then_some(" (new)")}
+            </div>
+            <style>{card.css.clone()}</style>
+            <div class="block card p-4" lang="ja" inner_html=side></div>
+            <div class="buttons">
+                {move || {
+                    if show_answer.get() {
+                        rating_buttons().into_any()
+                    } else {
+                        view! {
+                            <button class="button is-primary" on:click=move |_ev| show_answer.set(true)>
+                                "Show answer"
+                            </button>
+                        }
+                        .into_any()
+                    }
+                }}
+            </div>
+            {move || {
+                review_act
+                    .value()
+                    .get()
+                    .and_then(Result::err)
+                    .map(|err| view! { <div class="has-text-danger">{err.to_string()}</div> })
+            }}
+        }
+        .into_any()
+    };
+
+    card_view
+}
@@ -180,6 +180,21 @@ impl Client {
         Ok(source)
     }
 
+    pub async fn get_source_reader(&self, id: i32) -> WebResult<res::SourceReader> {
+        tracing::info!("Fetching source {id} for reading");
+
+        let res = Request::get(&format!("/api/sources/{id}/reader"))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let reader = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched source {id} for reading");
+        Ok(reader)
+    }
+
     pub async fn update_source(&self, id: i32, name: &str) -> WebResult<()> {
         tracing::info!("Updating source {id}");
 
@@ -294,12 +309,18 @@ impl Client {
         id: i32,
         name: &str,
         sources: &[req::IncludedSource],
+        exclude_known: bool,
+        rules: req::DeckRules,
+        card_types: req::WordCardTypes,
     ) -> WebResult<()> {
         tracing::info!("Updating sources for deck {id}");
 
         let json = serde_json::to_string(&req::UpdateDeck {
             name: name.into(),
             included_sources: sources.into(),
+            exclude_known,
+            rules,
+            card_types,
         })
         .map_err(WebError::from)?;
         let res = Request::post(&format!("/api/decks/{id}"))
@@ -315,10 +336,127 @@ impl Client {
         Ok(())
     }
 
+    pub async fn get_deck_preview(&self, id: i32) -> WebResult<res::DeckPreview> {
+        tracing::info!("Fetching preview for deck {id}");
+
+        let res = Request::get(&format!("/api/decks/{id}/preview"))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let preview = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched preview for deck {id}");
+        Ok(preview)
+    }
+
+    pub async fn get_note_types(&self, id: i32) -> WebResult<res::DeckNoteTypes> {
+        tracing::info!("Fetching note types for deck {id}");
+
+        let res = Request::get(&format!("/api/decks/{id}/note-types"))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let note_types = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched note types for deck {id}");
+        Ok(note_types)
+    }
+
+    pub async fn update_note_type(
+        &self,
+        deck_id: i32,
+        update: &req::UpdateNoteType,
+    ) -> WebResult<()> {
+        tracing::info!("Updating a note type for deck {deck_id}");
+
+        let json = serde_json::to_string(update).map_err(WebError::from)?;
+        let res = Request::post(&format!("/api/decks/{deck_id}/note-types"))
+            .credentials(RequestCredentials::Include)
+            .body(json)
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+
+        tracing::info!("Updated a note type for deck {deck_id}");
+        Ok(())
+    }
+
     pub fn generate_deck_url(&self, id: i32, filename: &str) -> String {
         format!("/api/decks/{id}/generate/{filename}")
     }
 
+    pub async fn reroll_example(&self, deck_id: i32, reroll: &req::Reroll) -> WebResult<()> {
+        tracing::info!("Rerolling the example for card {}", reroll.card_id);
+
+        let json = serde_json::to_string(reroll).map_err(WebError::from)?;
+        let res = Request::post(&format!("/api/decks/{deck_id}/reroll"))
+            .credentials(RequestCredentials::Include)
+            .body(json)
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+
+        tracing::info!("Rerolled the example for card {}", reroll.card_id);
+        Ok(())
+    }
+
+    pub async fn get_due_cards(&self, deck_id: i32) -> WebResult<Vec<res::ReviewCard>> {
+        tracing::info!("Fetching due cards for deck {deck_id}");
+
+        let res = Request::get(&format!("/api/decks/{deck_id}/reviews"))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let cards = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched due cards for deck {deck_id}");
+        Ok(cards)
+    }
+
+    pub async fn review(&self, review: &req::Review) -> WebResult<()> {
+        tracing::info!("Reviewing card {}", review.card_id);
+
+        let json = serde_json::to_string(review).map_err(WebError::from)?;
+        let res = Request::post("/api/reviews")
+            .credentials(RequestCredentials::Include)
+            .body(json)
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+
+        tracing::info!("Reviewed card {}", review.card_id);
+        Ok(())
+    }
+
+    pub async fn import_review_history(&self, data: &[u8]) -> WebResult<res::ReviewHistoryImport> {
+        tracing::info!("Importing review history");
+
+        let res = Request::post("/api/reviews/import")
+            .credentials(RequestCredentials::Include)
+            .body(js_sys::Uint8Array::from(data))
+            .header("Content-Type", "application/octet-stream")
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let import = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Imported review history");
+        Ok(import)
+    }
+
     pub async fn delete_deck(&self, id: i32) -> WebResult<()> {
         tracing::info!("Deleting deck {id}");
 
@@ -333,6 +471,55 @@ impl Client {
         Ok(())
     }
 
+    pub async fn get_word_statuses(&self) -> WebResult<Vec<res::UserWord>> {
+        tracing::info!("Fetching word statuses");
+
+        let res = Request::get("/api/words/statuses")
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let user_words = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched word statuses");
+        Ok(user_words)
+    }
+
+    pub async fn set_word_status(&self, update: &req::WordStatusUpdate) -> WebResult<()> {
+        tracing::info!("Setting the status of word {}", update.word_id);
+
+        let json = serde_json::to_string(update).map_err(WebError::from)?;
+        let res = Request::post("/api/words/status")
+            .credentials(RequestCredentials::Include)
+            .body(json)
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+
+        tracing::info!("Set the status of word {}", update.word_id);
+        Ok(())
+    }
+
+    pub async fn set_word_statuses(&self, updates: &[req::WordStatusUpdate]) -> WebResult<()> {
+        tracing::info!("Setting the statuses of {} words", updates.len());
+
+        let json = serde_json::to_string(updates).map_err(WebError::from)?;
+        let res = Request::post("/api/words/statuses")
+            .credentials(RequestCredentials::Include)
+            .body(json)
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+
+        tracing::info!("Set the statuses of {} words", updates.len());
+        Ok(())
+    }
+
     pub async fn get_ignored_words(&self) -> WebResult<Vec<res::IgnoredWord>> {
         tracing::info!("Fetching ignored words");
 
@@ -362,19 +549,124 @@ impl Client {
         Ok(())
     }
 
-    pub async fn segment_paragraph(
+    pub async fn get_frequency_lists(&self) -> WebResult<Vec<res::FrequencyList>> {
+        tracing::info!("Fetching frequency lists");
+
+        let res = Request::get("/api/words/frequency-lists")
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let frequency_lists = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched frequency lists");
+        Ok(frequency_lists)
+    }
+
+    pub async fn get_dictionaries(&self) -> WebResult<Vec<res::Dictionary>> {
+        tracing::info!("Fetching dictionaries");
+
+        let res = Request::get("/api/words/dictionaries")
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let dictionaries = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched dictionaries");
+        Ok(dictionaries)
+    }
+
+    pub async fn set_dictionaries(&self, dictionary_ids: &[i32]) -> WebResult<()> {
+        tracing::info!("Setting the enabled dictionaries");
+
+        let json = serde_json::to_string(&req::EnabledDictionaries {
+            dictionary_ids: dictionary_ids.to_vec(),
+        })
+        .map_err(WebError::from)?;
+        let res = Request::post("/api/words/dictionaries")
+            .credentials(RequestCredentials::Include)
+            .body(json)
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+
+        tracing::info!("Set the enabled dictionaries");
+        Ok(())
+    }
+
+    pub async fn get_kanji(&self, kanji: &str) -> WebResult<res::Kanji> {
+        tracing::info!("Fetching kanji {kanji}");
+
+        let res = Request::get(&format!("/api/kanji/{kanji}"))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let kanji_info = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched kanji {kanji}");
+        Ok(kanji_info)
+    }
+
+    pub async fn import_file(
         &self,
         source_id: i32,
-        paragraph: &str,
-    ) -> WebResult<res::SegmentedParagraph> {
-        tracing::info!("Segmenting paragraph {paragraph}");
+        filename: &str,
+        data: &[u8],
+    ) -> WebResult<Vec<i32>> {
+        tracing::info!("Importing {filename} into source {source_id}");
+
+        // see req::Import
+        let encoded_filename = String::from(js_sys::encode_uri_component(filename));
+        let res = Request::post(&format!(
+            "/api/sources/{source_id}/import?filename={encoded_filename}"
+        ))
+        .credentials(RequestCredentials::Include)
+        .body(js_sys::Uint8Array::from(data))
+        .header("Content-Type", "application/octet-stream")
+        .send()
+        .await
+        .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let job_ids = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Imported {filename} into source {source_id}");
+        Ok(job_ids)
+    }
+
+    pub async fn get_source_jobs(
+        &self,
+        source_id: i32,
+    ) -> WebResult<Vec<res::SegmentationJobSummary>> {
+        tracing::info!("Fetching segmentation jobs for source {source_id}");
+
+        let res = Request::get(&format!("/api/sources/{source_id}/jobs"))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let jobs = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched segmentation jobs for source {source_id}");
+        Ok(jobs)
+    }
+
+    pub async fn create_segmentation_job(&self, source_id: i32, paragraph: &str) -> WebResult<i32> {
+        tracing::info!("Creating segmentation job for paragraph {paragraph}");
 
         let json = serde_json::to_string(&req::Paragraph {
             source_id,
             paragraph: paragraph.into(),
         })
         .map_err(WebError::from)?;
-        let res = Request::post("/api/segment")
+        let res = Request::post("/api/segment/jobs")
             .credentials(RequestCredentials::Include)
             .body(json)
             .header("Content-Type", "application/json")
@@ -382,10 +674,43 @@ impl Client {
             .await
             .map_err(WebError::from)?;
         Self::assert_success(&res).await?;
-        let segmented = res.json().await.map_err(WebError::from)?;
+        let id = read_i32(&res).await?;
 
-        tracing::info!("Segmented paragraph {paragraph}");
-        Ok(segmented)
+        tracing::info!("Created segmentation job {id}");
+        Ok(id)
+    }
+
+    pub async fn get_segmentation_job(&self, id: i32) -> WebResult<res::SegmentationJob> {
+        tracing::info!("Fetching segmentation job {id}");
+
+        let res = Request::get(&format!("/api/segment/jobs/{id}"))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let job = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched segmentation job {id}");
+        Ok(job)
+    }
+
+    pub async fn get_segmentation_job_results(
+        &self,
+        id: i32,
+    ) -> WebResult<res::SegmentedParagraph> {
+        tracing::info!("Fetching results for segmentation job {id}");
+
+        let res = Request::get(&format!("/api/segment/jobs/{id}/results"))
+            .credentials(RequestCredentials::Include)
+            .send()
+            .await
+            .map_err(WebError::from)?;
+        Self::assert_success(&res).await?;
+        let results = res.json().await.map_err(WebError::from)?;
+
+        tracing::info!("Fetched results for segmentation job {id}");
+        Ok(results)
     }
 
     pub async fn segment_sentence(&self, sentence_id: i32) -> WebResult<res::SegmentedSentence> {
@@ -79,10 +79,18 @@ pub fn Content() -> impl IntoView {
                         path=(StaticSegment("source"), ParamSegment("source_id"), StaticSegment("add-sentences"))
                         view=SourceAddSentences
                     />
+                    <Route
+                        path=(StaticSegment("source"), ParamSegment("source_id"), StaticSegment("import"))
+                        view=SourceImport
+                    />
                     <Route
                         path=(StaticSegment("source"), ParamSegment("source_id"), StaticSegment("sentences"))
                         view=SourceSentences
                     />
+                    <Route
+                        path=(StaticSegment("source"), ParamSegment("source_id"), StaticSegment("reader"))
+                        view=SourceReader
+                    />
                     <Route
                         path=(StaticSegment("sentence"), ParamSegment("sentence_id"))
                         view=Sentence
@@ -95,10 +103,18 @@ pub fn Content() -> impl IntoView {
                         path=(StaticSegment("deck"), ParamSegment("deck_id"))
                         view=Deck
                     />
+                    <Route
+                        path=(StaticSegment("deck"), ParamSegment("deck_id"), StaticSegment("review"))
+                        view=DeckReview
+                    />
                     <Route
                         path=StaticSegment("ignored-words")
                         view=IgnoredWords
                     />
+                    <Route
+                        path=StaticSegment("dictionaries")
+                        view=Dictionaries
+                    />
                     <Route
                         path=StaticSegment("login")
                         view=Login
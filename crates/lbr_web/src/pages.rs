@@ -1,20 +1,24 @@
 //! Top level pages.
 
 use crate::{
-    components::{analysis::*, *},
+    components::{analysis::*, deck::*, reader::*, review::*, *},
     context::{get_client, get_session},
     error::{WebError, WebResult},
     utils,
 };
 use lbr_api::{request as req, response as res};
 use leptos::{
-    html::{Input, Textarea},
+    html::{Input, Select, Textarea},
     leptos_dom::helpers::TimeoutHandle,
     prelude::*,
 };
 use leptos_router::{components::*, params::Params};
 use send_wrapper::SendWrapper;
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
 #[component]
 pub fn Home() -> impl IntoView {
@@ -72,6 +76,8 @@ pub fn Home() -> impl IntoView {
                 view! {
                     <h2 class="subtitle is-6 has-text-weight-bold">"Other"</h2>
                     <A href="/ignored-words">"Ignored words"</A>
+                    <br/>
+                    <A href="/dictionaries">"Dictionaries"</A>
                 }
                 .into_any()
             })
@@ -193,15 +199,23 @@ pub fn Source() -> impl IntoView {
     // source
     let source_content = move |source: res::Source| {
         let add_sentences_href = format!("/source/{source_id}/add-sentences");
+        let import_href = format!("/source/{source_id}/import");
         let sentences_href = format!("/source/{source_id}/sentences");
+        let reader_href = format!("/source/{source_id}/reader");
         view! {
             <h2 class="subtitle">{format!("Viewing source {}", source.name)}</h2>
             <div class="block">
                 <A href=add_sentences_href>"Add sentences"</A>
             </div>
+            <div class="block">
+                <A href=import_href>"Import a file"</A>
+            </div>
             <div class="block">
                 <A href=sentences_href>"View sentences"</A>
             </div>
+            <div class="block">
+                <A href=reader_href>"Read"</A>
+            </div>
             <div class="block">
                 <h3 class="subtitle">"Edit source"</h3>
                 <form>
@@ -262,6 +276,7 @@ pub fn SourceSentences() -> impl IntoView {
                 view! {
                     <li>
                         <A href=format!("/sentence/{}", s.id)>{s.sentence}</A>
+                        {s.timestamps.map(|t| view! { " " <span class="has-text-grey">{utils::format_timestamps(t)}</span> })}
                     </li>
                 }
             })
@@ -297,6 +312,63 @@ pub fn SourceSentences() -> impl IntoView {
     WebResult::Ok(view)
 }
 
+#[derive(Debug, Clone, PartialEq, Params)]
+pub struct SourceReaderParams {
+    source_id: Option<i32>,
+}
+#[component]
+pub fn SourceReader() -> impl IntoView {
+    let SourceReaderParams { source_id } = utils::params()?;
+    let source_id = source_id.expect("failed to get source_id");
+    tracing::info!("Rendering SourceReader {source_id}");
+
+    // resources
+    let reader_res = utils::logged_in_resource!(get_source_reader(source_id));
+    let selected_word = RwSignal::new(None::<res::SentenceWord>);
+    let word_statuses = RwSignal::new(HashMap::<i32, res::WordStatus>::new());
+
+    // reader
+    let reader_content = move |reader: res::SourceReader| {
+        word_statuses.set(
+            reader
+                .sentences
+                .iter()
+                .flat_map(|s| &s.words)
+                .map(|w| (w.word_id, w.status))
+                .collect(),
+        );
+        let sentences = reader
+            .sentences
+            .into_iter()
+            .map(|sentence| view! { <ReaderSentence sentence selected_word word_statuses /> })
+            .collect_view();
+        view! {
+            <h2 class="subtitle">
+                <A href=format!("/source/{source_id}")>{reader.name}</A>
+            </h2>
+            <div class="columns">
+                <div class="column is-two-thirds">
+                    <div class="is-size-4" lang="ja">{sentences}</div>
+                </div>
+                <div class="column">
+                    <ReaderWordDetails selected_word word_statuses />
+                </div>
+            </div>
+        }
+    };
+    let reader_view = move |reader: Option<_>| match reader {
+        Some(reader) => reader_content(reader).into_any(),
+        None => utils::loading_fallback("Loading source...").into_any(),
+    };
+
+    let view = view! {
+        <LoginGuard require_login=true>
+            <ResourceView resource=reader_res view=reader_view />
+        </LoginGuard>
+    };
+    WebResult::Ok(view)
+}
+
 #[derive(Debug, Clone, PartialEq, Params)]
 pub struct SourceAddSentencesParams {
     source_id: Option<i32>,
@@ -314,7 +386,7 @@ pub fn SourceAddSentences() -> impl IntoView {
             .expect("failed to get analyse_textarea_ref")
             .value();
         let client = get_client();
-        async move { SendWrapper::new(client.segment_paragraph(source_id, &textarea_val)).await }
+        async move { SendWrapper::new(client.create_segmentation_job(source_id, &textarea_val)).await }
     });
 
     // source
@@ -339,19 +411,14 @@ pub fn SourceAddSentences() -> impl IntoView {
     };
 
     // analysis
-    let analysis_content = move |paragraph: res::SegmentedParagraph| {
-        view! { <SegmentedParagraphView source_id=source_id paragraph /> }
-    };
-    let analysis_view = move |segmented: Option<res::SegmentedParagraph>| match segmented {
-        Some(segments) => analysis_content(segments).into_any(),
-        None => view! { <div>"Nothing analysed yet"</div> }.into_any(),
-    };
     let analysis = move || {
         let view = if analyse_act.pending().get() {
-            view! { <div>"Analysing..."</div> }.into_any()
+            view! { <div>"Submitting..."</div> }.into_any()
         } else {
-            let paragraph = analyse_act.value().get().transpose()?;
-            analysis_view(paragraph).into_any()
+            match analyse_act.value().get().transpose()? {
+                Some(job_id) => view! { <SegmentationJobView source_id job_id /> }.into_any(),
+                None => view! { <div>"Nothing analysed yet"</div> }.into_any(),
+            }
         };
         WebResult::Ok(view)
     };
@@ -376,6 +443,158 @@ pub fn SourceAddSentences() -> impl IntoView {
     WebResult::Ok(view)
 }
 
+#[derive(Debug, Clone, PartialEq, Params)]
+pub struct SourceImportParams {
+    source_id: Option<i32>,
+}
+#[component]
+pub fn SourceImport() -> impl IntoView {
+    let SourceImportParams { source_id } = utils::params()?;
+    let source_id = source_id.expect("failed to get source_id");
+    tracing::info!("Rendering SourceImport {source_id}");
+
+    let file_ref = NodeRef::<Input>::new();
+    let import_act = Action::new(move |&()| {
+        let file = file_ref
+            .get()
+            .expect("failed to get file_ref")
+            .files()
+            .and_then(|files| files.get(0));
+        let client = get_client();
+        SendWrapper::new(async move {
+            let file = file.ok_or_else(|| WebError::new("No file selected"))?;
+            let filename = file.name();
+            let buffer = wasm_bindgen_futures::JsFuture::from(file.array_buffer()).await?;
+            let data = js_sys::Uint8Array::new(&buffer).to_vec();
+            client.import_file(source_id, &filename, &data).await
+        })
+    });
+    let selected_job = RwSignal::new(None::<i32>);
+
+    // source
+    let source_res = utils::logged_in_resource!(get_source(source_id));
+    let source_content = move |source: res::Source| {
+        view! {
+            <h2 class="subtitle">
+                <A href=format!("/source/{source_id}")>{source.name}</A>
+            </h2>
+            <div class="block">
+                "Import a book or other text from a plain text file, an Aozora Bunko text file or an EPUB file, "
+                "or the subtitles of a show from an SRT, ASS or WebVTT file. "
+                "The text is split into chapters that can be reviewed one at a time while the rest are being analysed."
+            </div>
+            <div class="file">
+                <label class="file-label">
+                    <input class="file-input" type="file" accept=".txt,.epub,.srt,.ass,.ssa,.vtt" node_ref=file_ref/>
+                    <span class="file-cta">
+                        <span class="file-label">"Choose a file..."</span>
+                    </span>
+                </label>
+            </div>
+            <button class="button is-primary mt-1" on:click=move |_ev| { import_act.dispatch(()); }>
+                "Import"
+            </button>
+        }
+    };
+    let source_view = move |source: Option<res::Source>| match source {
+        Some(source) => source_content(source).into_any(),
+        None => view! { <div>"Loading source..."</div> }.into_any(),
+    };
+    let import_status = move || {
+        let view = if import_act.pending().get() {
+            Some(view! { <div>"Importing..."</div> })
+        } else {
+            import_act.value().get().transpose()?.map(
+                |job_ids| view! { <div>{format!("Imported {} chapters", job_ids.len())}</div> },
+            )
+        };
+        WebResult::Ok(view)
+    };
+
+    // jobs, refetched after each import
+    let jobs_res = Resource::new(
+        move || (get_session().logged_in(), import_act.version().get()),
+        move |(logged_in, _)| {
+            let client = get_client();
+            async move {
+                let jobs = match logged_in {
+                    Some(true) => Some(SendWrapper::new(client.get_source_jobs(source_id)).await?),
+                    _ => None,
+                };
+                WebResult::Ok(jobs)
+            }
+        },
+    );
+    let jobs_content = move |jobs: Vec<res::SegmentationJobSummary>| {
+        let jobs = jobs
+            .into_iter()
+            .map(|job| {
+                let job_id = job.id;
+                let title = job.title.unwrap_or_else(|| format!("Paragraph {job_id}"));
+                let status = match job.status {
+                    res::SegmentationJobStatus::Queued => "queued",
+                    res::SegmentationJobStatus::Running => "analysing",
+                    res::SegmentationJobStatus::Finished => "finished",
+                    res::SegmentationJobStatus::Failed => "failed",
+                };
+                let button_class = move || {
+                    if selected_job.get() == Some(job_id) {
+                        "button is-small is-light"
+                    } else {
+                        "button is-small is-primary"
+                    }
+                };
+                view! {
+                    <li>
+                        <button class=button_class on:click=move |_ev| selected_job.set(Some(job_id))>
+                            {title}
+                        </button>
+                        {format!(
+                            " {status}, {}/{} sentences analysed, {} failed",
+                            job.segmented,
+                            job.sentences,
+                            job.failed,
+                        )}
+                    </li>
+                }
+            })
+            .collect_view();
+        view! {
+            <h3 class="subtitle">"Chapters"</h3>
+            <ul>{jobs}</ul>
+        }
+    };
+    let jobs_view = move |jobs: Option<Vec<res::SegmentationJobSummary>>| match jobs {
+        Some(jobs) => jobs_content(jobs).into_any(),
+        None => view! { <div>"Loading chapters..."</div> }.into_any(),
+    };
+    let job_view = move || {
+        selected_job
+            .get()
+            .map(|job_id| view! { <SegmentationJobView source_id job_id /> })
+    };
+
+    let view = view! {
+        <LoginGuard require_login=true>
+            <div class="block">
+                <ResourceView resource=source_res view=source_view/>
+            </div>
+            <div class="block">
+                <ErrorBoundary fallback={utils::errors_fallback}>
+                    {import_status}
+                </ErrorBoundary>
+            </div>
+            <div class="block">
+                <ResourceView resource=jobs_res view=jobs_view/>
+            </div>
+            <div class="block">
+                {job_view}
+            </div>
+        </LoginGuard>
+    };
+    WebResult::Ok(view)
+}
+
 #[derive(Debug, Clone, PartialEq, Params)]
 pub struct SentenceParams {
     sentence_id: Option<i32>,
@@ -426,7 +645,10 @@ pub fn Sentence() -> impl IntoView {
                         sentence_id=Some(sentence_id)
                         sentence={segmented_sentence.sentence}
                         segments={segmented_sentence.segments}
-                        ignored_words={Arc::new(segmented_sentence.ignored_words)}
+                        ruby={Vec::new()}
+                        timestamps={None}
+                        word_statuses={Arc::new(segmented_sentence.word_statuses)}
+                        word_definitions={Arc::new(segmented_sentence.word_definitions)}
                         on_successful_accept=on_successful_accept
                     />
                 }
@@ -484,6 +706,7 @@ pub fn Sentence() -> impl IntoView {
         view! {
             <div class="block">
                 <div>{sentence.sentence}</div>
+                {sentence.timestamps.map(|t| view! { <div class="has-text-grey">{utils::format_timestamps(t)}</div> })}
             </div>
             <div class="block">
                 <button class="button is-primary" on:click=move |_ev| { reanalyse_act.dispatch(()); }>
@@ -575,6 +798,7 @@ pub fn Deck() -> impl IntoView {
     // resources
     let deck_res = utils::logged_in_resource!(get_deck(deck_id));
     let sources_res = utils::logged_in_resource!(get_sources());
+    let frequency_lists_res = utils::logged_in_resource!(get_frequency_lists());
 
     #[derive(Clone, Copy)]
     struct SourceRefs {
@@ -587,15 +811,104 @@ pub fn Deck() -> impl IntoView {
 
     // actions
     let name_ref = NodeRef::<Input>::new();
+    let exclude_known_ref = NodeRef::<Input>::new();
+    let order_ref = NodeRef::<Select>::new();
+    let max_word_cards_ref = NodeRef::<Input>::new();
+    let max_kanji_cards_ref = NodeRef::<Input>::new();
+    let min_word_frequency_ref = NodeRef::<Input>::new();
+    let max_word_frequency_ref = NodeRef::<Input>::new();
+    let min_word_frequency_score_ref = NodeRef::<Input>::new();
+    let frequency_list_ref = NodeRef::<Select>::new();
+    let max_word_frequency_rank_ref = NodeRef::<Input>::new();
+    let max_kanji_grade_ref = NodeRef::<Input>::new();
+    let min_kanji_jlpt_ref = NodeRef::<Input>::new();
+    let extra_examples_ref = NodeRef::<Input>::new();
+    let example_preference_ref = NodeRef::<Select>::new();
+    let reading_cards_ref = NodeRef::<Input>::new();
+    let production_cards_ref = NodeRef::<Input>::new();
+    let cloze_cards_ref = NodeRef::<Input>::new();
     let (source_refs, set_source_checkbox_refs) = leptos::prelude::signal(Vec::<SourceRefs>::new());
     let (update_result_message, set_update_result_message) =
         leptos::prelude::signal((None::<&'static str>, None::<TimeoutHandle>));
     let update_act = Action::new(move |&()| {
         let client = get_client();
         let name = name_ref.get().expect("failed to get name_ref").value();
+        let exclude_known = exclude_known_ref
+            .get()
+            .expect("failed to get exclude_known_ref")
+            .checked();
+        let order = match order_ref.get().expect("failed to get order_ref").value().as_str() {
+            "appearance" => req::CardOrder::Appearance,
+            "random" => req::CardOrder::Random,
+            "common" => req::CardOrder::Common,
+            "grade" => req::CardOrder::Grade,
+            _ => req::CardOrder::Frequency,
+        };
+        let example_preference = match example_preference_ref
+            .get()
+            .expect("failed to get example_preference_ref")
+            .value()
+            .as_str()
+        {
+            "known_words" => req::ExamplePreference::KnownWords,
+            "source_diversity" => req::ExamplePreference::SourceDiversity,
+            _ => req::ExamplePreference::Shortest,
+        };
+        let frequency_list_id = frequency_list_ref
+            .get()
+            .expect("failed to get frequency_list_ref")
+            .value();
+        let extra_examples = extra_examples_ref
+            .get()
+            .expect("failed to get extra_examples_ref")
+            .value();
+        // empty inputs mean that the rule is not used
+        let optional_number = |node_ref: NodeRef<Input>| -> WebResult<Option<i32>> {
+            let value = node_ref.get().expect("failed to get rule input").value();
+            if value.trim().is_empty() {
+                Ok(None)
+            } else {
+                value.trim().parse().map(Some).map_err(|e| {
+                    WebError::new(format!("Failed to parse rule as number: {e}"))
+                })
+            }
+        };
+        let checked = |node_ref: NodeRef<Input>| {
+            node_ref
+                .get()
+                .expect("failed to get card type input")
+                .checked()
+        };
+        let card_types = req::WordCardTypes {
+            reading: checked(reading_cards_ref),
+            production: checked(production_cards_ref),
+            cloze: checked(cloze_cards_ref),
+        };
         let mut included_sources = Vec::new();
 
         async move {
+            let rules = req::DeckRules {
+                order,
+                max_word_cards: optional_number(max_word_cards_ref)?,
+                max_kanji_cards: optional_number(max_kanji_cards_ref)?,
+                min_word_frequency: optional_number(min_word_frequency_ref)?,
+                max_word_frequency: optional_number(max_word_frequency_ref)?,
+                max_kanji_grade: optional_number(max_kanji_grade_ref)?,
+                min_kanji_jlpt: optional_number(min_kanji_jlpt_ref)?,
+                extra_examples: extra_examples.trim().parse().map_err(|e| {
+                    WebError::new(format!("Failed to parse extra examples as number: {e}"))
+                })?,
+                example_preference,
+                min_word_frequency_score: optional_number(min_word_frequency_score_ref)?,
+                // the empty option means that the JMdict frequency scores are used
+                frequency_list_id: match frequency_list_id.as_str() {
+                    "" => None,
+                    id => Some(id.parse().map_err(|e| {
+                        WebError::new(format!("Failed to parse frequency list id: {e}"))
+                    })?),
+                },
+                max_word_frequency_rank: optional_number(max_word_frequency_rank_ref)?,
+            };
             for SourceRefs {
                 source_id,
                 include_words,
@@ -650,7 +963,15 @@ pub fn Deck() -> impl IntoView {
                 }
             }
 
-            SendWrapper::new(client.update_deck(deck_id, &name, &included_sources)).await?;
+            SendWrapper::new(client.update_deck(
+                deck_id,
+                &name,
+                &included_sources,
+                exclude_known,
+                rules,
+                card_types,
+            ))
+            .await?;
             // todo deck_res.refetch();
             if let Some(handle) = update_result_message.get().1 {
                 handle.clear();
@@ -667,6 +988,39 @@ pub fn Deck() -> impl IntoView {
             WebResult::Ok(())
         }
     });
+    let preview_act = Action::new(move |&()| {
+        let client = get_client();
+        async move {
+            let preview = SendWrapper::new(client.get_deck_preview(deck_id)).await?;
+            WebResult::Ok(preview)
+        }
+    });
+    let note_types_act = Action::new(move |&()| {
+        let client = get_client();
+        async move {
+            let note_types = SendWrapper::new(client.get_note_types(deck_id)).await?;
+            WebResult::Ok(note_types)
+        }
+    });
+    let history_file_ref = NodeRef::<Input>::new();
+    let import_history_act = Action::new(move |&()| {
+        let file = history_file_ref
+            .get()
+            .expect("failed to get history_file_ref")
+            .files()
+            .and_then(|files| files.get(0));
+        let client = get_client();
+        SendWrapper::new(async move {
+            let file = file.ok_or_else(|| WebError::new("No file selected"))?;
+            let buffer = wasm_bindgen_futures::JsFuture::from(file.array_buffer()).await?;
+            let data = js_sys::Uint8Array::new(&buffer).to_vec();
+            let import = client.import_review_history(&data).await?;
+            WebResult::Ok(format!(
+                "Imported {} reviews for {} cards, {} words are now known",
+                import.reviews, import.cards, import.known_words
+            ))
+        })
+    });
     let delete_act = Action::new(move |&()| {
         let confirmed = leptos::prelude::window()
             .confirm_with_message("Are you sure you want to delete this deck?")
@@ -751,6 +1105,33 @@ pub fn Deck() -> impl IntoView {
             })
             .collect_view();
         set_source_checkbox_refs.set(refs);
+        let rules = deck.rules;
+        let frequency_list_id = rules.frequency_list_id;
+        let frequency_list_options = move || {
+            frequency_lists_res
+                .get()
+                .and_then(Result::ok)
+                .flatten()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|list| {
+                    view! {
+                        <option value=list.id selected=frequency_list_id == Some(list.id)>
+                            {list.name}
+                        </option>
+                    }
+                })
+                .collect_view()
+        };
+        let card_types = deck.card_types;
+        let rule_input = move |label: &'static str, value: Option<i32>, node_ref: NodeRef<Input>| {
+            view! {
+                <label class="label">
+                    {label}
+                    <input class="input" style="max-width: 16rem;" type="number" min=0 max=i32::MAX placeholder="Not used" value=value node_ref=node_ref/>
+                </label>
+            }
+        };
 
         view! {
             <h2 class="subtitle">{format!("Viewing deck {}", deck.name)}</h2>
@@ -758,6 +1139,65 @@ pub fn Deck() -> impl IntoView {
                 <a href=generate_deck_url download=filename class="button is-primary">
                     "Generate deck"
                 </a>
+                <a href=format!("/deck/{deck_id}/review") class="button">
+                    "Review"
+                </a>
+            </div>
+            <div class="block">
+                <h3 class="subtitle">"Preview"</h3>
+                <div class="block">
+                    "See which cards the deck would be generated with. Click a card to see how it looks in Anki."
+                </div>
+                <button class="button" on:click=move |_ev| { preview_act.dispatch(()); }>
+                    "Preview cards"
+                </button>
+                {move || preview_act.pending().get().then_some(view! { <div>"Loading preview..."</div> })}
+                {move || match preview_act.value().get() {
+                    Some(Ok(preview)) => view! { <PreviewCards preview /> }.into_any(),
+                    Some(Err(err)) => view! { <div class="has-text-danger">{err.to_string()}</div> }.into_any(),
+                    None => ().into_any(),
+                }}
+            </div>
+            <div class="block">
+                <h3 class="subtitle">"Card templates"</h3>
+                <div class="block">
+                    "Customise how the deck's notes look in Anki. "
+                    "Each template creates a card for every note, and the first template is used for reviews."
+                </div>
+                <button class="button" on:click=move |_ev| { note_types_act.dispatch(()); }>
+                    "Edit card templates"
+                </button>
+                {move || note_types_act.pending().get().then_some(view! { <div>"Loading note types..."</div> })}
+                {move || match note_types_act.value().get() {
+                    Some(Ok(note_types)) => view! {
+                        <h4 class="subtitle">"Word notes"</h4>
+                        <NoteTypeEditor deck_id kind=res::CardKind::Word note_type=note_types.word />
+                        <h4 class="subtitle">"Kanji notes"</h4>
+                        <NoteTypeEditor deck_id kind=res::CardKind::Kanji note_type=note_types.kanji />
+                    }.into_any(),
+                    Some(Err(err)) => view! { <div class="has-text-danger">{err.to_string()}</div> }.into_any(),
+                    None => ().into_any(),
+                }}
+            </div>
+            <div class="block">
+                <h3 class="subtitle">"Import review history"</h3>
+                <div class="block">
+                    "Import the reviews of the generated cards from an Anki package or collection file. "
+                    "Words with mature cards are marked as known."
+                </div>
+                <div class="file">
+                    <label class="file-label">
+                        <input class="file-input" type="file" accept=".apkg,.colpkg,.anki2,.anki21" node_ref=history_file_ref/>
+                        <span class="file-cta">
+                            <span class="file-label">"Choose a file..."</span>
+                        </span>
+                    </label>
+                </div>
+                <button class="button mt-1" on:click=move |_ev| { import_history_act.dispatch(()); }>
+                    "Import"
+                </button>
+                {move || import_history_act.pending().get().then_some(view! { <div>"Importing..."</div> })}
+                <ActionView action=import_history_act/>
             </div>
             <div class="block">
                 <h3 class="subtitle">"Edit deck"</h3>
@@ -774,6 +1214,90 @@ pub fn Deck() -> impl IntoView {
                             {sources_list}
                         </ul>
                     </div>
+                    <div class="field">
+                        <label class="checkbox">
+                            <input class="checkbox mr-1" type="checkbox" checked=deck.exclude_known node_ref=exclude_known_ref/>
+                            "Exclude known words"
+                        </label>
+                    </div>
+                    <label class="label">
+                        "Card order"
+                        <div class="select is-block">
+                            <select node_ref=order_ref>
+                                <option value="frequency" selected=rules.order == res::CardOrder::Frequency>
+                                    "Most common first"
+                                </option>
+                                <option value="appearance" selected=rules.order == res::CardOrder::Appearance>
+                                    "In order of appearance"
+                                </option>
+                                <option value="random" selected=rules.order == res::CardOrder::Random>
+                                    "Random"
+                                </option>
+                                <option value="common" selected=rules.order == res::CardOrder::Common>
+                                    "Most common in Japanese first"
+                                </option>
+                                <option value="grade" selected=rules.order == res::CardOrder::Grade>
+                                    "Kanji by school grade"
+                                </option>
+                            </select>
+                        </div>
+                    </label>
+                    {rule_input("Maximum word cards", rules.max_word_cards, max_word_cards_ref)}
+                    {rule_input("Maximum kanji cards", rules.max_kanji_cards, max_kanji_cards_ref)}
+                    {rule_input("Minimum word occurrences", rules.min_word_frequency, min_word_frequency_ref)}
+                    {rule_input("Maximum word occurrences", rules.max_word_frequency, max_word_frequency_ref)}
+                    {rule_input("Minimum word frequency score (0-100, 50 for common words)", rules.min_word_frequency_score, min_word_frequency_score_ref)}
+                    <label class="label">
+                        "Frequency list used for ordering"
+                        <div class="select is-block">
+                            <select node_ref=frequency_list_ref>
+                                <option value="" selected=frequency_list_id.is_none()>
+                                    "JMdict frequency scores"
+                                </option>
+                                {frequency_list_options}
+                            </select>
+                        </div>
+                    </label>
+                    {rule_input("Maximum word frequency rank", rules.max_word_frequency_rank, max_word_frequency_rank_ref)}
+                    {rule_input("Maximum kanji school grade (1-6, 8 for jouyou)", rules.max_kanji_grade, max_kanji_grade_ref)}
+                    {rule_input("Minimum kanji JLPT level (4 easiest, 1 hardest)", rules.min_kanji_jlpt, min_kanji_jlpt_ref)}
+                    <label class="label">
+                        "Extra example sentences on word cards"
+                        <input class="input" style="max-width: 16rem;" type="number" min=0 max=i32::MAX value=rules.extra_examples node_ref=extra_examples_ref/>
+                    </label>
+                    <label class="label">
+                        "Preferred extra examples"
+                        <div class="select is-block">
+                            <select node_ref=example_preference_ref>
+                                <option value="shortest" selected=rules.example_preference == res::ExamplePreference::Shortest>
+                                    "Shortest sentences"
+                                </option>
+                                <option value="known_words" selected=rules.example_preference == res::ExamplePreference::KnownWords>
+                                    "Sentences with the fewest unknown words"
+                                </option>
+                                <option value="source_diversity" selected=rules.example_preference == res::ExamplePreference::SourceDiversity>
+                                    "Sentences from different sources"
+                                </option>
+                            </select>
+                        </div>
+                    </label>
+                    <label class="label">"Extra word cards"</label>
+                    <div class="field">
+                        <label class="checkbox">
+                            <input class="checkbox mr-1" type="checkbox" checked=card_types.reading node_ref=reading_cards_ref/>
+                            "Reading (word → reading)"
+                        </label>
+                        <br/>
+                        <label class="checkbox">
+                            <input class="checkbox mr-1" type="checkbox" checked=card_types.production node_ref=production_cards_ref/>
+                            "Production (meaning and sentence → word)"
+                        </label>
+                        <br/>
+                        <label class="checkbox">
+                            <input class="checkbox mr-1" type="checkbox" checked=card_types.cloze node_ref=cloze_cards_ref/>
+                            "Cloze (sentence with the word hidden)"
+                        </label>
+                    </div>
                     <button class="button" type="submit" on:click=move |ev| {
                         ev.prevent_default();
                         update_act.dispatch(());
@@ -828,6 +1352,39 @@ pub fn Deck() -> impl IntoView {
     WebResult::Ok(view)
 }
 
+#[derive(Debug, Clone, PartialEq, Params)]
+pub struct DeckReviewParams {
+    deck_id: Option<i32>,
+}
+#[component]
+pub fn DeckReview() -> impl IntoView {
+    let DeckReviewParams { deck_id } = utils::params()?;
+    let deck_id = deck_id.expect("failed to get deck_id");
+    tracing::info!("Rendering DeckReview {deck_id}");
+
+    // resources
+    let cards_res = utils::logged_in_resource!(get_due_cards(deck_id));
+
+    // views
+    let cards_view = move |cards: Option<Vec<res::ReviewCard>>| match cards {
+        Some(cards) => view! {
+            <h2 class="subtitle">
+                <A href=format!("/deck/{deck_id}")>"Back to deck"</A>
+            </h2>
+            <ReviewSession cards />
+        }
+        .into_any(),
+        None => utils::loading_fallback("Loading cards...").into_any(),
+    };
+
+    let view = view! {
+        <LoginGuard require_login=true>
+            <ResourceView resource=cards_res view=cards_view />
+        </LoginGuard>
+    };
+    WebResult::Ok(view)
+}
+
 #[component]
 pub fn IgnoredWords() -> impl IntoView {
     tracing::info!("Rendering IgnoredWords");
@@ -917,6 +1474,83 @@ pub fn IgnoredWords() -> impl IntoView {
     }
 }
 
+#[component]
+pub fn Dictionaries() -> impl IntoView {
+    tracing::info!("Rendering Dictionaries");
+
+    let enabled = RwSignal::new(HashSet::<i32>::new());
+    let save_act = Action::new(move |&()| {
+        let dictionary_ids = enabled.get_untracked().into_iter().collect::<Vec<_>>();
+        let client = get_client();
+        async move {
+            SendWrapper::new(client.set_dictionaries(&dictionary_ids)).await?;
+            WebResult::Ok("Saved dictionaries!")
+        }
+    });
+
+    let dictionaries_res = utils::logged_in_resource!(get_dictionaries());
+    let dictionaries_content = move |dictionaries: Vec<res::Dictionary>| {
+        if dictionaries.is_empty() {
+            return view! { <div>"No dictionaries have been imported"</div> }.into_any();
+        }
+        enabled.set(
+            dictionaries
+                .iter()
+                .filter(|d| d.enabled)
+                .map(|d| d.id)
+                .collect(),
+        );
+        let dictionaries = dictionaries
+            .into_iter()
+            .map(|d| {
+                let id = d.id;
+                let toggle = move |ev| {
+                    let checked = event_target_checked(&ev);
+                    enabled.update(|enabled| {
+                        if checked {
+                            enabled.insert(id);
+                        } else {
+                            enabled.remove(&id);
+                        }
+                    });
+                };
+                view! {
+                    <div class="field">
+                        <label class="checkbox">
+                            <input class="checkbox mr-1" type="checkbox" checked=d.enabled on:change=toggle/>
+                            {d.name}
+                        </label>
+                    </div>
+                }
+            })
+            .collect_view();
+        view! {
+            <div class="block">
+                {dictionaries}
+            </div>
+            <button class="button is-primary" on:click=move |_ev| { save_act.dispatch(()); }>
+                "Save"
+            </button>
+            <ActionView action=save_act/>
+        }
+        .into_any()
+    };
+    let dictionaries_view = move |dictionaries: Option<Vec<res::Dictionary>>| match dictionaries {
+        Some(dictionaries) => dictionaries_content(dictionaries).into_any(),
+        None => view! { <div>"Loading..."</div> }.into_any(),
+    };
+
+    view! {
+        <LoginGuard require_login=true>
+            <h2 class="subtitle">"Dictionaries"</h2>
+            <div class="block">
+                "Choose the dictionaries whose definitions are shown in the analysis view and on word cards."
+            </div>
+            <ResourceView resource=dictionaries_res view=dictionaries_view/>
+        </LoginGuard>
+    }
+}
+
 #[component]
 pub fn Login() -> impl IntoView {
     tracing::info!("Rendering Login");
@@ -5,6 +5,7 @@ use crate::{
     error::{WebError, WebResult},
 };
 pub use crate::{logged_in_resource, untangle};
+use lbr_api::response as res;
 use leptos::{IntoView, prelude::*};
 use leptos_router::params::Params;
 use serde::{Serialize, de::DeserializeOwned};
@@ -44,6 +45,24 @@ pub fn errors_fallback(errors: ArcRwSignal<Errors>) -> impl IntoView {
     }
 }
 
+/// Formats the timestamps of a sentence from subtitles, e.g. `01:02–01:05`.
+pub fn format_timestamps(timestamps: res::Timestamps) -> String {
+    let format = |ms: i32| {
+        let seconds = ms / 1000;
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        if hours > 0 {
+            format!("{hours}:{minutes:02}:{seconds:02}")
+        } else {
+            format!("{minutes:02}:{seconds:02}")
+        }
+    };
+    format!(
+        "{}–{}",
+        format(timestamps.start_ms),
+        format(timestamps.end_ms)
+    )
+}
+
 #[macro_export]
 macro_rules! logged_in_resource {
     ($($f:tt)*) => {